@@ -0,0 +1,200 @@
+use crate::parallel;
+use crate::path::{distances, shortest_path};
+use crate::rng::Rng;
+use crate::solve::scatter;
+use crate::{Cells, Dimensions, Directions, DIRECTIONS};
+
+use std::fmt;
+
+// Minimum number of cells in a dead end branch for it to be considered long
+const LONG_DEAD_END_CELLS: usize = 10;
+
+/// Difficulty measures of solving a maze between two cells.
+pub(crate) struct Difficulty {
+    // number of cells in the solution
+    length: usize,
+    // number of cells along the solution offering more than one way forward
+    decisions: usize,
+    // number of branches off the solution leading to long dead ends
+    dead_ends: usize,
+    // composite rating from 0 (easy) to 100 (hard)
+    score: usize,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "difficulty {} (solution of {} cells, {} decision points, {} long dead ends)",
+            self.score, self.length, self.decisions, self.dead_ends
+        )
+    }
+}
+
+/// Open neighbours of cell.
+fn open_neighbours(
+    dimensions: Dimensions,
+    cells: &Cells,
+    cell: usize,
+) -> impl Iterator<Item = usize> + '_ {
+    DIRECTIONS
+        .iter()
+        .filter(move |direction| !cells.has_wall(cell, **direction))
+        .filter_map(move |direction| direction.neighbour(dimensions, cell))
+}
+
+/// Number of cells in the deepest path of a branch, not entering visited cells.
+fn branch_depth(
+    dimensions: Dimensions,
+    cells: &Cells,
+    visited: &mut [bool],
+    branch: usize,
+) -> usize {
+    let mut depth = 0;
+    let mut stack = vec![(branch, 1)];
+    visited[branch] = true;
+    while let Some((cell, cell_depth)) = stack.pop() {
+        depth = depth.max(cell_depth);
+        for neighbour in open_neighbours(dimensions, cells, cell) {
+            if !visited[neighbour] {
+                visited[neighbour] = true;
+                stack.push((neighbour, cell_depth + 1));
+            }
+        }
+    }
+    depth
+}
+
+/// Difficulty of solving a maze along path, the shortest between its first and last cells.
+///
+/// The score weights equally the solution length relative to the maze size, the proportion
+/// of the solution which requires a decision and the proportion of decisions which offer
+/// a long dead end.
+pub(crate) fn difficulty(dimensions: Dimensions, cells: &Cells, path: &[usize]) -> Difficulty {
+    let mut visited = vec![false; cells.len()];
+    for cell in path {
+        visited[*cell] = true;
+    }
+
+    let (mut decisions, mut dead_ends) = (0, 0);
+    for cell in &path[..path.len() - 1] {
+        let branches = open_neighbours(dimensions, cells, *cell)
+            .filter(|neighbour| !visited[*neighbour])
+            .collect::<Vec<_>>();
+        if !branches.is_empty() {
+            decisions += 1;
+        }
+        for branch in branches {
+            if branch_depth(dimensions, cells, &mut visited, branch) >= LONG_DEAD_END_CELLS {
+                dead_ends += 1;
+            }
+        }
+    }
+
+    let score = (path.len() as f64 / cells.len() as f64
+        + decisions as f64 / path.len() as f64
+        + (dead_ends as f64 / decisions.max(1) as f64).min(1.0))
+        / 3.0
+        * 100.0;
+
+    Difficulty {
+        length: path.len(),
+        decisions,
+        dead_ends,
+        score: score.round() as usize,
+    }
+}
+
+/// Longest of the shortest paths between any two cells, the spine of the maze, using two
+/// breadth first searches; the first finds the farthest cell from any cell, the second the
+/// farthest from that. Exact for a perfect maze, otherwise possibly shorter than the longest.
+/// Only the cells connected to the first cell are considered.
+pub(crate) fn longest_path(dimensions: Dimensions, cells: &Cells) -> Option<Vec<usize>> {
+    let farthest = |from| {
+        distances(dimensions, cells, from)
+            .iter()
+            .enumerate()
+            .filter_map(|(cell, distance)| distance.map(|distance| (distance, cell)))
+            .max()
+            .map(|(_, cell)| cell)
+    };
+    let from = farthest(0)?;
+    shortest_path(dimensions, cells, from, farthest(from)?)
+}
+
+/// Cells with three or more open passages, the junctions at which a decision is made.
+pub(crate) fn junctions(dimensions: Dimensions, cells: &Cells) -> Vec<usize> {
+    (0..cells.len())
+        .filter(|cell| open_neighbours(dimensions, cells, *cell).count() >= 3)
+        .collect()
+}
+
+/// Number of open passages from cell to cells not yet reached by a solver, so the choices
+/// of where to go next from it.
+pub(crate) fn choices(dimensions: Dimensions, cells: &Cells, cell: usize) -> usize {
+    open_neighbours(dimensions, cells, cell)
+        .filter(|neighbour| {
+            let solution = cells.solution(*neighbour);
+            solution.previous.is_none() && !solution.from
+        })
+        .count()
+}
+
+/// Approximate [betweenness](https://en.wikipedia.org/wiki/Betweenness_centrality) of each
+/// cell, from 0 to 1 for the cell most often passed through by shortest paths, so that
+/// bottlenecks stand out. Uses Brandes' algorithm from up to samples sources chosen with
+/// rng, rather than from every cell.
+pub(crate) fn betweenness(
+    dimensions: Dimensions,
+    cells: &Cells,
+    rng: &mut dyn Rng,
+    samples: usize,
+) -> Vec<f64> {
+    // the sources are divided into parts, each adding its dependencies on a thread
+    let sources = scatter(rng, cells.len(), samples, &[]);
+    let parts = parallel::parts(&sources, |sources| {
+        let mut betweenness = vec![0.0; cells.len()];
+        for &source in sources {
+            // cells reached in order of distance, with the number of shortest paths to each
+            let distances = distances(dimensions, cells, source);
+            let mut order = (0..cells.len())
+                .filter(|cell| distances[*cell].is_some())
+                .collect::<Vec<_>>();
+            order.sort_by_key(|cell| distances[*cell]);
+            let distances = &distances;
+            let previous = |cell: usize| {
+                open_neighbours(dimensions, cells, cell).filter(move |neighbour| {
+                    distances[*neighbour].map(|distance| distance + 1) == distances[cell]
+                })
+            };
+            let mut paths = vec![0.0; cells.len()];
+            paths[source] = 1.0;
+            for cell in &order[1..] {
+                paths[*cell] = previous(*cell).map(|neighbour| paths[neighbour]).sum();
+            }
+
+            // dependency of the source on each cell, accumulated from the farthest
+            let mut dependencies = vec![0.0; cells.len()];
+            for cell in order[1..].iter().rev() {
+                for neighbour in previous(*cell) {
+                    dependencies[neighbour] +=
+                        paths[neighbour] / paths[*cell] * (1.0 + dependencies[*cell]);
+                }
+                betweenness[*cell] += dependencies[*cell];
+            }
+        }
+        betweenness
+    });
+    let mut betweenness = vec![0.0; cells.len()];
+    for part in parts {
+        for (total, value) in betweenness.iter_mut().zip(part) {
+            *total += value;
+        }
+    }
+
+    let most = betweenness.iter().copied().fold(0.0, f64::max);
+    if most > 0.0 {
+        betweenness.iter_mut().for_each(|value| *value /= most);
+    }
+    betweenness
+}
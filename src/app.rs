@@ -0,0 +1,1698 @@
+mod controls;
+mod frame;
+mod generation;
+mod playback;
+mod playing;
+mod pointer;
+mod saving;
+mod sharing;
+mod solving;
+mod view;
+
+use controls::Controls;
+
+use crate::analysis::{betweenness, difficulty, junctions, longest_path};
+use crate::compare::Comparison;
+use crate::direction::Direction;
+#[cfg(feature = "app")]
+use crate::element;
+use crate::error::Error;
+use crate::format::binary::{from_base64, to_base64};
+use crate::format::png::Animation;
+use crate::format::poster::Tile;
+use crate::format::MazeData;
+use crate::generate::{Generator, Generators};
+use crate::geometry::row_and_col;
+use crate::library::Library;
+use crate::parameter::{Control, Parameter, Values};
+use crate::path::{shortest_path, solution, PathTree};
+use crate::platform::random;
+use crate::play::Player;
+use crate::qr::QrCode;
+use crate::recording::Timeline;
+use crate::registry::Registered;
+use crate::rng::{Platform, Rng};
+use crate::settings::Settings;
+use crate::solve::{Solver, Solvers, MAX_STEPS, ORDER};
+use crate::sound::Sonification;
+use crate::theme::{LineCap, LineJoin, Theme};
+use crate::video::VideoRecorder;
+use crate::{solve, ui};
+use crate::{CellSolution, Cells, Dimensions, CELLS_PER_WALL_BYTE, MINIMUM_DIMENSION};
+
+use js_sys::{Array, Date, Object, Uint8Array};
+use wasm_bindgen::prelude::*;
+use web_sys::{
+    Blob, BlobPropertyBag, CanvasRenderingContext2d, Document, Element, Event, HtmlAnchorElement,
+    HtmlButtonElement, HtmlCanvasElement, HtmlElement, HtmlImageElement, HtmlInputElement,
+    HtmlOptionElement, HtmlSelectElement, Url,
+};
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Range;
+use std::rc::Rc;
+
+// Default number of cells
+pub(crate) const DEFAULT_WIDTH: u32 = 20;
+pub(crate) const DEFAULT_HEIGHT: u32 = 20;
+
+// Default and maximum multiplier of the number of algorithm steps applied each frame
+pub(crate) const DEFAULT_SPEED: u32 = 1;
+pub(crate) const MAXIMUM_SPEED: u32 = 20;
+
+// Number of cells of a maze for each algorithm step applied each frame at the default speed,
+// so that larger mazes take a similar time to animate
+const CELLS_PER_FRAME_STEP: usize = 2_500;
+
+// Milliseconds of each frame within which algorithm steps are applied
+const FRAME_BUDGET_MILLISECONDS: f64 = 8.0;
+
+// Default number of cells above which generation must be confirmed
+pub(crate) const DEFAULT_CONFIRM_CELLS: usize = 250_000;
+
+// Default megabytes of memory above which generation is refused
+pub(crate) const DEFAULT_MEMORY_BUDGET: usize = 1_024;
+
+// Bytes in a megabyte
+const MEGABYTE: usize = 1 << 20;
+
+// Range of the number of cells in each dimension of a surprise maze
+const SURPRISE_DIMENSIONS: std::ops::RangeInclusive<usize> = 10..=40;
+
+// Number of recently generated mazes in the gallery, and pixels of the longest side of
+// their thumbnails
+const GALLERY_MAZES: usize = 8;
+const THUMBNAIL_PIXELS: f64 = 64.0;
+
+// Number of random cells from which shortest paths are sampled for the betweenness of cells
+const BETWEENNESS_SAMPLES: usize = 64;
+
+// Number of cells in each dimension, and generator, of the daily maze
+const DAILY_DIMENSION: usize = 25;
+const DAILY_GENERATOR: &str = "wilson";
+
+// Default number of passages swapped when shuffling a maze
+const DEFAULT_SHUFFLE_SWAPS: usize = 20;
+
+// Default luminance below which a pixel of an imported image is part of a wall
+const DEFAULT_IMAGE_THRESHOLD: u8 = 128;
+
+// File extension of downloaded mazes
+const MAZE_FILE_EXTENSION: &str = "maze";
+
+// Name of a downloaded video recording
+const VIDEO_FILENAME: &str = "maze.webm";
+
+// Name of a downloaded rendering for print
+const PRINT_FILENAME: &str = "maze-print.png";
+
+// Default and maximum multiple of the resolution on screen of a rendering for print
+pub(crate) const DEFAULT_PRINT_SCALE: u32 = 2;
+pub(crate) const MAXIMUM_PRINT_SCALE: u32 = 8;
+
+// Style and width of the crop marks of the tiles of a poster
+const CROP_MARK_STYLE: &str = "rgb(0,0,255)";
+const CROP_MARK_WIDTH: f64 = 0.5;
+
+// Largest width or height of a canvas, and most pixels of the canvas of a maze, within the
+// limits of browsers including those on mobile devices
+const MAXIMUM_CANVAS_PIXELS: f64 = 16_384.0;
+const MAXIMUM_CANVAS_AREA: f64 = 16_777_216.0;
+
+// Default number of steps per frame, and milliseconds per frame, of an exported animation
+const DEFAULT_ANIMATION_STEPS: usize = 10;
+const DEFAULT_ANIMATION_DELAY: u16 = 40;
+
+// Maximum number of frames of an exported animation; more steps per frame are used if needed
+const MAXIMUM_ANIMATION_FRAMES: usize = 500;
+
+// Mixed into the seed of a maze for the random numbers choosing its from and to cells, so
+// that they are drawn apart from those of the generator
+const LOCATIONS_SEED: u64 = 0xD1B5_4A32_D192_ED03;
+
+// Keys of the maze code, and of the fingerprint of its walls, in the URL fragment of a
+// permalink
+const PERMALINK_KEY: &str = "maze";
+const FINGERPRINT_KEY: &str = "fingerprint";
+
+// Number of pixels in each module dimension of a QR code, and of light modules around it
+const QR_MODULE_PIXELS: u32 = 4;
+const QR_QUIET_MODULES: u32 = 4;
+
+// Most characters of a note attached to a cell
+const MAXIMUM_NOTE_CHARACTERS: usize = 200;
+
+// Duration of the pause between demo cycles
+const DEMO_PAUSE_MILLISECONDS: f64 = 2000.0;
+
+// Number of pixels in each cell dimension
+pub(crate) const CELL_PIXELS: u32 = 20;
+
+// Fill and stroke styles
+const BACKGROUND_STYLE: &str = "rgb(255,255,255)";
+const CELL_BORDER_STYLE: &str = "rgb(0,0,0)";
+const FROM_TO_STYLE: &str = "rgb(255,0,0)";
+const SEARCH_STYLE: &str = "rgba(255,127,0,0.5)";
+// of the result at the start then at the goal, between which its colour fades
+const RESULT_RGBS: [[f64; 3]; 2] = [[0.0, 159.0, 0.0], [255.0, 0.0, 0.0]];
+// of the first then second players
+const PLAYER_STYLES: [&str; 2] = ["rgb(0,127,255)", "rgb(0,159,63)"];
+pub(crate) const HINT_STYLE: &str = "rgba(0,127,255,0.5)";
+pub(crate) const ITEM_STYLE: &str = "rgb(255,191,0)";
+pub(crate) const ENEMY_STYLE: &str = "rgb(127,0,127)";
+const SPINE_STYLE: &str = "rgba(0,159,159,0.6)";
+const JUNCTION_STYLE: &str = "rgba(191,0,191,0.6)";
+const OPTIMAL_STYLE: &str = "rgba(0,191,0,0.35)";
+const FRONTIER_STYLE: &str = "rgba(255,0,127,0.5)";
+const NOTE_STYLE: &str = "rgb(0,0,191)";
+const WAYPOINT_STYLE: &str = "rgb(63,63,63)";
+const PREVIEW_STYLE: &str = "rgba(0,127,255,0.25)";
+// of the legs of a solve through waypoints, in turn
+const LEG_STYLES: [&str; 4] = [
+    "rgb(0,159,0)",
+    "rgb(0,95,255)",
+    "rgb(223,127,0)",
+    "rgb(191,0,191)",
+];
+
+// Font of the numbers of the waypoints
+const WAYPOINT_FONT: &str = "bold 11px sans-serif";
+/// Drawing of a legend swatch in the style of a theme.
+type Swatch = fn(&CanvasRenderingContext2d, &Theme);
+
+// Swatches of the legend by label, each drawn in a cell as in the maze
+const LEGEND: [(&str, Swatch); 7] = [
+    ("walls", |context, theme| {
+        let pixels = CELL_PIXELS as f64;
+        context.set_line_width(theme.wall_width);
+        context.set_stroke_style_str(CELL_BORDER_STYLE);
+        context.begin_path();
+        context.move_to(pixels * 0.2, pixels * 0.2);
+        context.line_to(pixels * 0.2, pixels * 0.8);
+        context.line_to(pixels * 0.8, pixels * 0.8);
+        context.stroke();
+    }),
+    ("not carved", |context, _| {
+        context.set_fill_style_str(CELL_BORDER_STYLE);
+        context.fill_rect(0.0, 0.0, CELL_PIXELS as f64, CELL_PIXELS as f64);
+    }),
+    ("frontier", |context, _| {
+        context.set_fill_style_str(FRONTIER_STYLE);
+        context.fill_rect(0.0, 0.0, CELL_PIXELS as f64, CELL_PIXELS as f64);
+    }),
+    ("explored", |context, _| {
+        legend_line(context, SEARCH_LINE_WIDTH, SEARCH_STYLE, 0.0..1.0);
+    }),
+    ("result, start to goal", |context, _| {
+        for (rgb, span) in RESULT_RGBS.iter().zip([0.0..0.5, 0.5..1.0]) {
+            let style = format!("rgb({:.0},{:.0},{:.0})", rgb[0], rgb[1], rgb[2]);
+            legend_line(context, RESULT_LINE_WIDTH, &style, span);
+        }
+    }),
+    ("start", |context, _| {
+        let centre = CELL_PIXELS as f64 / 2.0;
+        context.set_fill_style_str(FROM_TO_STYLE);
+        context.begin_path();
+        let _ = context.arc(
+            centre,
+            centre,
+            CELL_PIXELS as f64 * 0.4,
+            0.0,
+            std::f64::consts::TAU,
+        );
+        context.fill();
+    }),
+    ("goal", |context, _| {
+        let centre = CELL_PIXELS as f64 / 2.0;
+        context.set_line_width(CELL_PIXELS as f64 * 0.1);
+        context.set_stroke_style_str(FROM_TO_STYLE);
+        context.begin_path();
+        let _ = context.arc(
+            centre,
+            centre,
+            CELL_PIXELS as f64 * 0.3,
+            0.0,
+            std::f64::consts::TAU,
+        );
+        context.stroke();
+    }),
+];
+
+// of the most between cells, less opaque for the less between
+const HEAT_RGB: &str = "255,63,0";
+const HEAT_OPACITY: f64 = 0.6;
+
+// Stroke widths; that of the walls is from the theme
+const SEARCH_LINE_WIDTH: f64 = 2.0;
+const RESULT_LINE_WIDTH: f64 = 4.0;
+pub(crate) const PLAYER_LINE_WIDTH: f64 = 4.0;
+const SPINE_LINE_WIDTH: f64 = 6.0;
+
+impl Cells {
+    /// Draw cell into canvas.
+    fn draw(
+        &self,
+        dimensions: Dimensions,
+        idx: usize,
+        context: &CanvasRenderingContext2d,
+        theme: &Theme,
+    ) {
+        // Drawing references:
+        // - https://developer.mozilla.org/en-US/docs/Web/API/Canvas_API/Tutorial/Drawing_shapes
+        // - https://developer.mozilla.org/en-US/docs/Web/API/Canvas_API/Tutorial/Applying_styles_and_colors
+
+        let (row, col) = row_and_col(dimensions, idx);
+        let (x, y) = (col * CELL_PIXELS as usize, row * CELL_PIXELS as usize);
+        let solution = self.solution(idx);
+        match self.walks[idx] {
+            Some(_) => {
+                theme.draw_backdrop(context, dimensions, row, col);
+
+                context.set_line_width(theme.wall_width);
+                context.set_stroke_style_str(CELL_BORDER_STYLE);
+                context.begin_path();
+                if self.has_wall(idx, Direction::North) {
+                    context.move_to(x as f64, y as f64);
+                    context.line_to((x + CELL_PIXELS as usize) as f64, y as f64);
+                }
+                if self.has_wall(idx, Direction::East) {
+                    context.move_to((x + CELL_PIXELS as usize) as f64, y as f64);
+                    context.line_to(
+                        (x + CELL_PIXELS as usize) as f64,
+                        (y + CELL_PIXELS as usize) as f64,
+                    );
+                }
+                if self.has_wall(idx, Direction::South) {
+                    context.move_to(
+                        (x + CELL_PIXELS as usize) as f64,
+                        (y + CELL_PIXELS as usize) as f64,
+                    );
+                    context.line_to(x as f64, (y + CELL_PIXELS as usize) as f64);
+                }
+                if self.has_wall(idx, Direction::West) {
+                    context.move_to(x as f64, (y + CELL_PIXELS as usize) as f64);
+                    context.line_to(x as f64, y as f64);
+                }
+                context.stroke();
+
+                if solution.from {
+                    context.set_fill_style_str(FROM_TO_STYLE);
+                    context.begin_path();
+                    let _ = context.arc(
+                        x as f64 + CELL_PIXELS as f64 / 2.0,
+                        y as f64 + CELL_PIXELS as f64 / 2.0,
+                        CELL_PIXELS as f64 * 0.4,
+                        0.0,
+                        std::f64::consts::TAU,
+                    );
+                    context.fill();
+                }
+
+                if solution.to {
+                    context.set_line_width(CELL_PIXELS as f64 * 0.1);
+                    context.set_stroke_style_str(FROM_TO_STYLE);
+                    context.begin_path();
+                    let _ = context.arc(
+                        x as f64 + CELL_PIXELS as f64 / 2.0,
+                        y as f64 + CELL_PIXELS as f64 / 2.0,
+                        CELL_PIXELS as f64 * 0.3,
+                        0.0,
+                        std::f64::consts::TAU,
+                    );
+                    context.stroke();
+                }
+
+                // the result is drawn in order afterwards, so only the search here
+                if let Some(previous) = solution.previous.filter(|_| !solution.result) {
+                    let (prev_row, prev_col) = row_and_col(dimensions, previous);
+                    let (prev_x, prev_y) = (
+                        prev_col * CELL_PIXELS as usize,
+                        prev_row * CELL_PIXELS as usize,
+                    );
+
+                    context.set_line_width(SEARCH_LINE_WIDTH);
+                    context.set_stroke_style_str(SEARCH_STYLE);
+                    context.begin_path();
+                    context.move_to(
+                        prev_x as f64 + CELL_PIXELS as f64 / 2.0,
+                        prev_y as f64 + CELL_PIXELS as f64 / 2.0,
+                    );
+                    context.line_to(
+                        x as f64 + CELL_PIXELS as f64 / 2.0,
+                        y as f64 + CELL_PIXELS as f64 / 2.0,
+                    );
+                    context.stroke();
+                }
+            }
+            None => {
+                context.set_fill_style_str(CELL_BORDER_STYLE);
+                context.fill_rect(x as f64, y as f64, CELL_PIXELS as f64, CELL_PIXELS as f64);
+            }
+        }
+    }
+}
+
+/// A type indicating the phase of the application.
+#[derive(Clone)]
+enum Phase {
+    Generate,
+    Solve,
+    Compare,
+    Play { race: bool },
+    Import(MazeData),
+    Complete,
+}
+
+/// Marker of the from or to cell, as dragged to another cell.
+#[derive(Clone, Copy, PartialEq)]
+enum Marker {
+    From,
+    To,
+}
+
+/// A type for the state of the maze of an instance of the application.
+struct App {
+    phase: RefCell<Phase>,
+    // time at which the current phase started; if None then not timed
+    phase_started: RefCell<Option<f64>>,
+    // seed of the maze, also choosing its from and to cells; if None then not known
+    seed: RefCell<Option<u64>>,
+    // leaderboard key of the maze, from its size, generator and seed; if None then the seed
+    // is not known
+    maze_key: RefCell<Option<String>>,
+    dimensions: RefCell<Dimensions>,
+    cells: RefCell<Cells>,
+    // from and to cell indexes
+    from: RefCell<usize>,
+    to: RefCell<usize>,
+    generator: RefCell<Box<dyn Generator>>,
+    solvers: RefCell<Solvers>,
+    // controls of the parameters of the solvers
+    solver_parameters: Rc<ParameterPanels>,
+    solver: RefCell<Box<dyn Solver>>,
+    // whether stepping is paused
+    paused: RefCell<bool>,
+    // recording of the generate and solve phases
+    timeline: RefCell<Timeline>,
+    // solver for comparison; if None then not comparing
+    comparison: RefCell<Option<Comparison>>,
+    // analyses shown over the maze
+    overlays: RefCell<Overlays>,
+    // shortest paths from the from cell, shared by the uses which need them
+    paths: RefCell<PathCache>,
+    // human players; empty if not playing, otherwise the first and, racing them, the second
+    players: RefCell<Vec<Player>>,
+    // video recording of the maze canvas; if None then not recording
+    recorder: RefCell<Option<VideoRecorder>>,
+    // style in which the maze is drawn
+    theme: RefCell<Theme>,
+    // sound of the solver; if None then not yet heard
+    sonification: RefCell<Option<Sonification>>,
+    // visualisation of each frame; if None then not yet created
+    frame: RefCell<Option<Closure<dyn FnMut()>>>,
+    // whether the next frame is requested
+    animating: RefCell<bool>,
+}
+
+impl App {
+    /// Request frames until there is nothing to animate, unless already requested.
+    fn animate(&self) {
+        if !self.animating.replace(true) {
+            if let Some(frame) = self.frame.borrow().as_ref() {
+                request_animation_frame(frame);
+            }
+        }
+    }
+
+    /// Shortest path through cells of dimensions from cell from to cell to. If precompute then
+    /// taken from the shortest paths from cell from to every cell, kept for later uses;
+    /// otherwise searched for alone. `None` if not connected.
+    fn shortest_path(
+        &self,
+        dimensions: Dimensions,
+        cells: &Cells,
+        from: usize,
+        to: usize,
+        precompute: bool,
+    ) -> Option<Vec<usize>> {
+        match precompute {
+            true => self
+                .paths
+                .borrow_mut()
+                .get(dimensions, cells, from)
+                .path(to),
+            false => shortest_path(dimensions, cells, from, to),
+        }
+    }
+}
+
+/// Draw cells into canvas in the style of theme; if culling then only those within the
+/// viewport of the window.
+fn draw(
+    context: &CanvasRenderingContext2d,
+    dimensions: Dimensions,
+    cells: &Cells,
+    theme: &Theme,
+    cull: bool,
+) {
+    let (rows, cols) = match context.canvas() {
+        Some(canvas) if cull => visible_cells(&canvas, dimensions),
+        _ => (0..dimensions.1, 0..dimensions.0),
+    };
+    let pixels = CELL_PIXELS as f64;
+    context.set_fill_style_str(BACKGROUND_STYLE);
+    context.fill_rect(
+        cols.start as f64 * pixels,
+        rows.start as f64 * pixels,
+        cols.len() as f64 * pixels,
+        rows.len() as f64 * pixels,
+    );
+    for row in rows {
+        for col in cols.clone() {
+            cells.draw(dimensions, row * dimensions.0 + col, context, theme);
+        }
+    }
+    draw_result(context, dimensions, cells);
+}
+
+/// Draw the result of a solver into canvas as one path, its colour fading from the start to
+/// the goal so that its direction is seen.
+fn draw_result(context: &CanvasRenderingContext2d, dimensions: Dimensions, cells: &Cells) {
+    let flagged = |flag: fn(&CellSolution) -> bool| {
+        cells
+            .solutions
+            .iter()
+            .find_map(|(cell, solution)| flag(solution).then_some(*cell))
+    };
+    let Some(path) = flagged(|solution| solution.from)
+        .zip(flagged(|solution| solution.to))
+        .and_then(|(from, to)| solution(cells, from, to))
+    else {
+        return;
+    };
+    let centre = |cell| {
+        let (row, col) = row_and_col(dimensions, cell);
+        (
+            (col as f64 + 0.5) * CELL_PIXELS as f64,
+            (row as f64 + 0.5) * CELL_PIXELS as f64,
+        )
+    };
+    let [start, end] = RESULT_RGBS;
+    let moves = path.len().saturating_sub(1).max(1) as f64;
+    context.set_line_width(RESULT_LINE_WIDTH);
+    for (idx, pair) in path.windows(2).enumerate() {
+        // each move is coloured at its middle
+        let fraction = (idx as f64 + 0.5) / moves;
+        let [red, green, blue] =
+            [0, 1, 2].map(|channel| start[channel] + (end[channel] - start[channel]) * fraction);
+        context.set_stroke_style_str(&format!("rgb({red:.0},{green:.0},{blue:.0})"));
+        let ((from_x, from_y), (to_x, to_y)) = (centre(pair[0]), centre(pair[1]));
+        context.begin_path();
+        context.move_to(from_x, from_y);
+        context.line_to(to_x, to_y);
+        context.stroke();
+    }
+}
+
+/// Draw players into canvas, each in their own style.
+fn draw_players(context: &CanvasRenderingContext2d, dimensions: Dimensions, players: &[Player]) {
+    for (player, style) in players.iter().zip(PLAYER_STYLES) {
+        player.draw(dimensions, context, style);
+    }
+}
+
+/// Draw the swatches of the legend in the style of theme.
+fn draw_legend(swatches: &[HtmlCanvasElement], theme: &Theme) -> Result<(), JsValue> {
+    for (swatch, (_, draw)) in swatches.iter().zip(LEGEND) {
+        // resizing clears the swatch and its style
+        swatch.set_width(CELL_PIXELS);
+        swatch.set_height(CELL_PIXELS);
+        let context = swatch
+            .get_context("2d")?
+            .ok_or("should have 2d context")?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+        theme.apply(&context);
+        draw(&context, theme);
+    }
+    Ok(())
+}
+
+/// Draw a line of a legend swatch across its middle, over the span of its width.
+fn legend_line(context: &CanvasRenderingContext2d, width: f64, style: &str, span: Range<f64>) {
+    let pixels = CELL_PIXELS as f64;
+    context.set_line_width(width);
+    context.set_stroke_style_str(style);
+    context.begin_path();
+    context.move_to(span.start * pixels, pixels / 2.0);
+    context.line_to(span.end * pixels, pixels / 2.0);
+    context.stroke();
+}
+
+/// Draw the frontier cells of a generator into canvas.
+fn draw_frontier(context: &CanvasRenderingContext2d, dimensions: Dimensions, frontier: &[usize]) {
+    let pixels = CELL_PIXELS as f64;
+    context.set_fill_style_str(FRONTIER_STYLE);
+    for cell in frontier {
+        let (row, col) = row_and_col(dimensions, *cell);
+        context.fill_rect(col as f64 * pixels, row as f64 * pixels, pixels, pixels);
+    }
+}
+
+/// Draw the maze of app again, with its overlays and players, and that of any comparison.
+fn redraw(
+    app: &App,
+    context: &CanvasRenderingContext2d,
+    context_comparison: &CanvasRenderingContext2d,
+) {
+    let dimensions = *app.dimensions.borrow();
+    let theme = app.theme.borrow();
+    draw(context, dimensions, &app.cells.borrow(), &theme, true);
+    app.overlays.borrow().draw(context, dimensions);
+    draw_players(context, dimensions, &app.players.borrow());
+    if let Some(comparison) = app.comparison.borrow().as_ref() {
+        draw(
+            context_comparison,
+            dimensions,
+            &comparison.cells,
+            &theme,
+            true,
+        );
+    }
+}
+
+/// A type for the shortest paths from a cell to every other, kept while the walls and the
+/// cell are unchanged so that each use does not search again. Edits, braiding and moving the
+/// from cell all change one or the other, so the paths are searched again when next used.
+#[derive(Default)]
+struct PathCache {
+    // dimensions and packed walls of the cells searched, with the paths found; if None then
+    // not searched
+    kept: Option<(Dimensions, Vec<u8>, PathTree)>,
+}
+
+impl PathCache {
+    /// Shortest paths through cells of dimensions from cell from, searched only if the walls
+    /// or from cell changed since last.
+    fn get(&mut self, dimensions: Dimensions, cells: &Cells, from: usize) -> &PathTree {
+        let kept = self.kept.take().filter(|(kept_dimensions, walls, paths)| {
+            *kept_dimensions == dimensions
+                && walls.as_slice() == cells.packed_walls()
+                && paths.from() == from
+        });
+        let (_, _, paths) = self.kept.insert(kept.unwrap_or_else(|| {
+            (
+                dimensions,
+                cells.packed_walls().to_vec(),
+                PathTree::new(dimensions, cells, from),
+            )
+        }));
+        paths
+    }
+}
+
+/// A type for the analyses of a maze shown over it, each empty if not shown.
+#[derive(Default)]
+struct Overlays {
+    // cells of the longest path
+    spine: Vec<usize>,
+    // cells with three or more open passages
+    junctions: Vec<usize>,
+    // betweenness of each cell, from 0 to 1
+    heat: Vec<f64>,
+    // cells of the shortest path, when a solver found a longer one
+    optimal: Vec<usize>,
+    // notes attached to cells by the user, by cell index
+    notes: BTreeMap<usize, String>,
+    // cells the solver visits in order on the way to the goal, placed by the user
+    waypoints: Vec<usize>,
+    // paths of the legs of a solve through waypoints, from one stop to the next
+    legs: Vec<Vec<usize>>,
+    // cells of the shortest path from the from cell to the cell hovered over
+    preview: Vec<usize>,
+}
+
+impl Overlays {
+    /// Clear the analyses of the last solve.
+    fn clear_solution(&mut self) {
+        self.optimal.clear();
+        self.legs.clear();
+    }
+
+    /// Preview the cells of a shortest path, or hide it if empty. Returns whether the preview
+    /// changed.
+    fn preview(&mut self, preview: Vec<usize>) -> bool {
+        if preview == self.preview {
+            return false;
+        }
+        self.preview = preview;
+        true
+    }
+
+    /// Draw into canvas, each analysis through the centres of its cells.
+    fn draw(&self, context: &CanvasRenderingContext2d, dimensions: Dimensions) {
+        let centre = |cell| {
+            let (row, col) = row_and_col(dimensions, cell);
+            (
+                (col as f64 + 0.5) * CELL_PIXELS as f64,
+                (row as f64 + 0.5) * CELL_PIXELS as f64,
+            )
+        };
+
+        let pixels = CELL_PIXELS as f64;
+        for (cell, heat) in self.heat.iter().enumerate() {
+            if *heat > 0.0 {
+                let (row, col) = row_and_col(dimensions, cell);
+                context.set_fill_style_str(&format!("rgba({HEAT_RGB},{:.3})", heat * HEAT_OPACITY));
+                context.fill_rect(col as f64 * pixels, row as f64 * pixels, pixels, pixels);
+            }
+        }
+
+        context.set_line_width(SPINE_LINE_WIDTH);
+        for (path, style) in [
+            (&self.preview, PREVIEW_STYLE),
+            (&self.optimal, OPTIMAL_STYLE),
+            (&self.spine, SPINE_STYLE),
+        ] {
+            context.set_stroke_style_str(style);
+            context.begin_path();
+            for (idx, cell) in path.iter().enumerate() {
+                let (x, y) = centre(*cell);
+                match idx {
+                    0 => context.move_to(x, y),
+                    _ => context.line_to(x, y),
+                }
+            }
+            context.stroke();
+        }
+
+        context.set_fill_style_str(JUNCTION_STYLE);
+        for cell in &self.junctions {
+            let (x, y) = centre(*cell);
+            context.begin_path();
+            let _ = context.arc(x, y, CELL_PIXELS as f64 * 0.25, 0.0, std::f64::consts::TAU);
+            context.fill();
+        }
+
+        context.set_fill_style_str(NOTE_STYLE);
+        for cell in self.notes.keys() {
+            let (x, y) = centre(*cell);
+            context.begin_path();
+            let _ = context.arc(x, y, CELL_PIXELS as f64 * 0.15, 0.0, std::f64::consts::TAU);
+            context.fill();
+        }
+        // each leg in its own colour over the result, then the waypoints numbered in order
+        context.set_line_width(RESULT_LINE_WIDTH);
+        for (leg, style) in self.legs.iter().zip(LEG_STYLES.iter().cycle()) {
+            context.set_stroke_style_str(style);
+            context.begin_path();
+            for (idx, cell) in leg.iter().enumerate() {
+                let (x, y) = centre(*cell);
+                match idx {
+                    0 => context.move_to(x, y),
+                    _ => context.line_to(x, y),
+                }
+            }
+            context.stroke();
+        }
+        context.set_font(WAYPOINT_FONT);
+        context.set_text_align("center");
+        context.set_text_baseline("middle");
+        for (idx, cell) in self.waypoints.iter().enumerate() {
+            let (x, y) = centre(*cell);
+            context.set_fill_style_str(WAYPOINT_STYLE);
+            context.fill_rect(
+                x - pixels * 0.4,
+                y - pixels * 0.4,
+                pixels * 0.8,
+                pixels * 0.8,
+            );
+            context.set_fill_style_str(BACKGROUND_STYLE);
+            let _ = context.fill_text(&(idx + 1).to_string(), x, y);
+        }
+    }
+
+    /// Show the longest path of the maze, or hide it if shown. Returns its description if
+    /// shown.
+    fn toggle_spine(&mut self, dimensions: Dimensions, cells: &Cells) -> Option<String> {
+        if !self.spine.is_empty() {
+            self.spine.clear();
+            return None;
+        }
+        self.spine = longest_path(dimensions, cells).unwrap_or_default();
+        let (from, to) = (self.spine.first()?, self.spine.last()?);
+        let ((from_row, from_col), (to_row, to_col)) =
+            (row_and_col(dimensions, *from), row_and_col(dimensions, *to));
+        Some(format!(
+            "Longest path of {} moves, from row {}, column {} to row {}, column {}",
+            self.spine.len() - 1,
+            from_row + 1,
+            from_col + 1,
+            to_row + 1,
+            to_col + 1
+        ))
+    }
+
+    /// Show the junctions of the maze, or hide them if shown. Returns their description if
+    /// shown.
+    fn toggle_junctions(&mut self, dimensions: Dimensions, cells: &Cells) -> Option<String> {
+        if !self.junctions.is_empty() {
+            self.junctions.clear();
+            return None;
+        }
+        self.junctions = junctions(dimensions, cells);
+        Some(format!("{} junctions shown", self.junctions.len()))
+    }
+
+    /// Show the betweenness of the cells of the maze as heat, or hide it if shown. Returns
+    /// its description if shown.
+    fn toggle_heat(&mut self, dimensions: Dimensions, cells: &Cells) -> Option<String> {
+        if !self.heat.is_empty() {
+            self.heat.clear();
+            return None;
+        }
+        self.heat = betweenness(dimensions, cells, &mut Platform, BETWEENNESS_SAMPLES);
+        let samples = BETWEENNESS_SAMPLES.min(cells.len());
+        Some(format!(
+            "Bottlenecks shown, from shortest paths sampled from {samples} cells"
+        ))
+    }
+}
+
+/// Statistics of the maze along path, the shortest between cells from and to; its
+/// difficulty, if they are connected, and number of junctions.
+fn statistics(dimensions: Dimensions, cells: &Cells, path: Option<&[usize]>) -> String {
+    let junctions = format!("{} junctions", junctions(dimensions, cells).len());
+    match path {
+        Some(path) => format!("{}; {junctions}", difficulty(dimensions, cells, path)),
+        None => junctions,
+    }
+}
+
+/// Rows and columns of the cells of canvas within the viewport of the window, allowing for
+/// the canvas being scaled to fit the page. Every cell if the canvas is not laid out.
+fn visible_cells(
+    canvas: &HtmlCanvasElement,
+    dimensions: Dimensions,
+) -> (Range<usize>, Range<usize>) {
+    let rect = canvas.get_bounding_client_rect();
+    let window = window();
+    let viewport = (
+        window.inner_width().ok().and_then(|width| width.as_f64()),
+        window
+            .inner_height()
+            .ok()
+            .and_then(|height| height.as_f64()),
+    );
+    let (Some(width), Some(height)) = viewport else {
+        return (0..dimensions.1, 0..dimensions.0);
+    };
+    if rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return (0..dimensions.1, 0..dimensions.0);
+    }
+    // cells between offsets on the page from the start of the canvas, in one dimension
+    let cells = |start: f64, end: f64, scale: f64, count: usize| {
+        let cell = |offset: f64| offset.max(0.0) * scale / CELL_PIXELS as f64;
+        (cell(start).floor() as usize).min(count)..(cell(end).ceil() as usize).min(count)
+    };
+    (
+        cells(
+            -rect.top(),
+            height - rect.top(),
+            (dimensions.1 as u32 * CELL_PIXELS) as f64 / rect.height(),
+            dimensions.1,
+        ),
+        cells(
+            -rect.left(),
+            width - rect.left(),
+            (dimensions.0 as u32 * CELL_PIXELS) as f64 / rect.width(),
+            dimensions.0,
+        ),
+    )
+}
+
+/// Estimated bytes of memory needed for a maze of dimensions; for the cells of the maze and
+/// a comparison, the recording of their steps, the tables of a solver, the shortest paths
+/// kept and the pixels of the canvases.
+fn memory_estimate(dimensions: Dimensions) -> usize {
+    const WORD: usize = size_of::<usize>();
+    let count = dimensions.0.saturating_mul(dimensions.1);
+    let bytes_per_cell =
+        // walk and an entry of the solutions, for the maze and a comparison
+        2 * (2 * WORD + 4 * WORD)
+        // a change of a cell and its states before and after, for each step of generating
+        // and solving
+        + 2 * 2 * 5 * WORD
+        // distance, visited flag and fringe entry of a solver
+        + 2 * WORD + 1 + 2 * WORD
+        // previous cell and distance of the shortest paths from the start
+        + 4 * WORD;
+    // walls, packed, of the maze and a comparison
+    let walls = 2 * count.div_ceil(CELLS_PER_WALL_BYTE);
+    // pixels of the maze and comparison canvases
+    let (width, height) = canvas_size(dimensions);
+    count
+        .saturating_mul(bytes_per_cell)
+        .saturating_add(walls)
+        .saturating_add(2 * 4 * width as usize * height as usize)
+}
+
+/// Scale of the canvas of a maze of dimensions from its cell pixels; 1 unless smaller to be
+/// within the limits of browsers.
+fn canvas_scale(dimensions: Dimensions) -> f64 {
+    let (width, height) = (
+        (dimensions.0 as u32 * CELL_PIXELS) as f64,
+        (dimensions.1 as u32 * CELL_PIXELS) as f64,
+    );
+    (MAXIMUM_CANVAS_PIXELS / width.max(height))
+        .min((MAXIMUM_CANVAS_AREA / (width * height)).sqrt())
+        .min(1.0)
+}
+
+/// Width and height in pixels of the canvas of a maze of dimensions, scaled as
+/// `canvas_scale`.
+fn canvas_size(dimensions: Dimensions) -> (u32, u32) {
+    let scale = canvas_scale(dimensions);
+    (
+        ((dimensions.0 as u32 * CELL_PIXELS) as f64 * scale).ceil() as u32,
+        ((dimensions.1 as u32 * CELL_PIXELS) as f64 * scale).ceil() as u32,
+    )
+}
+
+/// Size canvas for a maze of dimensions, as `canvas_size`, scaling its context so that the
+/// maze is drawn in cell pixels whatever the size.
+fn size_canvas(
+    canvas: &HtmlCanvasElement,
+    context: &CanvasRenderingContext2d,
+    dimensions: Dimensions,
+) -> Result<(), JsValue> {
+    let (width, height) = canvas_size(dimensions);
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let scale = canvas_scale(dimensions);
+    context.scale(scale, scale)
+}
+
+/// Parse dimension from input, clamped to the minimum. If the input is not used as given
+/// then also a message explaining why; if not a number then the previous dimension is used.
+fn parse_dimension(
+    input: &HtmlInputElement,
+    name: &str,
+    previous: usize,
+) -> (usize, Option<String>) {
+    match input.value().trim().parse::<f64>() {
+        Ok(value) if value.is_finite() => {
+            let dimension = value.round().max(MINIMUM_DIMENSION as f64) as usize;
+            match dimension as f64 == value {
+                true => (dimension, None),
+                false => (
+                    dimension,
+                    Some(format!("{name} of {value} is not valid; using {dimension}")),
+                ),
+            }
+        }
+        _ => (
+            previous,
+            Some(format!("{name} is not a number; using {previous}")),
+        ),
+    }
+}
+
+/// Enable timeline input positioned at the end.
+fn enable_timeline(input: &HtmlInputElement, len: usize) {
+    input.set_max(&len.to_string());
+    input.set_value(&len.to_string());
+    input.set_disabled(false);
+}
+
+/// Row and column of the cell at an offset in pixels on the canvas, allowing
+/// for the canvas being scaled to fit the page. `None` if outside the maze.
+fn canvas_row_and_col(
+    canvas: &HtmlCanvasElement,
+    dimensions: Dimensions,
+    x: i32,
+    y: i32,
+) -> Option<(usize, usize)> {
+    // cell pixels for each pixel on the page
+    let scale = match canvas.client_width() {
+        0 => 1.0 / canvas_scale(dimensions),
+        width => (dimensions.0 as u32 * CELL_PIXELS) as f64 / width as f64,
+    };
+    let (row, col) = (
+        (y.max(0) as f64 * scale) as usize / CELL_PIXELS as usize,
+        (x.max(0) as f64 * scale) as usize / CELL_PIXELS as usize,
+    );
+    (row < dimensions.1 && col < dimensions.0).then_some((row, col))
+}
+
+/// Replace the options of select with names.
+fn fill_options(
+    select: &HtmlSelectElement,
+    names: impl Iterator<Item = impl AsRef<str>>,
+) -> Result<(), JsValue> {
+    select.set_length(0);
+    for name in names {
+        let option = HtmlOptionElement::new_with_text_and_value(name.as_ref(), name.as_ref())?;
+        select.append_child(&option)?;
+    }
+    Ok(())
+}
+
+/// Replace the options of select with the algorithms of registry, each valued by its id and
+/// showing its display name.
+fn fill_algorithms<T: Registered>(
+    select: &HtmlSelectElement,
+    registry: &BTreeMap<String, T>,
+) -> Result<(), JsValue> {
+    select.set_length(0);
+    for (id, entry) in registry {
+        let option = HtmlOptionElement::new_with_text_and_value(entry.name(), id)?;
+        select.append_child(&option)?;
+    }
+    Ok(())
+}
+
+/// Replace the options of select with the names of the mazes in library.
+fn fill_library(select: &HtmlSelectElement, library: &Library) -> Result<(), JsValue> {
+    fill_options(select, library.names())
+}
+
+/// Select the option with value, or the first option if there is none.
+fn select_value(select: &HtmlSelectElement, value: &str) {
+    select.set_value(value);
+    if select.selected_index() < 0 {
+        select.set_selected_index(0);
+    }
+}
+
+/// Select a random option.
+fn select_random(select: &HtmlSelectElement) {
+    select.set_selected_index((random() * select.length() as f64) as i32);
+}
+
+/// Id of the generator with any values of its parameters which are not the defaults, so
+/// that mazes of the same seed and id are the same.
+fn parameterised(generators: &Generators, id: &str, values: &Values) -> String {
+    let parameters = generators.get(id).map_or(&[][..], |kind| kind.parameters());
+    match Values::of(parameters, values).describe(parameters) {
+        Some(description) => format!("{id} ({description})"),
+        None => id.to_string(),
+    }
+}
+
+/// A type for the controls of the parameters of each algorithm, shown while it is selected.
+struct ParameterPanels(BTreeMap<String, (HtmlElement, Vec<(Parameter, HtmlInputElement)>)>);
+
+impl ParameterPanels {
+    /// Create the controls of the parameters of algorithms, by id, in parent, set to the
+    /// values chosen or else the defaults. Ids are id followed by the index of the algorithm.
+    /// Text not valid for a control is replaced by the last valid.
+    fn new<'a>(
+        builder: &ui::Builder,
+        parent: &Element,
+        id: &str,
+        algorithms: impl Iterator<Item = (&'a String, &'static [Parameter])>,
+        chosen: &Values,
+    ) -> Result<Self, Error> {
+        let mut panels = BTreeMap::new();
+        for (index, (algorithm, parameters)) in algorithms.enumerate() {
+            let (container, inputs) = ui::parameters(
+                builder,
+                parent,
+                &format!("{id}-{index}"),
+                parameters,
+                chosen,
+            )?;
+            for (parameter, input) in parameters.iter().zip(&inputs) {
+                if parameter.control != Control::Order {
+                    continue;
+                }
+                let input_clone = input.clone();
+                let parameter = *parameter;
+                let mut valid = input.value();
+                let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+                    match parameter.parse(&input_clone.value()) {
+                        Some(value) => valid = parameter.format(value),
+                        None => input_clone.set_value(&valid),
+                    }
+                });
+                input
+                    .add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+                closure.forget();
+            }
+            let inputs = parameters.iter().copied().zip(inputs).collect();
+            panels.insert(algorithm.clone(), (container, inputs));
+        }
+        Ok(Self(panels))
+    }
+
+    /// Show the controls of the algorithm with id only.
+    fn show(&self, id: &str) {
+        for (algorithm, (container, inputs)) in &self.0 {
+            container.set_hidden(algorithm != id || inputs.is_empty());
+        }
+    }
+
+    /// Values chosen with the controls of the algorithm with id; those not valid are left to
+    /// the defaults.
+    fn values(&self, id: &str) -> Values {
+        let mut values = Values::default();
+        for (parameter, input) in self.0.get(id).into_iter().flat_map(|(_, inputs)| inputs) {
+            let value = match parameter.control {
+                Control::Checkbox => Some(f64::from(u8::from(input.checked()))),
+                _ => parameter.parse(&input.value()),
+            };
+            if let Some(value) = value {
+                values.set(parameter.key, value);
+            }
+        }
+        values
+    }
+}
+
+/// Generator of id from generators, using rng and the values chosen for its parameters.
+fn create_generator(
+    generators: &Generators,
+    id: &str,
+    rng: Box<dyn Rng>,
+    values: &Values,
+) -> Result<Box<dyn Generator>, Error> {
+    let create = generators
+        .get(id)
+        .ok_or_else(|| format!("unknown generator \"{id}\""))?;
+    Ok(create.create(rng, values))
+}
+
+/// Theme chosen by the controls; previous for any control not valid.
+fn theme(
+    input_wall_width: &HtmlInputElement,
+    input_passage_width: &HtmlInputElement,
+    select_line_cap: &HtmlSelectElement,
+    select_line_join: &HtmlSelectElement,
+    previous: &Theme,
+) -> Theme {
+    let mut theme = previous.clone();
+    if let Ok(width) = input_wall_width.value().parse() {
+        theme.set_wall_width(width);
+    }
+    theme.passage_width = input_passage_width
+        .value()
+        .parse()
+        .unwrap_or(previous.passage_width);
+    theme.line_cap = LineCap::parse(&select_line_cap.value()).unwrap_or(previous.line_cap);
+    theme.line_join = LineJoin::parse(&select_line_join.value()).unwrap_or(previous.line_join);
+    theme
+}
+
+/// Moves found by a solver compared with the optimal number, for a solution longer than
+/// optimal.
+fn optimality(found: usize, optimal: usize) -> String {
+    format!(
+        "found {found} moves vs optimal {optimal} ({:.1}×)",
+        found as f64 / optimal.max(1) as f64
+    )
+}
+
+/// Solver of id from solvers, with the values chosen for its parameters in panels.
+fn create_solver(
+    solvers: &Solvers,
+    id: &str,
+    panels: &ParameterPanels,
+) -> Result<Box<dyn Solver>, Error> {
+    let create = solvers
+        .get(id)
+        .ok_or_else(|| format!("unknown solver \"{id}\""))?;
+    Ok(create.create(Box::new(Platform), &panels.values(id)))
+}
+
+/// Behaviour calling f, reporting any error on banner.
+fn reporting<T>(
+    banner: &HtmlElement,
+    mut f: impl FnMut(T) -> Result<(), Error> + 'static,
+) -> impl FnMut(T) + 'static {
+    let banner = banner.clone();
+    move |value| {
+        if let Err(error) = f(value) {
+            error.report(&banner);
+        }
+    }
+}
+
+/// Milliseconds since the page was loaded, or since the epoch if the window has no
+/// performance timer.
+fn now() -> f64 {
+    window()
+        .performance()
+        .map_or_else(Date::now, |performance| performance.now())
+}
+
+/// Description of the time elapsed since a phase started and its rate of steps.
+fn elapsed(verb: &str, started: f64, steps: usize) -> String {
+    let milliseconds = now() - started;
+    format!(
+        "{verb} in {milliseconds:.0} ms ({:.0} steps per second)",
+        steps as f64 * 1000.0 / milliseconds.max(1.0)
+    )
+}
+
+/// Link to the page opening the maze, encoded with its fingerprint in the URL fragment.
+fn permalink(data: &MazeData) -> String {
+    let href = window().location().href().unwrap_or_default();
+    let page = href.split_once('#').map_or(href.as_str(), |(page, _)| page);
+    format!(
+        "{page}#{PERMALINK_KEY}={}&{FINGERPRINT_KEY}={}",
+        to_base64(data),
+        data.fingerprint()
+    )
+}
+
+/// Show the mazes of gallery, most recent first, as thumbnails; hiding the remaining.
+fn fill_gallery(
+    thumbnails: &[(HtmlButtonElement, HtmlCanvasElement)],
+    gallery: &VecDeque<MazeData>,
+) -> Result<(), JsValue> {
+    for (idx, (button, canvas)) in thumbnails.iter().enumerate() {
+        match gallery.get(idx) {
+            Some(data) => {
+                draw_thumbnail(canvas, data)?;
+                button.set_hidden(false);
+            }
+            None => button.set_hidden(true),
+        }
+    }
+    Ok(())
+}
+
+/// Draw the maze of data into canvas, scaled so that its longest side fits a thumbnail.
+fn draw_thumbnail(canvas: &HtmlCanvasElement, data: &MazeData) -> Result<(), JsValue> {
+    let (width, height) = data.dimensions;
+    let scale = THUMBNAIL_PIXELS / (width.max(height) as u32 * CELL_PIXELS) as f64;
+    canvas.set_width(((width as u32 * CELL_PIXELS) as f64 * scale).ceil() as u32);
+    canvas.set_height(((height as u32 * CELL_PIXELS) as f64 * scale).ceil() as u32);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or("should have 2d context")?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+    context.scale(scale, scale)?;
+    let theme = Theme::default();
+    theme.apply(&context);
+    draw(&context, data.dimensions, &data.cells(), &theme, false);
+    Ok(())
+}
+
+/// Draw a QR code into canvas, resized to fit it and its quiet zone.
+fn draw_qr_code(canvas: &HtmlCanvasElement, code: &QrCode) -> Result<(), JsValue> {
+    let modules = code.size() as u32 + 2 * QR_QUIET_MODULES;
+    canvas.set_width(modules * QR_MODULE_PIXELS);
+    canvas.set_height(modules * QR_MODULE_PIXELS);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or("should have 2d context")?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+    context.set_fill_style_str("white");
+    context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+    context.set_fill_style_str("black");
+    for y in 0..code.size() {
+        for x in (0..code.size()).filter(|x| code.is_dark(*x, y)) {
+            context.fill_rect(
+                ((x as u32 + QR_QUIET_MODULES) * QR_MODULE_PIXELS) as f64,
+                ((y as u32 + QR_QUIET_MODULES) * QR_MODULE_PIXELS) as f64,
+                QR_MODULE_PIXELS as f64,
+                QR_MODULE_PIXELS as f64,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Today's date in UTC, so the same for everyone, and the seed of its daily maze.
+fn daily() -> (String, u64) {
+    let date = Date::new_0();
+    let (year, month, day) = (
+        date.get_utc_full_year(),
+        date.get_utc_month() + 1,
+        date.get_utc_date(),
+    );
+    (
+        format!("{year:04}-{month:02}-{day:02}"),
+        u64::from(year) * 10_000 + u64::from(month) * 100 + u64::from(day),
+    )
+}
+
+/// Maze encoded in the URL fragment of the page, if any, checked against the fingerprint
+/// alongside it if given.
+fn permalink_maze() -> Option<Result<MazeData, String>> {
+    let hash = window().location().hash().ok()?;
+    let parameters = hash
+        .trim_start_matches('#')
+        .split('&')
+        .filter_map(|parameter| parameter.split_once('='))
+        .collect::<BTreeMap<_, _>>();
+    let data = from_base64(parameters.get(PERMALINK_KEY)?);
+    Some(data.and_then(|data| match parameters.get(FINGERPRINT_KEY) {
+        Some(fingerprint) if *fingerprint != data.fingerprint() => {
+            Err(format!("the maze does not match fingerprint {fingerprint}"))
+        }
+        _ => Ok(data),
+    }))
+}
+
+/// Settings remembered in local storage, overridden by the query parameters of the page.
+///
+/// Parameters are the names of the settings, or `w`, `h` and `gen` for the width, height
+/// and generator, and `autoplay` for demo mode. Algorithms may be given by their ids, or by
+/// the start of their names ignoring case, spaces and punctuation, with `*` as "star".
+pub(crate) fn initial_settings() -> Settings {
+    let mut settings = Settings::load();
+    for (name, value) in query_parameters() {
+        let name = name.as_str();
+        match name {
+            "w" => settings.set("width", &value),
+            "h" => settings.set("height", &value),
+            "gen" => settings.set("generator", &value),
+            "autoplay" => settings.demo = value != "0" && value != "false",
+            name => settings.set(name, &value),
+        }
+    }
+    settings
+}
+
+/// Decoded names and values of the query parameters of the page.
+pub(crate) fn query_parameters() -> Vec<(String, String)> {
+    let decode = |text: &str| {
+        js_sys::decode_uri_component(&text.replace('+', " "))
+            .map(String::from)
+            .unwrap_or_default()
+    };
+    let search = window().location().search().unwrap_or_default();
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|parameter| !parameter.is_empty())
+        .map(|parameter| {
+            let (name, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+/// RGBA pixels and size of a loaded image.
+fn image_pixels(image: &HtmlImageElement) -> Result<(Vec<u8>, Dimensions), String> {
+    let (width, height) = (image.natural_width(), image.natural_height());
+    let context = window()
+        .document()
+        .ok_or("no document")?
+        .create_element("canvas")
+        .ok()
+        .and_then(|canvas| canvas.dyn_into::<HtmlCanvasElement>().ok())
+        .and_then(|canvas| {
+            canvas.set_width(width);
+            canvas.set_height(height);
+            canvas.get_context("2d").ok().flatten()
+        })
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or("could not draw image")?;
+    context
+        .draw_image_with_html_image_element(image, 0.0, 0.0)
+        .map_err(|_| "could not draw image")?;
+    let data = context
+        .get_image_data(0.0, 0.0, width as f64, height as f64)
+        .map_err(|_| "could not read image pixels")?;
+    Ok((data.data().to_vec(), (width as usize, height as usize)))
+}
+
+/// Offer text for download as a file.
+fn download(filename: &str, text: &str, mime: &str) -> Result<(), JsValue> {
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    download_blob(
+        filename,
+        &Blob::new_with_str_sequence_and_options(&Array::of1(&text.into()), &options)?,
+    )
+}
+
+/// Offer bytes for download as a file.
+fn download_bytes(filename: &str, bytes: &[u8], mime: &str) -> Result<(), JsValue> {
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    download_blob(
+        filename,
+        &Blob::new_with_u8_array_sequence_and_options(
+            &Array::of1(&Uint8Array::from(bytes)),
+            &options,
+        )?,
+    )
+}
+
+/// Offer blob for download as a file.
+fn download_blob(filename: &str, blob: &Blob) -> Result<(), JsValue> {
+    let url = Url::create_object_url_with_blob(blob)?;
+    download_url(filename, &url)?;
+    Url::revoke_object_url(&url)
+}
+
+/// Offer the resource at url for download as a file.
+fn download_url(filename: &str, url: &str) -> Result<(), JsValue> {
+    let anchor = window()
+        .document()
+        .ok_or_else(|| JsValue::from("no document"))?
+        .create_element("a")?
+        .dyn_into::<HtmlAnchorElement>()?;
+    anchor.set_href(url);
+    anchor.set_download(filename);
+    anchor.click();
+    Ok(())
+}
+
+/// Render the maze of data into a new canvas in the style of theme, at scale times the
+/// resolution on screen and with a margin so that the walls on the boundary are whole.
+/// The scale is reduced if needed to keep within the largest canvas. Returns the canvas
+/// and the scale used.
+fn render(data: &MazeData, theme: &Theme, scale: f64) -> Result<(HtmlCanvasElement, f64), JsValue> {
+    let margin = CELL_PIXELS as f64 / 2.0;
+    let area = (
+        -margin,
+        -margin,
+        data.dimensions.0 as f64 * CELL_PIXELS as f64 + 2.0 * margin,
+        data.dimensions.1 as f64 * CELL_PIXELS as f64 + 2.0 * margin,
+    );
+    render_area(data, theme, scale, area).map(|(canvas, _, scale)| (canvas, scale))
+}
+
+/// Render the part of the maze of data in tile as `render`, with crop marks at the corners
+/// of the cells within the tile, drawn out into the cells it overlaps.
+fn render_tile(
+    data: &MazeData,
+    theme: &Theme,
+    scale: f64,
+    tile: &Tile,
+) -> Result<HtmlCanvasElement, JsValue> {
+    let cell = CELL_PIXELS as f64;
+    let overlap = tile.overlap as f64 * cell;
+    let (left, top) = (tile.origin.0 as f64 * cell, tile.origin.1 as f64 * cell);
+    let (right, bottom) = (
+        left + tile.cells.0 as f64 * cell,
+        top + tile.cells.1 as f64 * cell,
+    );
+    // the rendered area, with a margin of a wall width for the walls on the boundary
+    let margin = overlap + theme.wall_width;
+    let area = (
+        left - margin,
+        top - margin,
+        right - left + 2.0 * margin,
+        bottom - top + 2.0 * margin,
+    );
+    let (canvas, context, _) = render_area(data, theme, scale, area)?;
+
+    let length = overlap.max(cell / 2.0);
+    context.begin_path();
+    for (x, dx) in [(left, -length), (right, length)] {
+        for (y, dy) in [(top, -length), (bottom, length)] {
+            context.move_to(x, y);
+            context.line_to(x + dx, y);
+            context.move_to(x, y);
+            context.line_to(x, y + dy);
+        }
+    }
+    context.set_stroke_style_str(CROP_MARK_STYLE);
+    context.set_line_width(CROP_MARK_WIDTH);
+    context.stroke();
+    Ok(canvas)
+}
+
+/// Render the area of the maze of data, as its left, top, width and height in pixels on
+/// screen, into a new canvas as `render`. Returns the canvas, its context drawn on in
+/// pixels on screen, and the scale used.
+fn render_area(
+    data: &MazeData,
+    theme: &Theme,
+    scale: f64,
+    (left, top, width, height): (f64, f64, f64, f64),
+) -> Result<(HtmlCanvasElement, CanvasRenderingContext2d, f64), JsValue> {
+    let scale = scale.min(MAXIMUM_CANVAS_PIXELS / width.max(height));
+    let canvas = window()
+        .document()
+        .ok_or_else(|| JsValue::from("no document"))?
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width((width * scale).ceil() as u32);
+    canvas.set_height((height * scale).ceil() as u32);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or("should have 2d context")?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+    context.set_fill_style_str(BACKGROUND_STYLE);
+    context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+    context.scale(scale, scale)?;
+    context.translate(-left, -top)?;
+    theme.apply(&context);
+    draw(&context, data.dimensions, &data.cells(), theme, false);
+    Ok((canvas, context, scale))
+}
+
+/// Animated PNG replaying the recorded steps of app, with a frame every number of steps
+/// shown for milliseconds, and its number of frames. `None` if nothing is recorded.
+fn animation(app: &App, steps: usize, delay: u16) -> Option<(Vec<u8>, usize)> {
+    let mut timeline = app.timeline.borrow_mut();
+    if timeline.len() == 0 {
+        return None;
+    }
+    let steps = steps.max(timeline.len().div_ceil(MAXIMUM_ANIMATION_FRAMES));
+
+    // replay a copy of the cells, leaving the timeline where it was
+    let mut cells = app.cells.borrow().clone();
+    let position = timeline.position();
+    let mut animation = Animation::new(*app.dimensions.borrow(), delay);
+    for step in (0..timeline.len()).step_by(steps).chain([timeline.len()]) {
+        timeline.seek(&mut cells, step);
+        animation.frame(&cells);
+    }
+    timeline.seek(&mut cells, position);
+    Some((animation.to_apng(), animation.len()))
+}
+
+/// Stop the video recording of app, if any, offering the video by link once it is complete.
+fn stop_recording(
+    app: &App,
+    button_record: &HtmlButtonElement,
+    button_stop: &HtmlButtonElement,
+    message: &Element,
+    link: &HtmlAnchorElement,
+) {
+    let Some(recorder) = app.recorder.borrow_mut().take() else {
+        return;
+    };
+    button_stop.set_disabled(true);
+    message.set_text_content(Some("finishing the video"));
+    let (button_record, message, link) = (button_record.clone(), message.clone(), link.clone());
+    recorder.stop(move |url| {
+        button_record.set_disabled(false);
+        match url {
+            Ok(url) => {
+                // release the previous video, if any
+                if !link.href().is_empty() {
+                    let _ = Url::revoke_object_url(&link.href());
+                }
+                link.set_href(&url);
+                link.set_download(VIDEO_FILENAME);
+                link.set_hidden(false);
+                message.set_text_content(Some("video recorded"));
+            }
+            Err(_) => message.set_text_content(Some("could not record the video")),
+        }
+    });
+}
+
+/// Copy text to the clipboard, reporting the outcome in message.
+fn copy_to_clipboard(text: &str, message: &Element) {
+    let copied = {
+        let message = message.clone();
+        Closure::<dyn FnMut(_)>::new(move |_: JsValue| {
+            message.set_text_content(Some("copied to the clipboard"));
+        })
+    };
+    let failed = {
+        let message = message.clone();
+        Closure::<dyn FnMut(_)>::new(move |_: JsValue| {
+            message.set_text_content(Some("could not copy to the clipboard"));
+        })
+    };
+    let _ = window()
+        .navigator()
+        .clipboard()
+        .write_text(text)
+        .then2(&copied, &failed);
+    copied.forget();
+    failed.forget();
+}
+
+pub(crate) fn window() -> web_sys::Window {
+    web_sys::window().expect("should have window")
+}
+
+fn request_animation_frame(f: &Closure<dyn FnMut()>) {
+    window()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register request animation frame callback");
+}
+
+/// Entry point of the application, defining the `<wasm-maze>` custom element and building
+/// the user interface into the document body unless the document has any such elements.
+/// Only with the `app` feature; otherwise the user interface is built by calling `init`.
+#[cfg(feature = "app")]
+#[wasm_bindgen(start)]
+pub fn main() -> Result<(), JsValue> {
+    let document = window().document().ok_or("no document")?;
+    element::define();
+    if document.query_selector(element::TAG)?.is_none() {
+        let body = document.body().ok_or("should have document body")?;
+        build(&document, &body, initial_settings())?;
+    }
+    Ok(())
+}
+
+/// Build an instance of the user interface into container.
+///
+/// The properties of options override the remembered settings and query parameters;
+/// `width`, `height`, `generator`, `solver`, `comparison`, `order`, `max-steps`,
+/// `pause-at-decisions`, `confirm-cells`, `memory-budget`, `precompute-paths`, `new-locations`,
+/// `auto-solve`, `race`, `slide`, `items`, `enemies`, `two-players`, `cell-details`, `legend`,
+/// `diagnostics`, `sound`, `wall-width`,
+/// `passage-width`, `line-cap`, `line-join`, `print-scale` and `demo`, or `autoplay` for demo
+/// mode.
+#[wasm_bindgen]
+pub fn init(container: HtmlElement, options: JsValue) -> Result<(), JsValue> {
+    let mut settings = initial_settings();
+    if let Some(options) = options.dyn_ref::<Object>() {
+        for entry in Object::entries(options) {
+            let entry = entry.unchecked_into::<Array>();
+            let (name, value) = (entry.get(0).as_string().unwrap_or_default(), entry.get(1));
+            let value = value
+                .as_string()
+                .or_else(|| value.as_f64().map(|value| value.to_string()))
+                .or_else(|| value.as_bool().map(|value| value.to_string()))
+                .unwrap_or_default();
+            match name.as_str() {
+                "autoplay" => settings.set("demo", &value),
+                name => settings.set(name, &value),
+            }
+        }
+    }
+    build(
+        &window().document().ok_or("no document")?,
+        &container,
+        settings,
+    )
+}
+
+/// Build an instance of the user interface into container, initially with settings.
+pub(crate) fn build(
+    document: &Document,
+    container: &Element,
+    settings: Settings,
+) -> Result<(), JsValue> {
+    ui::inject_styles(document)?;
+
+    // initial maze dimensions, unless so large that generation would need confirming or
+    // be refused
+    let initial_dimensions = match settings.width.saturating_mul(settings.height) {
+        cells
+            if cells > settings.confirm_cells
+                || memory_estimate((settings.width, settings.height))
+                    > settings.memory_budget.saturating_mul(MEGABYTE) =>
+        {
+            (DEFAULT_WIDTH as usize, DEFAULT_HEIGHT as usize)
+        }
+        _ => (settings.width, settings.height),
+    };
+
+    let controls = Rc::new(Controls::new(
+        document,
+        container,
+        &settings,
+        initial_dimensions,
+    )?);
+    let id = controls.select_generator.borrow().value();
+    let generator = create_generator(
+        &controls.generators,
+        &id,
+        Box::new(Platform),
+        &controls.generator_parameters.values(&id),
+    )?;
+
+    // setup solvers; the remembered direction order and step limit are the initial values of
+    // those parameters
+    let solvers = solve::solvers();
+    let mut chosen = Values::default();
+    chosen.set(ORDER, Direction::order_index(settings.order) as f64);
+    chosen.set(MAX_STEPS, settings.max_steps as f64);
+    for select in [
+        &*controls.select_solver.borrow(),
+        &controls.select_comparison,
+    ] {
+        fill_algorithms(select, &solvers)?;
+    }
+    select_value(&controls.select_solver.borrow(), &settings.solver);
+    select_value(&controls.select_comparison, &settings.comparison);
+    let solver_parameters = ParameterPanels::new(
+        &controls.builder,
+        &controls.div_solver_parameters,
+        "solver-parameter",
+        solvers.iter().map(|(id, kind)| (id, kind.parameters())),
+        &chosen,
+    )?;
+    let id = controls.select_solver.borrow().value();
+    solver_parameters.show(&id);
+    let solver = create_solver(&solvers, &id, &solver_parameters)?;
+    let solver_parameters = Rc::new(solver_parameters);
+
+    // state of the maze, shared by the behaviours of this instance
+    let app = Rc::new(App {
+        phase: RefCell::new(match permalink_maze() {
+            Some(Ok(data)) => Phase::Import(data),
+            Some(Err(message)) => {
+                controls
+                    .div_share
+                    .set_text_content(Some(&format!("could not open link: {message}")));
+                Phase::Generate
+            }
+            None => Phase::Generate,
+        }),
+        phase_started: RefCell::new(Some(now())),
+        seed: RefCell::new(None),
+        maze_key: RefCell::new(None),
+        dimensions: RefCell::new(initial_dimensions),
+        cells: RefCell::new(Cells::new(initial_dimensions)),
+        from: RefCell::new(0),
+        to: RefCell::new(0),
+        generator: RefCell::new(generator),
+        solvers: RefCell::new(solvers),
+        solver_parameters,
+        solver: RefCell::new(solver),
+        paused: RefCell::new(false),
+        timeline: RefCell::new(Timeline::default()),
+        comparison: RefCell::new(None),
+        overlays: RefCell::new(Overlays::default()),
+        paths: RefCell::new(PathCache::default()),
+        players: RefCell::new(Vec::new()),
+        recorder: RefCell::new(None),
+        theme: RefCell::new(settings.theme.clone()),
+        sonification: RefCell::new(None),
+        frame: RefCell::new(None),
+        animating: RefCell::new(false),
+    });
+
+    controls::remember_settings(&app, &controls, &settings)?;
+    generation::behaviours(&app, &controls)?;
+    solving::behaviours(&app, &controls)?;
+    playing::behaviours(&app, &controls)?;
+    sharing::behaviours(&app, &controls)?;
+    saving::behaviours(&app, &controls)?;
+    playback::behaviours(&app, &controls)?;
+    pointer::behaviours(&app, &controls)?;
+    view::behaviours(&app, &controls)?;
+
+    frame::start(&app, &controls);
+
+    Ok(())
+}
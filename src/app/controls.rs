@@ -0,0 +1,1086 @@
+use super::{
+    draw_legend, fill_algorithms, fill_library, fill_options, select_value, size_canvas, theme,
+    App, ParameterPanels, DEFAULT_ANIMATION_DELAY, DEFAULT_ANIMATION_STEPS,
+    DEFAULT_IMAGE_THRESHOLD, DEFAULT_SHUFFLE_SWAPS, GALLERY_MAZES, LEGEND, MAXIMUM_PRINT_SCALE,
+    MAXIMUM_SPEED, MAZE_FILE_EXTENSION,
+};
+use crate::direction::Direction;
+use crate::embed::Embedding;
+use crate::format::poster::{DEFAULT_TILE_CELLS, DEFAULT_TILE_OVERLAP};
+use crate::format::MazeData;
+use crate::generate::Generators;
+use crate::leaderboard::Leaderboard;
+use crate::library::Library;
+use crate::parameter::Values;
+use crate::platform::{Level, DEFAULT_LEVEL, LEVELS};
+use crate::settings::Settings;
+use crate::solve::{DEPTH_FIRST_SEARCH_ID, MAX_STEPS, ORDER};
+use crate::theme::{
+    LineCap, LineJoin, LINE_CAPS, LINE_JOINS, MAXIMUM_WALL_WIDTH, MINIMUM_WALL_WIDTH,
+};
+use crate::{generate, platform, ui, Dimensions, MINIMUM_DIMENSION};
+
+use wasm_bindgen::prelude::*;
+use web_sys::{
+    CanvasRenderingContext2d, Document, Element, Event, HtmlAnchorElement, HtmlButtonElement,
+    HtmlCanvasElement, HtmlElement, HtmlImageElement, HtmlInputElement, HtmlSelectElement,
+};
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use web_sys::HtmlTextAreaElement;
+
+/// Controls of an instance, and what they show.
+pub(super) struct Controls {
+    // document of the instance
+    pub(super) document: Document,
+    // builder of the elements of the instance
+    pub(super) builder: ui::Builder,
+    pub(super) div_app: HtmlElement,
+    pub(super) div_error: HtmlElement,
+    // context of the maze canvas
+    pub(super) context: Box<RefCell<CanvasRenderingContext2d>>,
+    pub(super) div_legend: HtmlElement,
+    // swatches of the legend, in the order of `LEGEND`
+    pub(super) legend_swatches: Vec<HtmlCanvasElement>,
+    pub(super) div_diagnostics: HtmlElement,
+    pub(super) div_difficulty: Element,
+    pub(super) div_metrics: Element,
+    pub(super) div_generate_time: Element,
+    pub(super) div_solve_time: Element,
+    pub(super) div_cell: Element,
+    pub(super) div_announcement: Element,
+    pub(super) div_solution: Element,
+    pub(super) div_comparison: HtmlElement,
+    // context of the canvas of the solver compared
+    pub(super) context_comparison: CanvasRenderingContext2d,
+    pub(super) div_comparison_metrics: Element,
+    pub(super) input_width: HtmlInputElement,
+    pub(super) input_height: HtmlInputElement,
+    pub(super) div_memory: Element,
+    pub(super) input_confirm_cells: HtmlInputElement,
+    pub(super) input_memory_budget: HtmlInputElement,
+    pub(super) input_precompute_paths: HtmlInputElement,
+    pub(super) div_size: Element,
+    pub(super) input_seed: HtmlInputElement,
+    pub(super) div_fingerprint: Element,
+    pub(super) select_generator: Rc<RefCell<HtmlSelectElement>>,
+    pub(super) button_generator: HtmlButtonElement,
+    pub(super) button_surprise: HtmlButtonElement,
+    pub(super) button_daily: HtmlButtonElement,
+    pub(super) button_complete: HtmlButtonElement,
+    pub(super) input_morph_seed: HtmlInputElement,
+    pub(super) button_morph: HtmlButtonElement,
+    pub(super) input_shuffle_swaps: HtmlInputElement,
+    pub(super) button_shuffle: HtmlButtonElement,
+    pub(super) select_solver: Rc<RefCell<HtmlSelectElement>>,
+    pub(super) div_solver_parameters: HtmlElement,
+    pub(super) input_from_to: HtmlInputElement,
+    pub(super) input_auto_solve: HtmlInputElement,
+    pub(super) button_solver: Rc<RefCell<HtmlButtonElement>>,
+    pub(super) select_comparison: HtmlSelectElement,
+    pub(super) button_compare: Rc<RefCell<HtmlButtonElement>>,
+    pub(super) input_waypoints: HtmlInputElement,
+    pub(super) button_clear_waypoints: HtmlButtonElement,
+    pub(super) input_pause_at_decisions: HtmlInputElement,
+    pub(super) button_resume: HtmlButtonElement,
+    pub(super) input_race: HtmlInputElement,
+    pub(super) input_two_players: HtmlInputElement,
+    pub(super) input_slide: HtmlInputElement,
+    pub(super) input_items: HtmlInputElement,
+    pub(super) input_enemies: HtmlInputElement,
+    pub(super) button_play: Rc<RefCell<HtmlButtonElement>>,
+    pub(super) button_hint: Rc<RefCell<HtmlButtonElement>>,
+    pub(super) div_status: Element,
+    pub(super) div_leaderboard: Element,
+    // best times of the mazes played
+    pub(super) leaderboard: Rc<RefCell<Leaderboard>>,
+    pub(super) div_daily: Element,
+    pub(super) input_cell_details: HtmlInputElement,
+    pub(super) input_legend: HtmlInputElement,
+    pub(super) input_diagnostics: HtmlInputElement,
+    pub(super) input_annotate: HtmlInputElement,
+    pub(super) button_spine: HtmlButtonElement,
+    pub(super) button_junctions: HtmlButtonElement,
+    pub(super) button_heat: HtmlButtonElement,
+    pub(super) div_overlays: Element,
+    pub(super) input_sound: HtmlInputElement,
+    pub(super) input_wall_width: HtmlInputElement,
+    pub(super) input_passage_width: HtmlInputElement,
+    pub(super) select_line_cap: HtmlSelectElement,
+    pub(super) select_line_join: HtmlSelectElement,
+    pub(super) input_backdrop: HtmlInputElement,
+    pub(super) button_clear_backdrop: HtmlButtonElement,
+    pub(super) select_log_level: HtmlSelectElement,
+    pub(super) button_replay: Rc<RefCell<HtmlButtonElement>>,
+    pub(super) input_timeline: Rc<RefCell<HtmlInputElement>>,
+    pub(super) input_speed: HtmlInputElement,
+    pub(super) input_demo: HtmlInputElement,
+    // generating algorithms, by name
+    pub(super) generators: Generators,
+    pub(super) button_record: HtmlButtonElement,
+    pub(super) button_stop_recording: HtmlButtonElement,
+    pub(super) input_animation_steps: HtmlInputElement,
+    pub(super) input_animation_delay: HtmlInputElement,
+    pub(super) button_export_animation: HtmlButtonElement,
+    pub(super) div_playback: Element,
+    pub(super) link_recording: HtmlAnchorElement,
+    pub(super) textarea_maze: HtmlTextAreaElement,
+    pub(super) button_export_json: HtmlButtonElement,
+    pub(super) button_import_json: HtmlButtonElement,
+    pub(super) button_export_code: HtmlButtonElement,
+    pub(super) button_import_code: HtmlButtonElement,
+    pub(super) button_copy: HtmlButtonElement,
+    pub(super) button_copy_link: HtmlButtonElement,
+    pub(super) button_copy_ascii: HtmlButtonElement,
+    pub(super) button_copy_json: HtmlButtonElement,
+    pub(super) button_qr_code: HtmlButtonElement,
+    pub(super) canvas_qr_code: HtmlCanvasElement,
+    pub(super) button_export_path: HtmlButtonElement,
+    pub(super) button_export_moves: HtmlButtonElement,
+    pub(super) button_export_dot: HtmlButtonElement,
+    pub(super) button_download: HtmlButtonElement,
+    pub(super) input_upload: HtmlInputElement,
+    pub(super) input_image: HtmlInputElement,
+    pub(super) input_image_cell_pixels: HtmlInputElement,
+    pub(super) input_image_threshold: HtmlInputElement,
+    pub(super) input_print_scale: HtmlInputElement,
+    pub(super) button_print: HtmlButtonElement,
+    pub(super) button_worksheet: HtmlButtonElement,
+    pub(super) input_tile_width: HtmlInputElement,
+    pub(super) input_tile_height: HtmlInputElement,
+    pub(super) input_tile_overlap: HtmlInputElement,
+    pub(super) button_tiles_svg: HtmlButtonElement,
+    pub(super) button_tiles_png: HtmlButtonElement,
+    pub(super) div_share: Element,
+    // pages of the worksheet, each a caption and a picture of the maze
+    pub(super) pages: [(Element, HtmlImageElement); 2],
+    pub(super) div_worksheet: HtmlElement,
+    // mazes saved by name
+    pub(super) library: Rc<RefCell<Library>>,
+    pub(super) input_maze_name: HtmlInputElement,
+    pub(super) button_save_maze: HtmlButtonElement,
+    pub(super) select_library: HtmlSelectElement,
+    pub(super) button_load_maze: HtmlButtonElement,
+    pub(super) button_delete_maze: HtmlButtonElement,
+    pub(super) div_library: Element,
+    // mazes most recently generated, most recent first
+    pub(super) gallery: Rc<RefCell<VecDeque<MazeData>>>,
+    // buttons and canvases of the thumbnails of the gallery
+    pub(super) thumbnails: Vec<(HtmlButtonElement, HtmlCanvasElement)>,
+    // controls of the parameters of the generators
+    pub(super) generator_parameters: Rc<ParameterPanels>,
+    // page embedding the instance in an iframe; if None then not embedded
+    pub(super) embedding: Option<Embedding>,
+}
+
+impl Controls {
+    /// Lay out the controls of an instance in container, set as settings and for a maze of
+    /// dimensions.
+    pub(super) fn new(
+        document: &Document,
+        container: &Element,
+        settings: &Settings,
+        dimensions: Dimensions,
+    ) -> Result<Self, JsValue> {
+        let builder = ui::Builder::new(document);
+
+        let div_app = ui::container(&builder, container, "app", "app")?;
+        // focusable so that clicking within directs the keyboard to this instance
+        div_app.set_tab_index(-1);
+
+        // errors, shown across the top of the instance until clicked
+        let div_error = ui::banner(&builder, &div_app, "error")?;
+        {
+            let div_error_clone = div_error.clone();
+            let closure = Closure::<dyn FnMut()>::new(move || div_error_clone.set_hidden(true));
+            div_error
+                .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+            closure.forget();
+        }
+
+        let div_panels = ui::container(&builder, &div_app, "panels", "panels")?;
+
+        let div = ui::container(&builder, &div_panels, "panel", "panel")?;
+
+        let canvas = ui::canvas(&builder, &div, "canvas", "maze")?;
+
+        let context = canvas
+            .get_context("2d")?
+            .ok_or("should have 2d context")?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+        size_canvas(&canvas, &context, dimensions)?;
+        settings.theme.apply(&context);
+        settings.theme.fit(&canvas, dimensions);
+
+        let (div_legend, legend_swatches) =
+            ui::legend(&builder, &div, "legend", &LEGEND.map(|(label, _)| label))?;
+        div_legend.set_hidden(!settings.legend);
+        draw_legend(&legend_swatches, &settings.theme)?;
+
+        let div_diagnostics = ui::container(&builder, &div, "text", "diagnostics")?;
+        div_diagnostics.set_hidden(!settings.diagnostics);
+
+        let div_difficulty = ui::text(&builder, &div, "difficulty")?;
+        let div_metrics = ui::text(&builder, &div, "metrics")?;
+        let div_generate_time = ui::text(&builder, &div, "generate-time")?;
+        let div_solve_time = ui::text(&builder, &div, "solve-time")?;
+        let div_cell = ui::text(&builder, &div, "cell")?;
+        let div_announcement = ui::status(&builder, &div, "announcement")?;
+        let div_solution = ui::screen_reader(&builder, &div, "solution")?;
+
+        let div_comparison = ui::container(&builder, &div_panels, "panel", "comparison-panel")?;
+        div_comparison.set_hidden(true);
+
+        let canvas = ui::canvas(
+            &builder,
+            &div_comparison,
+            "comparison-canvas",
+            "maze solved by the comparison solver",
+        )?;
+
+        let context_comparison = canvas
+            .get_context("2d")?
+            .ok_or("should have 2d context")?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+
+        let div_comparison_metrics = ui::text(&builder, &div_comparison, "comparison-metrics")?;
+
+        let div_controls = ui::container(&builder, &div_app, "controls", "controls")?;
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "generator", "Generator")?;
+
+        let input_width = ui::number(
+            &builder,
+            &fieldset,
+            "width",
+            "width",
+            &settings.width.to_string(),
+            &MINIMUM_DIMENSION.to_string(),
+        )?;
+
+        let input_height = ui::number(
+            &builder,
+            &fieldset,
+            "height",
+            "height",
+            &settings.height.to_string(),
+            &MINIMUM_DIMENSION.to_string(),
+        )?;
+
+        let div_memory = ui::text(&builder, &fieldset, "memory-estimate")?;
+
+        let input_confirm_cells = ui::number(
+            &builder,
+            &fieldset,
+            "confirm-cells",
+            "cells before confirming",
+            &settings.confirm_cells.to_string(),
+            "0",
+        )?;
+
+        let input_memory_budget = ui::number(
+            &builder,
+            &fieldset,
+            "memory-budget",
+            "memory budget (MB)",
+            &settings.memory_budget.to_string(),
+            "1",
+        )?;
+
+        let input_precompute_paths = ui::checkbox(
+            &builder,
+            &fieldset,
+            "precompute-paths",
+            "precompute shortest paths",
+            settings.precompute_paths,
+        )?;
+
+        let div_size = ui::message(&builder, &fieldset, "size-message")?;
+
+        let input_seed = ui::number(
+            &builder,
+            &fieldset,
+            "seed",
+            "seed (empty for random)",
+            &settings.seed.map_or(String::new(), |seed| seed.to_string()),
+            "0",
+        )?;
+        input_seed.set_placeholder("random");
+
+        // fingerprint of the walls of the maze, to check that two mazes are the same however
+        // they were produced
+        let div_fingerprint = ui::text(&builder, &fieldset, "fingerprint")?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let select_generator = ui::select(&builder, &row, "generator-select", "generator")?;
+        let div_generator_parameters = ui::container(
+            &builder,
+            &fieldset,
+            "parameter-panels",
+            "generator-parameters",
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_generator = ui::button(&builder, &row, "generate", "Generate", false)?;
+        let button_surprise = ui::button(&builder, &row, "surprise", "Surprise me", false)?;
+        let button_daily = ui::button(&builder, &row, "daily", "Daily maze", false)?;
+        let button_complete = ui::button(&builder, &row, "complete", "Complete maze", false)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let input_morph_seed = ui::number(&builder, &row, "morph-seed", "morph to seed", "", "0")?;
+        input_morph_seed.set_placeholder("random");
+        let button_morph = ui::button(&builder, &row, "morph", "Morph", false)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let input_shuffle_swaps = ui::number(
+            &builder,
+            &row,
+            "shuffle-swaps",
+            "swaps",
+            &DEFAULT_SHUFFLE_SWAPS.to_string(),
+            "1",
+        )?;
+        let button_shuffle = ui::button(&builder, &row, "shuffle", "Shuffle", false)?;
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "solver", "Solver")?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let select_solver = ui::select(&builder, &row, "solver-select", "solver")?;
+        let div_solver_parameters =
+            ui::container(&builder, &fieldset, "parameter-panels", "solver-parameters")?;
+
+        let input_from_to = ui::checkbox(
+            &builder,
+            &fieldset,
+            "new-locations",
+            "with new locations",
+            settings.new_locations,
+        )?;
+
+        let input_auto_solve = ui::checkbox(
+            &builder,
+            &fieldset,
+            "auto-solve",
+            "after generating",
+            settings.auto_solve,
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_solver = ui::button(&builder, &row, "solve", "Solve", true)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let select_comparison = ui::select(
+            &builder,
+            &row,
+            "comparison-select",
+            "solver to compare with",
+        )?;
+        let button_compare = ui::button(&builder, &row, "compare", "Compare", true)?;
+
+        let input_waypoints = ui::checkbox(
+            &builder,
+            &fieldset,
+            "waypoints",
+            "place waypoints (click cells in the order to visit them)",
+            false,
+        )?;
+        let row = ui::row(&builder, &fieldset)?;
+        let button_clear_waypoints =
+            ui::button(&builder, &row, "clear-waypoints", "Clear waypoints", false)?;
+
+        let input_pause_at_decisions = ui::checkbox(
+            &builder,
+            &fieldset,
+            "pause-at-decisions",
+            "pause at decision points",
+            settings.pause_at_decisions,
+        )?;
+        let row = ui::row(&builder, &fieldset)?;
+        let button_resume = ui::button(&builder, &row, "resume", "Resume", true)?;
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "play", "Play")?;
+
+        let input_race = ui::checkbox(
+            &builder,
+            &fieldset,
+            "race",
+            "race the solver",
+            settings.race,
+        )?;
+
+        let input_two_players = ui::checkbox(
+            &builder,
+            &fieldset,
+            "two-players",
+            "two players (arrow keys and WASD)",
+            settings.two_players,
+        )?;
+
+        let input_slide = ui::checkbox(
+            &builder,
+            &fieldset,
+            "slide",
+            "slide on ice to the next wall",
+            settings.slide,
+        )?;
+
+        let input_items = ui::number(
+            &builder,
+            &fieldset,
+            "items",
+            "items to collect",
+            &settings.items.to_string(),
+            "0",
+        )?;
+
+        let input_enemies = ui::number(
+            &builder,
+            &fieldset,
+            "enemies",
+            "patrolling enemies",
+            &settings.enemies.to_string(),
+            "0",
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_play = ui::button(&builder, &row, "play", "Play", true)?;
+        let button_hint = ui::button(&builder, &row, "hint", "Hint", true)?;
+
+        let div_status = ui::status(&builder, &fieldset, "status")?;
+        let div_leaderboard = ui::text(&builder, &fieldset, "leaderboard")?;
+        let div_daily = ui::text(&builder, &fieldset, "daily-result")?;
+        let leaderboard = Rc::new(RefCell::new(Leaderboard::load()));
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "view", "View")?;
+
+        let input_cell_details = ui::checkbox(
+            &builder,
+            &fieldset,
+            "cell-details",
+            "show cell details on hover",
+            settings.cell_details,
+        )?;
+
+        let input_legend = ui::checkbox(
+            &builder,
+            &fieldset,
+            "legend",
+            "show colour legend",
+            settings.legend,
+        )?;
+
+        let input_diagnostics = ui::checkbox(
+            &builder,
+            &fieldset,
+            "diagnostics",
+            "show frame and step rates",
+            settings.diagnostics,
+        )?;
+
+        let input_annotate = ui::checkbox(
+            &builder,
+            &fieldset,
+            "annotate",
+            "annotate cells (click a cell to add, edit or remove its note)",
+            false,
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_spine = ui::button(&builder, &row, "spine", "Longest path", false)?;
+        let button_junctions = ui::button(&builder, &row, "junctions", "Junctions", false)?;
+        let button_heat = ui::button(&builder, &row, "bottlenecks", "Bottlenecks", false)?;
+        let div_overlays = ui::text(&builder, &fieldset, "overlays")?;
+
+        let input_sound = ui::checkbox(
+            &builder,
+            &fieldset,
+            "sound",
+            "hear the solver, pitched by distance from the start",
+            settings.sound,
+        )?;
+
+        let input_wall_width = ui::number(
+            &builder,
+            &fieldset,
+            "wall-width",
+            "wall width in pixels",
+            &settings.theme.wall_width.to_string(),
+            &MINIMUM_WALL_WIDTH.to_string(),
+        )?;
+        input_wall_width.set_max(&MAXIMUM_WALL_WIDTH.to_string());
+        input_wall_width.set_step("0.5");
+
+        let input_passage_width = ui::number(
+            &builder,
+            &fieldset,
+            "passage-width",
+            "passage width in pixels (0 to fit)",
+            &settings.theme.passage_width.to_string(),
+            "0",
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let select_line_cap = ui::select(&builder, &row, "line-cap-select", "line ends")?;
+        fill_options(&select_line_cap, LINE_CAPS.iter().map(LineCap::name))?;
+        select_value(&select_line_cap, settings.theme.line_cap.name());
+        let select_line_join = ui::select(&builder, &row, "line-join-select", "line corners")?;
+        fill_options(&select_line_join, LINE_JOINS.iter().map(LineJoin::name))?;
+        select_value(&select_line_join, settings.theme.line_join.name());
+
+        let input_backdrop = ui::file_input(
+            &builder,
+            &fieldset,
+            "backdrop",
+            "picture under the passages",
+            "image/*",
+        )?;
+        let row = ui::row(&builder, &fieldset)?;
+        let button_clear_backdrop =
+            ui::button(&builder, &row, "clear-backdrop", "Clear picture", true)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let select_log_level = ui::select(&builder, &row, "log-level-select", "console log level")?;
+        fill_options(&select_log_level, LEVELS.iter().map(Level::name))?;
+        select_value(&select_log_level, settings.log_level.name());
+        platform::set_level(settings.log_level);
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "playback", "Playback")?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_replay = ui::button(&builder, &row, "replay", "Replay", true)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let input_timeline = ui::range(&builder, &row, "timeline", "timeline step")?;
+        input_timeline.set_disabled(true);
+
+        let row = ui::row(&builder, &fieldset)?;
+        let input_speed = ui::range(&builder, &row, "speed", "animation speed")?;
+        input_speed.set_min("1");
+        input_speed.set_max(&MAXIMUM_SPEED.to_string());
+        input_speed.set_value(&settings.speed.to_string());
+
+        let input_demo = ui::checkbox(
+            &builder,
+            &fieldset,
+            "demo",
+            "cycle through random generators and solvers",
+            settings.demo,
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_record = ui::button(&builder, &row, "record", "Record video", false)?;
+        let button_stop_recording =
+            ui::button(&builder, &row, "stop-recording", "Stop recording", true)?;
+
+        let input_animation_steps = ui::number(
+            &builder,
+            &fieldset,
+            "animation-steps",
+            "steps per animation frame",
+            &DEFAULT_ANIMATION_STEPS.to_string(),
+            "1",
+        )?;
+        let input_animation_delay = ui::number(
+            &builder,
+            &fieldset,
+            "animation-delay",
+            "milliseconds per animation frame",
+            &DEFAULT_ANIMATION_DELAY.to_string(),
+            "10",
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_export_animation = ui::button(
+            &builder,
+            &row,
+            "export-animation",
+            "Export animation",
+            false,
+        )?;
+
+        let div_playback = ui::message(&builder, &fieldset, "playback-message")?;
+        let link_recording = ui::link(&builder, &fieldset, "recording-link", "Download video")?;
+        link_recording.set_hidden(true);
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "share", "Share")?;
+
+        let textarea_maze = ui::textarea(&builder, &fieldset, "maze-text", "maze as text")?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_export_json = ui::button(&builder, &row, "export-json", "Export JSON", false)?;
+        let button_import_json = ui::button(&builder, &row, "import-json", "Import JSON", false)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_export_code = ui::button(&builder, &row, "export-code", "Export code", false)?;
+        let button_import_code = ui::button(&builder, &row, "import-code", "Import code", false)?;
+        let button_copy = ui::button(&builder, &row, "copy", "Copy", false)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_copy_link = ui::button(&builder, &row, "copy-link", "Copy link", false)?;
+        let button_copy_ascii = ui::button(&builder, &row, "copy-ascii", "Copy ASCII", false)?;
+        let button_copy_json = ui::button(&builder, &row, "copy-json", "Copy JSON", false)?;
+        let button_qr_code = ui::button(&builder, &row, "qr-code", "QR code", false)?;
+
+        let canvas_qr_code = ui::canvas(
+            &builder,
+            &fieldset,
+            "qr-canvas",
+            "QR code of the maze permalink",
+        )?;
+        canvas_qr_code.set_hidden(true);
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_export_path = ui::button(&builder, &row, "export-path", "Export path", false)?;
+        let button_export_moves =
+            ui::button(&builder, &row, "export-moves", "Export moves", false)?;
+        let button_export_dot = ui::button(&builder, &row, "export-dot", "Export DOT", false)?;
+        let button_download = ui::button(&builder, &row, "download", "Download .maze", false)?;
+
+        let input_upload = ui::file_input(
+            &builder,
+            &fieldset,
+            "upload",
+            "load .maze file",
+            &format!(".{MAZE_FILE_EXTENSION},application/json"),
+        )?;
+
+        let input_image = ui::file_input(
+            &builder,
+            &fieldset,
+            "image",
+            "load maze image (walls dark, passages light)",
+            "image/*",
+        )?;
+
+        let input_image_cell_pixels = ui::number(
+            &builder,
+            &fieldset,
+            "image-cell-pixels",
+            "image cell pixels (0 to detect)",
+            "0",
+            "0",
+        )?;
+
+        let input_image_threshold = ui::number(
+            &builder,
+            &fieldset,
+            "image-threshold",
+            "image wall threshold (0 to 255)",
+            &DEFAULT_IMAGE_THRESHOLD.to_string(),
+            "0",
+        )?;
+        input_image_threshold.set_max("255");
+
+        let input_print_scale = ui::number(
+            &builder,
+            &fieldset,
+            "print-scale",
+            "print resolution (times that on screen)",
+            &settings.print_scale.to_string(),
+            "1",
+        )?;
+        input_print_scale.set_max(&MAXIMUM_PRINT_SCALE.to_string());
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_print = ui::button(&builder, &row, "render-print", "Render for print", false)?;
+        let button_worksheet =
+            ui::button(&builder, &row, "print-worksheet", "Print worksheet", false)?;
+
+        let input_tile_width = ui::number(
+            &builder,
+            &fieldset,
+            "tile-width",
+            "poster tile width (cells)",
+            &DEFAULT_TILE_CELLS.0.to_string(),
+            "1",
+        )?;
+        let input_tile_height = ui::number(
+            &builder,
+            &fieldset,
+            "tile-height",
+            "poster tile height (cells)",
+            &DEFAULT_TILE_CELLS.1.to_string(),
+            "1",
+        )?;
+        let input_tile_overlap = ui::number(
+            &builder,
+            &fieldset,
+            "tile-overlap",
+            "poster tile overlap (cells)",
+            &DEFAULT_TILE_OVERLAP.to_string(),
+            "0",
+        )?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_tiles_svg =
+            ui::button(&builder, &row, "poster-svg", "Poster tiles (SVG)", false)?;
+        let button_tiles_png =
+            ui::button(&builder, &row, "poster-png", "Poster tiles (PNG)", false)?;
+
+        let div_share = ui::message(&builder, &fieldset, "share-message")?;
+
+        // pages of the unsolved and solved maze, printed in place of the document
+        let div_worksheet = ui::worksheet(&builder, &div_app, "worksheet")?;
+        let pages = [
+            ui::worksheet_page(&builder, &div_worksheet)?,
+            ui::worksheet_page(&builder, &div_worksheet)?,
+        ];
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "library", "Library")?;
+
+        let input_maze_name = ui::text_input(&builder, &fieldset, "maze-name", "name")?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let button_save_maze = ui::button(&builder, &row, "save-maze", "Save", false)?;
+
+        let row = ui::row(&builder, &fieldset)?;
+        let select_library = ui::select(&builder, &row, "library-select", "saved mazes")?;
+        let button_load_maze = ui::button(&builder, &row, "load-maze", "Load", false)?;
+        let button_delete_maze = ui::button(&builder, &row, "delete-maze", "Delete", false)?;
+
+        let div_library = ui::message(&builder, &fieldset, "library-message")?;
+
+        // mazes saved by name
+        let library = Library::load();
+        fill_library(&select_library, &library)?;
+        let library = Rc::new(RefCell::new(library));
+
+        let fieldset = ui::fieldset(&builder, &div_controls, "gallery", "Recent mazes")?;
+        let div_gallery = ui::container(&builder, &fieldset, "gallery", "gallery")?;
+        let thumbnails = (0..GALLERY_MAZES)
+            .map(|idx| {
+                let thumbnail = ui::thumbnail(
+                    &builder,
+                    &div_gallery,
+                    &format!("gallery-{idx}"),
+                    &format!("restore recent maze {}", idx + 1),
+                )?;
+                thumbnail.0.set_hidden(true);
+                Ok(thumbnail)
+            })
+            .collect::<Result<Vec<_>, JsValue>>()?;
+        // mazes most recently generated, most recent first
+        let gallery = Rc::new(RefCell::new(VecDeque::<MazeData>::new()));
+
+        // setup generators
+        let generators = generate::generators();
+        fill_algorithms(&select_generator, &generators)?;
+        select_value(&select_generator, &settings.generator);
+        let generator_parameters = ParameterPanels::new(
+            &builder,
+            &div_generator_parameters,
+            "generator-parameter",
+            generators.iter().map(|(id, kind)| (id, kind.parameters())),
+            &Values::default(),
+        )?;
+        generator_parameters.show(&select_generator.value());
+        let generator_parameters = Rc::new(generator_parameters);
+
+        let context = Box::new(RefCell::new(context));
+        let select_generator = Rc::new(RefCell::new(select_generator));
+        let select_solver = Rc::new(RefCell::new(select_solver));
+        let button_solver = Rc::new(RefCell::new(button_solver));
+        let button_play = Rc::new(RefCell::new(button_play));
+        let button_hint = Rc::new(RefCell::new(button_hint));
+        let button_replay = Rc::new(RefCell::new(button_replay));
+        let button_compare = Rc::new(RefCell::new(button_compare));
+        let input_timeline = Rc::new(RefCell::new(input_timeline));
+        let embedding = Embedding::new();
+        Ok(Controls {
+            document: document.clone(),
+            builder,
+            div_app,
+            div_error,
+            context,
+            div_legend,
+            legend_swatches,
+            div_diagnostics,
+            div_difficulty,
+            div_metrics,
+            div_generate_time,
+            div_solve_time,
+            div_cell,
+            div_announcement,
+            div_solution,
+            div_comparison,
+            div_comparison_metrics,
+            context_comparison,
+            input_width,
+            input_height,
+            div_memory,
+            input_confirm_cells,
+            input_memory_budget,
+            input_precompute_paths,
+            div_size,
+            input_seed,
+            div_fingerprint,
+            select_generator,
+            button_generator,
+            button_surprise,
+            button_daily,
+            button_complete,
+            input_morph_seed,
+            button_morph,
+            input_shuffle_swaps,
+            button_shuffle,
+            select_solver,
+            div_solver_parameters,
+            input_from_to,
+            input_auto_solve,
+            button_solver,
+            select_comparison,
+            button_compare,
+            input_waypoints,
+            button_clear_waypoints,
+            input_pause_at_decisions,
+            button_resume,
+            input_race,
+            input_two_players,
+            input_slide,
+            input_items,
+            input_enemies,
+            button_play,
+            button_hint,
+            div_status,
+            div_leaderboard,
+            leaderboard,
+            div_daily,
+            input_cell_details,
+            input_legend,
+            input_diagnostics,
+            input_annotate,
+            button_spine,
+            button_junctions,
+            button_heat,
+            div_overlays,
+            input_sound,
+            input_wall_width,
+            input_passage_width,
+            select_line_cap,
+            select_line_join,
+            input_backdrop,
+            button_clear_backdrop,
+            select_log_level,
+            button_replay,
+            input_timeline,
+            input_speed,
+            input_demo,
+            generators,
+            button_record,
+            button_stop_recording,
+            input_animation_steps,
+            input_animation_delay,
+            button_export_animation,
+            div_playback,
+            link_recording,
+            textarea_maze,
+            button_export_json,
+            button_import_json,
+            button_export_code,
+            button_import_code,
+            button_copy,
+            button_copy_link,
+            button_copy_ascii,
+            button_copy_json,
+            button_qr_code,
+            canvas_qr_code,
+            button_export_path,
+            button_export_moves,
+            button_export_dot,
+            button_download,
+            input_upload,
+            input_image,
+            input_image_cell_pixels,
+            input_image_threshold,
+            input_print_scale,
+            button_print,
+            button_worksheet,
+            input_tile_width,
+            input_tile_height,
+            input_tile_overlap,
+            button_tiles_svg,
+            button_tiles_png,
+            div_share,
+            pages,
+            div_worksheet,
+            library,
+            input_maze_name,
+            button_save_maze,
+            select_library,
+            button_load_maze,
+            button_delete_maze,
+            div_library,
+            gallery,
+            thumbnails,
+            generator_parameters,
+            embedding,
+        })
+    }
+}
+
+/// Remember settings on change of any control.
+pub(super) fn remember_settings(
+    app: &Rc<App>,
+    controls: &Rc<Controls>,
+    settings: &Settings,
+) -> Result<(), JsValue> {
+    let Controls {
+        div_solver_parameters,
+        input_auto_solve,
+        input_cell_details,
+        input_confirm_cells,
+        input_demo,
+        input_diagnostics,
+        input_enemies,
+        input_from_to,
+        input_height,
+        input_items,
+        input_legend,
+        input_memory_budget,
+        input_passage_width,
+        input_pause_at_decisions,
+        input_precompute_paths,
+        input_print_scale,
+        input_race,
+        input_seed,
+        input_slide,
+        input_sound,
+        input_speed,
+        input_two_players,
+        input_wall_width,
+        input_width,
+        select_comparison,
+        select_generator,
+        select_line_cap,
+        select_line_join,
+        select_log_level,
+        select_solver,
+        ..
+    } = &**controls;
+    let mut settings = settings.clone();
+    let input_width = input_width.clone();
+    let input_height = input_height.clone();
+    let input_confirm_cells = input_confirm_cells.clone();
+    let input_memory_budget = input_memory_budget.clone();
+    let input_precompute_paths = input_precompute_paths.clone();
+    let input_seed = input_seed.clone();
+    let select_generator = select_generator.borrow().clone();
+    let select_solver = select_solver.borrow().clone();
+    let select_comparison = select_comparison.clone();
+    let solver_parameters = app.solver_parameters.clone();
+    let input_pause_at_decisions = input_pause_at_decisions.clone();
+    let input_from_to = input_from_to.clone();
+    let input_auto_solve = input_auto_solve.clone();
+    let input_race = input_race.clone();
+    let input_two_players = input_two_players.clone();
+    let input_slide = input_slide.clone();
+    let input_items = input_items.clone();
+    let input_enemies = input_enemies.clone();
+    let input_cell_details = input_cell_details.clone();
+    let input_legend = input_legend.clone();
+    let input_diagnostics = input_diagnostics.clone();
+    let input_sound = input_sound.clone();
+    let input_wall_width = input_wall_width.clone();
+    let input_passage_width = input_passage_width.clone();
+    let select_line_cap = select_line_cap.clone();
+    let select_line_join = select_line_join.clone();
+    let input_print_scale = input_print_scale.clone();
+    let input_demo = input_demo.clone();
+    let input_speed = input_speed.clone();
+    let select_log_level = select_log_level.clone();
+    let controls: [&Element; 30] = [
+        &input_width,
+        &input_height,
+        &input_confirm_cells,
+        &input_memory_budget,
+        &input_precompute_paths,
+        &input_seed,
+        &select_generator,
+        &select_solver,
+        &select_comparison,
+        div_solver_parameters,
+        &input_pause_at_decisions,
+        &input_from_to,
+        &input_auto_solve,
+        &input_race,
+        &input_two_players,
+        &input_slide,
+        &input_items,
+        &input_enemies,
+        &input_cell_details,
+        &input_legend,
+        &input_diagnostics,
+        &input_sound,
+        &input_wall_width,
+        &input_passage_width,
+        &select_line_cap,
+        &select_line_join,
+        &input_print_scale,
+        &input_demo,
+        &input_speed,
+        &select_log_level,
+    ];
+    let controls = controls.map(Element::clone);
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        settings.width = input_width.value().parse().unwrap_or(settings.width);
+        settings.height = input_height.value().parse().unwrap_or(settings.height);
+        settings.confirm_cells = input_confirm_cells
+            .value()
+            .parse()
+            .unwrap_or(settings.confirm_cells);
+        settings.memory_budget = input_memory_budget
+            .value()
+            .parse()
+            .unwrap_or(settings.memory_budget);
+        settings.precompute_paths = input_precompute_paths.checked();
+        settings.seed = input_seed.value().trim().parse().ok();
+        settings.generator = select_generator.value();
+        settings.solver = select_solver.value();
+        settings.comparison = select_comparison.value();
+        // the direction order is that of the depth first search, and the step limit that
+        // of the selected solver
+        let order = solver_parameters
+            .values(DEPTH_FIRST_SEARCH_ID)
+            .number(ORDER);
+        settings.order = Direction::order_at(order as usize);
+        settings.max_steps = solver_parameters
+            .values(&select_solver.value())
+            .number(MAX_STEPS) as usize;
+        settings.pause_at_decisions = input_pause_at_decisions.checked();
+        settings.new_locations = input_from_to.checked();
+        settings.auto_solve = input_auto_solve.checked();
+        settings.race = input_race.checked();
+        settings.two_players = input_two_players.checked();
+        settings.slide = input_slide.checked();
+        settings.items = input_items.value().parse().unwrap_or(settings.items);
+        settings.enemies = input_enemies.value().parse().unwrap_or(settings.enemies);
+        settings.cell_details = input_cell_details.checked();
+        settings.legend = input_legend.checked();
+        settings.diagnostics = input_diagnostics.checked();
+        settings.sound = input_sound.checked();
+        settings.theme = theme(
+            &input_wall_width,
+            &input_passage_width,
+            &select_line_cap,
+            &select_line_join,
+            &settings.theme,
+        );
+        settings.print_scale = input_print_scale
+            .value()
+            .parse()
+            .unwrap_or(settings.print_scale);
+        settings.demo = input_demo.checked();
+        settings.speed = input_speed.value().parse().unwrap_or(settings.speed);
+        settings.log_level = Level::parse(&select_log_level.value()).unwrap_or(DEFAULT_LEVEL);
+        platform::set_level(settings.log_level);
+        settings.save();
+    });
+    for control in controls {
+        control.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    }
+    closure.forget();
+    Ok(())
+}
@@ -0,0 +1,685 @@
+use super::controls::Controls;
+use super::{
+    create_solver, daily, draw, draw_frontier, draw_players, elapsed, enable_timeline,
+    fill_gallery, now, optimality, permalink, request_animation_frame, select_random, size_canvas,
+    statistics, stop_recording, App, Overlays, Phase, CELLS_PER_FRAME_STEP, DAILY_DIMENSION,
+    DAILY_GENERATOR, DEFAULT_SPEED, DEMO_PAUSE_MILLISECONDS, FRAME_BUDGET_MILLISECONDS,
+    GALLERY_MAZES, LOCATIONS_SEED,
+};
+use crate::analysis::choices;
+use crate::compare::summary;
+use crate::diagnostics::Diagnostics;
+use crate::error::Error;
+use crate::format::{fingerprint, MazeData};
+use crate::geometry::row_and_col;
+use crate::leaderboard::maze_key;
+use crate::path::{describe_moves, solution};
+use crate::recording::Timeline;
+use crate::rng::{Platform, Rng, Xorshift};
+use crate::sound::{distance_pitch, Sonification};
+use crate::step::StepResult;
+use crate::{leaderboard, ui};
+
+use js_sys::Date;
+use wasm_bindgen::prelude::*;
+
+use std::rc::Rc;
+
+/// A type for the state of the frames of an instance, kept from one to the next.
+#[derive(Default)]
+pub(super) struct Frames {
+    // time at which the demo restarts; if None then not waiting
+    demo_restart: Option<f64>,
+    // rates of the frames, measured while shown
+    diagnostics: Diagnostics,
+}
+
+/// Start the visualisation of app, requested each frame until there is nothing to animate.
+pub(super) fn start(app: &Rc<App>, controls: &Rc<Controls>) {
+    let mut frames = Frames::default();
+    let (animation, controls) = (app.clone(), controls.clone());
+    *app.frame.borrow_mut() = Some(Closure::new(move || {
+        animation.frame(&controls, &mut frames)
+    }));
+    app.animate();
+}
+
+impl App {
+    /// Step the phase for as long as the frame allows and draw the maze, then request the
+    /// next frame unless there is nothing left to animate.
+    fn frame(&self, controls: &Controls, frames: &mut Frames) {
+        let Controls {
+            div_error,
+            input_diagnostics,
+            div_diagnostics,
+            select_generator,
+            select_solver,
+            button_generator,
+            ..
+        } = controls;
+        if *self.paused.borrow() {
+            *self.animating.borrow_mut() = false;
+            return;
+        }
+        // whether complete throughout the previous frame, so that nothing is left to do
+        let completed = matches!(*self.phase.borrow(), Phase::Complete);
+        let frame_started = now();
+        // number of steps applied
+        let mut applied = 0;
+
+        let stepped = self
+            .step(controls, frames, &mut applied)
+            .unwrap_or_else(|error| {
+                error.report(div_error);
+                // stop the phase which failed rather than fail again each frame
+                *self.phase.borrow_mut() = Phase::Complete;
+                false
+            });
+        if stepped {
+            self.draw_frame(controls);
+        }
+
+        if input_diagnostics.checked() {
+            let time = now();
+            if let Some(description) = frames
+                .diagnostics
+                .frame(time, applied, time - frame_started)
+            {
+                div_diagnostics.set_text_content(Some(&description));
+            }
+        }
+
+        // restart the demo with a random generator and solver after the pause
+        if frames
+            .demo_restart
+            .is_some_and(|restart| Date::now() >= restart)
+        {
+            frames.demo_restart = None;
+            select_random(&select_generator.borrow());
+            select_random(&select_solver.borrow());
+            button_generator.click();
+        }
+
+        // stop once complete unless waiting to restart the demo; behaviours starting a
+        // phase request frames again
+        if completed
+            && matches!(*self.phase.borrow(), Phase::Complete)
+            && frames.demo_restart.is_none()
+        {
+            *self.animating.borrow_mut() = false;
+        } else {
+            request_animation_frame(self.frame.borrow().as_ref().unwrap());
+        }
+    }
+
+    /// Apply steps of the phase in proportion to the size of the maze and the speed, for as
+    /// long as the budget of the frame allows, counting them in applied. Returns whether
+    /// any step was applied.
+    fn step(
+        &self,
+        controls: &Controls,
+        frames: &mut Frames,
+        applied: &mut usize,
+    ) -> Result<bool, Error> {
+        let mut phase = self.phase.borrow_mut();
+        let steps = controls
+            .input_speed
+            .value()
+            .parse()
+            .unwrap_or(DEFAULT_SPEED) as usize
+            * (self.cells.borrow().len() / CELLS_PER_FRAME_STEP).max(1);
+        let budget = now() + FRAME_BUDGET_MILLISECONDS;
+        let mut stepped = false;
+        for _ in 0..steps {
+            let step = match *phase {
+                Phase::Generate => {
+                    self.step_generate(controls, &mut phase)?;
+                    true
+                }
+                Phase::Solve => {
+                    self.step_solve(controls, &mut phase)?;
+                    true
+                }
+                Phase::Compare => {
+                    self.step_compare(&mut phase);
+                    true
+                }
+                Phase::Play { race } => {
+                    self.step_play(controls, &mut phase, race);
+                    true
+                }
+                Phase::Import(ref data) => {
+                    let data = data.clone();
+                    self.import(controls, &data)?;
+                    *phase = Phase::Complete;
+                    true
+                }
+                Phase::Complete => {
+                    self.complete(controls, frames);
+                    false
+                }
+            };
+            stepped |= step;
+            *applied += usize::from(step);
+            // only the algorithm phases apply more than one step each frame
+            if !step
+                || !matches!(*phase, Phase::Generate | Phase::Solve | Phase::Compare)
+                || *self.paused.borrow()
+                || now() >= budget
+            {
+                break;
+            }
+        }
+        Ok(stepped)
+    }
+
+    /// Apply a step of the generator. Once the maze is generated, choose its from and to
+    /// cells, show its statistics and move phase on to solving it or complete.
+    fn step_generate(&self, controls: &Controls, phase: &mut Phase) -> Result<(), Error> {
+        let Controls {
+            gallery,
+            thumbnails,
+            input_precompute_paths,
+            div_difficulty,
+            div_fingerprint,
+            select_solver,
+            button_solver,
+            button_play,
+            button_compare,
+            div_announcement,
+            embedding,
+            div_generate_time,
+            input_auto_solve,
+            input_demo,
+            input_timeline,
+            ..
+        } = controls;
+        let mut cells = self.cells.borrow_mut();
+        if self
+            .timeline
+            .borrow_mut()
+            .generation
+            .record(&mut cells, |cells| {
+                self.generator
+                    .borrow_mut()
+                    .step(*self.dimensions.borrow(), cells)
+                    .is_continue()
+            })
+        {
+            return Ok(());
+        }
+        // the seed also chooses from and to, so that it repeats the puzzle, from a stream
+        // apart from that of the generator
+        let mut rng: Box<dyn Rng> = match *self.seed.borrow() {
+            Some(seed) => Box::new(Xorshift::new(seed ^ LOCATIONS_SEED)),
+            None => Box::new(Platform),
+        };
+        let (mut from, mut to) = (self.from.borrow_mut(), self.to.borrow_mut());
+        (*from, *to) = (rng.index(cells.len()), rng.index(cells.len()));
+        while *from == *to {
+            *to = rng.index(cells.len());
+        }
+        cells.reset_solution(*from, *to);
+        {
+            let mut gallery = gallery.borrow_mut();
+            gallery.push_front(MazeData::new(*self.dimensions.borrow(), &cells, *from, *to));
+            gallery.truncate(GALLERY_MAZES);
+            fill_gallery(thumbnails, &gallery)?;
+        }
+        // the shortest paths are searched once here, when precomputing, for the statistics
+        // and every later use
+        let dimensions = *self.dimensions.borrow();
+        let precompute = input_precompute_paths.checked();
+        let path = self.shortest_path(dimensions, &cells, *from, *to, precompute);
+        div_difficulty.set_text_content(Some(&statistics(dimensions, &cells, path.as_deref())));
+        div_fingerprint.set_text_content(Some(&format!(
+            "fingerprint {}",
+            fingerprint(dimensions, &cells.all_walls())
+        )));
+        *self.solver.borrow_mut() = create_solver(
+            &self.solvers.borrow(),
+            &select_solver.borrow().value(),
+            &self.solver_parameters,
+        )?;
+        button_solver.borrow().set_disabled(false);
+        button_play.borrow().set_disabled(false);
+        button_compare.borrow().set_disabled(false);
+        div_announcement.set_text_content(Some(&format!(
+            "maze generated, {} by {} cells",
+            dimensions.0, dimensions.1
+        )));
+        if let Some(embedding) = embedding {
+            embedding.notify(
+                "generated",
+                &[
+                    ("width", dimensions.0.into()),
+                    ("height", dimensions.1.into()),
+                ],
+            );
+        }
+        if let Some(started) = self.phase_started.borrow_mut().take() {
+            div_generate_time.set_text_content(Some(&elapsed(
+                "generated",
+                started,
+                self.timeline.borrow().generation.len(),
+            )));
+        }
+        *phase = match input_auto_solve.checked() || input_demo.checked() {
+            true => {
+                *self.phase_started.borrow_mut() = Some(now());
+                Phase::Solve
+            }
+            false => {
+                enable_timeline(&input_timeline.borrow(), self.timeline.borrow().len());
+                Phase::Complete
+            }
+        };
+        Ok(())
+    }
+
+    /// Apply a step of the solver, and of any comparison, pausing at a decision if chosen.
+    /// Once solved, show the solution and move phase on to finishing the comparison or
+    /// complete.
+    fn step_solve(&self, controls: &Controls, phase: &mut Phase) -> Result<(), Error> {
+        let Controls {
+            input_pause_at_decisions,
+            button_resume,
+            div_announcement,
+            input_sound,
+            input_timeline,
+            button_replay,
+            div_solve_time,
+            embedding,
+            input_precompute_paths,
+            div_solution,
+            ..
+        } = controls;
+        let (mut cells, from, to) = (
+            self.cells.borrow_mut(),
+            self.from.borrow(),
+            self.to.borrow(),
+        );
+        let dimensions = *self.dimensions.borrow();
+        let comparing = self
+            .comparison
+            .borrow_mut()
+            .as_mut()
+            .is_some_and(|comparison| comparison.step(dimensions, *from, *to));
+        let continuing = self
+            .timeline
+            .borrow_mut()
+            .solution
+            .record(&mut cells, |cells| {
+                self.solver
+                    .borrow_mut()
+                    .step(dimensions, cells, *from, *to)
+                    .continuing()
+            });
+        // at a decision point the solve pauses, so that the choice can be discussed before
+        // resuming
+        if continuing == Ok(true) && input_pause_at_decisions.checked() {
+            let decision = self
+                .timeline
+                .borrow()
+                .solution
+                .last_changes()
+                .map(|(cell, _, _)| (cell, choices(dimensions, &cells, cell)))
+                .find(|(_, choices)| *choices >= 2);
+            if let Some((cell, choices)) = decision {
+                *self.paused.borrow_mut() = true;
+                button_resume.set_disabled(false);
+                let (row, col) = row_and_col(dimensions, cell);
+                div_announcement.set_text_content(Some(&format!(
+                    "paused at a decision in row {}, column {}, with {choices} ways on",
+                    row + 1,
+                    col + 1
+                )));
+            }
+        }
+        if input_sound.checked() {
+            // the last cell changed by the step is heard
+            let cell = self
+                .timeline
+                .borrow()
+                .solution
+                .last_changes()
+                .last()
+                .map(|(cell, _, _)| cell);
+            if let Some(cell) = cell {
+                let mut sonification = self.sonification.borrow_mut();
+                if sonification.is_none() {
+                    *sonification = Some(Sonification::new()?);
+                }
+                if let Some(sonification) = sonification.as_mut() {
+                    sonification.note(distance_pitch(dimensions, *from, cell));
+                }
+            }
+        }
+        if continuing == Ok(true) {
+            return Ok(());
+        }
+        enable_timeline(&input_timeline.borrow(), self.timeline.borrow().len());
+        button_replay.borrow().set_disabled(false);
+        if let Some(started) = self.phase_started.borrow_mut().take() {
+            div_solve_time.set_text_content(Some(&elapsed(
+                "solved",
+                started,
+                self.timeline.borrow().solution.len(),
+            )));
+        }
+        let path = solution(&cells, *from, *to);
+        if let Some(embedding) = embedding {
+            embedding.notify("solved", &[("found", path.is_some().into())]);
+        }
+        div_announcement.set_text_content(Some(&match path {
+            Some(_) => {
+                let steps = self.timeline.borrow().solution.len();
+                match self.solver.borrow().outcome() {
+                    Some(outcome) => format!("solution found, {steps} steps; {outcome}"),
+                    None => format!("solution found, {steps} steps"),
+                }
+            }
+            None => match continuing {
+                Err(reason) => format!("no solution found; {reason}"),
+                Ok(_) => "no solution found".to_string(),
+            },
+        }));
+        // a longer solution is compared with the shortest, shown faintly
+        let optimal = path.as_ref().and_then(|path| {
+            self.shortest_path(
+                dimensions,
+                &cells,
+                *from,
+                *to,
+                input_precompute_paths.checked(),
+            )
+            .filter(|shortest| shortest.len() < path.len())
+        });
+        div_solution.set_text_content(
+            path.map(|path| {
+                let description = describe_moves(dimensions, &path);
+                match &optimal {
+                    Some(optimal) => format!(
+                        "Solution of {description}; {}",
+                        optimality(path.len() - 1, optimal.len() - 1)
+                    ),
+                    None => format!("Solution of {description}"),
+                }
+            })
+            .as_deref(),
+        );
+        let mut overlays = self.overlays.borrow_mut();
+        overlays.optimal = optimal.unwrap_or_default();
+        overlays.legs = self.solver.borrow().legs();
+        *phase = match comparing {
+            true => Phase::Compare,
+            false => Phase::Complete,
+        };
+        Ok(())
+    }
+
+    /// Apply a step of the solver compared, moving phase on to complete once it is done.
+    fn step_compare(&self, phase: &mut Phase) {
+        let (from, to) = (self.from.borrow(), self.to.borrow());
+        if !self
+            .comparison
+            .borrow_mut()
+            .as_mut()
+            .is_some_and(|comparison| comparison.step(*self.dimensions.borrow(), *from, *to))
+        {
+            *phase = Phase::Complete;
+        }
+    }
+
+    /// Move the enemies and, if race, apply a step of the solver racing the players. Once a
+    /// player or the solver reaches the goal, show the winner and move phase on to complete.
+    fn step_play(&self, controls: &Controls, phase: &mut Phase, race: bool) {
+        let Controls {
+            div_status,
+            leaderboard,
+            div_leaderboard,
+            div_daily,
+            button_hint,
+            ..
+        } = controls;
+        let (mut cells, from, to) = (
+            self.cells.borrow_mut(),
+            self.from.borrow(),
+            self.to.borrow(),
+        );
+        let dimensions = *self.dimensions.borrow();
+        let mut players = self.players.borrow_mut();
+        for player in players.iter_mut() {
+            if player.patrol(dimensions, &cells, &mut Platform) {
+                div_status.set_text_content(Some("Caught by an enemy; back to the start"));
+            }
+        }
+        let winner = players
+            .iter()
+            .position(|player| player.cell == *to && player.collected());
+        if let Some(winner) = winner {
+            // the time of playing alone is kept among the best for the maze
+            let seconds = self
+                .phase_started
+                .borrow_mut()
+                .take()
+                .filter(|_| players.len() == 1)
+                .map(|started| (now() - started) / 1000.0);
+            let position = match (seconds, self.maze_key.borrow().as_deref()) {
+                (Some(seconds), Some(maze)) => {
+                    let mut leaderboard = leaderboard.borrow_mut();
+                    let position = leaderboard.record(maze, seconds);
+                    div_leaderboard.set_text_content(Some(&leaderboard::describe(
+                        leaderboard.best(maze),
+                        position,
+                    )));
+                    position
+                }
+                _ => None,
+            };
+            let time = seconds
+                .map(|seconds| format!(" in {seconds:.1} s"))
+                .unwrap_or_default();
+            // a result of the daily maze is shared with a link to it
+            let (date, seed) = daily();
+            let daily_key = maze_key((DAILY_DIMENSION, DAILY_DIMENSION), DAILY_GENERATOR, seed);
+            if seconds.is_some() && self.maze_key.borrow().as_ref() == Some(&daily_key) {
+                let data = MazeData::new(dimensions, &cells, *from, *to);
+                div_daily.set_text_content(Some(&format!(
+                    "Daily maze {date}: solved{time} {}",
+                    permalink(&data)
+                )));
+            }
+            let best = match position {
+                Some(0) => ", a new personal best",
+                _ => "",
+            };
+            div_status.set_text_content(Some(&match (players.len(), winner, race) {
+                (1, _, true) => format!("You win{time}{best}!"),
+                (1, _, false) => format!("You reached the goal{time}{best}!"),
+                (_, 0, _) => "The first player wins!".to_string(),
+                _ => "The second player wins!".to_string(),
+            }));
+            ui::highlight(div_status, position == Some(0));
+            button_hint.borrow().set_disabled(true);
+            *phase = Phase::Complete;
+        } else if race {
+            let mut solver = self.solver.borrow_mut();
+            match solver.step(dimensions, &mut cells, *from, *to) {
+                StepResult::Continue { .. } => {}
+                StepResult::Finished => {
+                    div_status.set_text_content(Some("The solver wins!"));
+                    *self.phase_started.borrow_mut() = None;
+                    button_hint.borrow().set_disabled(true);
+                    *phase = Phase::Complete;
+                }
+                StepResult::Failed(reason) => {
+                    // the solver drops out, leaving the player to finish alone
+                    div_status.set_text_content(Some(&format!("The solver failed; {reason}")));
+                    *phase = Phase::Play { race: false };
+                }
+            }
+        }
+    }
+
+    /// Replace the maze with that of data, clearing what was shown of the previous maze.
+    fn import(&self, controls: &Controls, data: &MazeData) -> Result<(), Error> {
+        let Controls {
+            button_hint,
+            button_replay,
+            input_timeline,
+            div_status,
+            div_generate_time,
+            div_solve_time,
+            div_solution,
+            div_comparison,
+            div_overlays,
+            div_leaderboard,
+            div_daily,
+            input_width,
+            input_height,
+            context,
+            input_precompute_paths,
+            div_difficulty,
+            div_fingerprint,
+            select_solver,
+            button_solver,
+            button_play,
+            button_compare,
+            div_announcement,
+            ..
+        } = controls;
+        button_hint.borrow().set_disabled(true);
+        button_replay.borrow().set_disabled(true);
+        input_timeline.borrow().set_disabled(true);
+        div_status.set_text_content(None);
+        div_generate_time.set_text_content(None);
+        div_solve_time.set_text_content(None);
+        div_solution.set_text_content(None);
+        div_comparison.set_hidden(true);
+        *self.comparison.borrow_mut() = None;
+        self.players.borrow_mut().clear();
+        *self.overlays.borrow_mut() = Overlays {
+            notes: data.notes.clone(),
+            ..Overlays::default()
+        };
+        div_overlays.set_text_content(None);
+        *self.timeline.borrow_mut() = Timeline::default();
+        *self.phase_started.borrow_mut() = None;
+        *self.seed.borrow_mut() = None;
+        *self.maze_key.borrow_mut() = None;
+        ui::highlight(div_status, false);
+        div_leaderboard.set_text_content(None);
+        div_daily.set_text_content(None);
+        let mut dimensions = self.dimensions.borrow_mut();
+        *dimensions = data.dimensions;
+        input_width.set_value(&dimensions.0.to_string());
+        input_height.set_value(&dimensions.1.to_string());
+        {
+            let context = context.borrow();
+            let canvas = context.canvas().ok_or("the maze has no canvas")?;
+            size_canvas(&canvas, &context, *dimensions)?;
+            let theme = self.theme.borrow();
+            theme.apply(&context);
+            theme.fit(&canvas, *dimensions);
+        }
+        let mut cells = self.cells.borrow_mut();
+        *cells = data.cells();
+        (*self.from.borrow_mut(), *self.to.borrow_mut()) = (data.from, data.to);
+        let precompute = input_precompute_paths.checked();
+        let path = self.shortest_path(*dimensions, &cells, data.from, data.to, precompute);
+        div_difficulty.set_text_content(Some(&statistics(*dimensions, &cells, path.as_deref())));
+        div_fingerprint.set_text_content(Some(&format!("fingerprint {}", data.fingerprint())));
+        *self.solver.borrow_mut() = create_solver(
+            &self.solvers.borrow(),
+            &select_solver.borrow().value(),
+            &self.solver_parameters,
+        )?;
+        button_solver.borrow().set_disabled(false);
+        button_play.borrow().set_disabled(false);
+        button_compare.borrow().set_disabled(false);
+        div_announcement.set_text_content(Some(&format!(
+            "maze imported, {} by {} cells",
+            dimensions.0, dimensions.1
+        )));
+        Ok(())
+    }
+
+    /// Finish a recording of the maze generated or solved, unless in demo mode, where the
+    /// restart of the demo is timed instead.
+    fn complete(&self, controls: &Controls, frames: &mut Frames) {
+        let Controls {
+            input_demo,
+            button_record,
+            button_stop_recording,
+            div_playback,
+            link_recording,
+            ..
+        } = controls;
+        if !input_demo.checked()
+            && self
+                .recorder
+                .borrow()
+                .as_ref()
+                .is_some_and(|recorder| recorder.stepped)
+        {
+            stop_recording(
+                self,
+                button_record,
+                button_stop_recording,
+                div_playback,
+                link_recording,
+            );
+        }
+        frames.demo_restart = match input_demo.checked() {
+            true => frames
+                .demo_restart
+                .or(Some(Date::now() + DEMO_PAUSE_MILLISECONDS)),
+            false => None,
+        };
+    }
+
+    /// Draw the maze as stepped, with its overlays, any frontier of the generator and the
+    /// players, and any comparison; showing their metrics.
+    fn draw_frame(&self, controls: &Controls) {
+        let Controls {
+            context,
+            div_metrics,
+            context_comparison,
+            div_comparison_metrics,
+            ..
+        } = controls;
+        if let Some(recorder) = self.recorder.borrow_mut().as_mut() {
+            recorder.stepped = true;
+        }
+        // a recording captures the whole canvas, so then every cell is drawn
+        let context = context.borrow();
+        let cull = self.recorder.borrow().is_none();
+        let theme = self.theme.borrow();
+        let dimensions = *self.dimensions.borrow();
+        draw(&context, dimensions, &self.cells.borrow(), &theme, cull);
+        self.overlays.borrow().draw(&context, dimensions);
+        if matches!(*self.phase.borrow(), Phase::Generate) {
+            draw_frontier(&context, dimensions, self.generator.borrow().frontier());
+        }
+        draw_players(&context, dimensions, &self.players.borrow());
+        let timeline = self.timeline.borrow();
+        div_metrics.set_text_content(
+            match timeline.solution.is_empty() {
+                true => None,
+                false => Some(summary(timeline.solution.len(), &self.cells.borrow())),
+            }
+            .as_deref(),
+        );
+        if let Some(comparison) = self.comparison.borrow().as_ref() {
+            draw(
+                context_comparison,
+                dimensions,
+                &comparison.cells,
+                &theme,
+                true,
+            );
+            div_comparison_metrics.set_text_content(Some(&comparison.summary()));
+        }
+    }
+}
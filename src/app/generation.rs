@@ -0,0 +1,473 @@
+use super::controls::Controls;
+use super::{
+    create_generator, daily, memory_estimate, now, parameterised, parse_dimension, reporting,
+    select_random, select_value, size_canvas, window, App, Overlays, Phase, DAILY_DIMENSION,
+    DAILY_GENERATOR, DEFAULT_CONFIRM_CELLS, DEFAULT_HEIGHT, DEFAULT_MEMORY_BUDGET,
+    DEFAULT_SHUFFLE_SWAPS, DEFAULT_WIDTH, MEGABYTE, SURPRISE_DIMENSIONS,
+};
+use crate::generate::morph::Morph;
+use crate::generate::shuffle::Shuffle;
+use crate::generate::{Generator, Kruskal};
+use crate::leaderboard::maze_key;
+use crate::platform::random;
+use crate::recording::Timeline;
+use crate::rng::{Platform, Xorshift};
+use crate::{platform, ui, Cells};
+
+use wasm_bindgen::prelude::*;
+use web_sys::Event;
+
+use std::rc::Rc;
+
+/// Start changing the maze of app with generator, announced as announcement; what was shown
+/// of the previous maze is cleared and its recording ended, with the maze as last recorded.
+fn begin_maze_change(
+    app: &App,
+    controls: &Controls,
+    phase: &mut Phase,
+    announcement: &str,
+    generator: Box<dyn Generator>,
+) {
+    let Controls {
+        button_solver,
+        button_play,
+        button_hint,
+        button_replay,
+        button_compare,
+        input_timeline,
+        div_status,
+        div_leaderboard,
+        div_daily,
+        div_difficulty,
+        div_fingerprint,
+        div_generate_time,
+        div_solve_time,
+        div_announcement,
+        div_solution,
+        div_comparison,
+        div_overlays,
+        ..
+    } = controls;
+    for button in [
+        button_solver,
+        button_play,
+        button_hint,
+        button_replay,
+        button_compare,
+    ] {
+        button.borrow().set_disabled(true);
+    }
+    input_timeline.borrow().set_disabled(true);
+    div_status.set_text_content(None);
+    ui::highlight(div_status, false);
+    for text in [
+        div_leaderboard,
+        div_daily,
+        div_difficulty,
+        div_fingerprint,
+        div_generate_time,
+        div_solve_time,
+        div_solution,
+        div_overlays,
+    ] {
+        text.set_text_content(None);
+    }
+    div_announcement.set_text_content(Some(announcement));
+    div_comparison.set_hidden(true);
+    *app.comparison.borrow_mut() = None;
+    app.players.borrow_mut().clear();
+    *app.overlays.borrow_mut() = Overlays::default();
+    let mut cells = app.cells.borrow_mut();
+    let mut timeline = app.timeline.borrow_mut();
+    timeline.seek_end(&mut cells);
+    *timeline = Timeline::default();
+    cells.reset_solution(*app.from.borrow(), *app.to.borrow());
+    *app.generator.borrow_mut() = generator;
+    *phase = Phase::Generate;
+    *app.phase_started.borrow_mut() = Some(now());
+}
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    show_generator_parameters(controls)?;
+    show_memory_estimate(controls)?;
+    surprise_button(controls)?;
+    daily_button(controls)?;
+    morph_button(app, controls)?;
+    generate_button(app, controls)?;
+    complete_button(app, controls)?;
+    shuffle_button(app, controls)?;
+    Ok(())
+}
+
+/// Show the parameters of the generator on change of generator.
+fn show_generator_parameters(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        generator_parameters,
+        select_generator,
+        ..
+    } = &**controls;
+    let select_generator_clone = select_generator.clone();
+    let generator_parameters = generator_parameters.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        generator_parameters.show(&select_generator_clone.borrow().value());
+    });
+    select_generator
+        .borrow()
+        .add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Show the memory estimate on change of the dimensions.
+fn show_memory_estimate(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        div_memory,
+        input_height,
+        input_width,
+        ..
+    } = &**controls;
+    let targets = [input_width.clone(), input_height.clone()];
+    let input_width = input_width.clone();
+    let input_height = input_height.clone();
+    let div_memory = div_memory.clone();
+    let show_estimate = move || {
+        let (width, _) = parse_dimension(&input_width, "width", DEFAULT_WIDTH as usize);
+        let (height, _) = parse_dimension(&input_height, "height", DEFAULT_HEIGHT as usize);
+        let estimate = memory_estimate((width, height)).div_ceil(MEGABYTE);
+        div_memory.set_text_content(Some(&format!("needs about {estimate} MB")));
+    };
+    show_estimate();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| show_estimate());
+    for target in targets {
+        target.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
+    }
+    closure.forget();
+    Ok(())
+}
+
+/// Surprise button behaviour.
+fn surprise_button(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_generator,
+        button_surprise,
+        input_auto_solve,
+        input_height,
+        input_seed,
+        input_width,
+        select_generator,
+        select_solver,
+        ..
+    } = &**controls;
+    let input_width = input_width.clone();
+    let input_height = input_height.clone();
+    let input_seed = input_seed.clone();
+    let input_auto_solve = input_auto_solve.clone();
+    let select_generator = select_generator.clone();
+    let select_solver = select_solver.clone();
+    let button_generator = button_generator.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        input_seed.set_value("");
+        for input in [&input_width, &input_height] {
+            let dimension = SURPRISE_DIMENSIONS.start()
+                + (random() * SURPRISE_DIMENSIONS.clone().count() as f64) as usize;
+            input.set_value(&dimension.to_string());
+        }
+        select_random(&select_generator.borrow());
+        select_random(&select_solver.borrow());
+        input_auto_solve.set_checked(true);
+        button_generator.click();
+    });
+    button_surprise.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Daily maze button behaviour; the same size, generator and seed for everyone each day,
+/// left to be played.
+fn daily_button(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_daily,
+        button_generator,
+        input_auto_solve,
+        input_height,
+        input_seed,
+        input_width,
+        select_generator,
+        ..
+    } = &**controls;
+    let input_width = input_width.clone();
+    let input_height = input_height.clone();
+    let input_seed = input_seed.clone();
+    let input_auto_solve = input_auto_solve.clone();
+    let select_generator = select_generator.clone();
+    let button_generator = button_generator.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let (_, seed) = daily();
+        input_seed.set_value(&seed.to_string());
+        for input in [&input_width, &input_height] {
+            input.set_value(&DAILY_DIMENSION.to_string());
+        }
+        select_value(&select_generator.borrow(), DAILY_GENERATOR);
+        input_auto_solve.set_checked(false);
+        button_generator.click();
+    });
+    button_daily.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Morph button behaviour; the maze changes a passage at a time into that of the seed with
+/// the selected generator, staying perfect as it changes.
+fn morph_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_morph,
+        div_error,
+        generator_parameters,
+        generators,
+        input_morph_seed,
+        input_seed,
+        select_generator,
+        ..
+    } = &**controls;
+    let input_morph_seed = input_morph_seed.clone();
+    let generators = generators.clone();
+    let generator_parameters = generator_parameters.clone();
+    let select_generator = select_generator.clone();
+    let input_seed = input_seed.clone();
+    let controls = controls.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(reporting(div_error, move |_: Event| {
+        let mut phase = app.phase.borrow_mut();
+        if matches!(*phase, Phase::Import(_)) {
+            return Ok(());
+        }
+        let dimensions = *app.dimensions.borrow();
+        let seed = input_morph_seed
+            .value()
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| platform::random_seed());
+        input_morph_seed.set_placeholder(&format!("random (last {seed})"));
+        let generator = select_generator.borrow().value();
+        generator_parameters.show(&generator);
+        let values = generator_parameters.values(&generator);
+        let mut target = Cells::new(dimensions);
+        let mut create = create_generator(
+            &generators,
+            &generator,
+            Box::new(Xorshift::new(seed)),
+            &values,
+        )?;
+        while create.step(dimensions, &mut target).is_continue() {}
+        begin_maze_change(
+            &app,
+            &controls,
+            &mut phase,
+            "morphing maze",
+            Box::new(Morph::new(Box::new(Platform), target)),
+        );
+        // once morphed, the maze is that of the seed
+        input_seed.set_value(&seed.to_string());
+        *app.seed.borrow_mut() = Some(seed);
+        *app.maze_key.borrow_mut() = Some(maze_key(
+            dimensions,
+            &parameterised(&generators, &generator, &values),
+            seed,
+        ));
+        app.animate();
+        Ok(())
+    }));
+    button_morph.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Generate button behaviour.
+fn generate_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_generator,
+        context,
+        div_error,
+        div_size,
+        generator_parameters,
+        generators,
+        input_confirm_cells,
+        input_height,
+        input_memory_budget,
+        input_seed,
+        input_width,
+        select_generator,
+        ..
+    } = &**controls;
+    let div_size = div_size.clone();
+    let generators = generators.clone();
+    let input_confirm_cells = input_confirm_cells.clone();
+    let input_memory_budget = input_memory_budget.clone();
+    let generator_parameters = generator_parameters.clone();
+    let input_width = input_width.clone();
+    let input_height = input_height.clone();
+    let input_seed = input_seed.clone();
+    let context = context.clone();
+    let select_generator = select_generator.clone();
+    let controls = controls.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(reporting(div_error, move |_: Event| {
+        let mut dimensions = app.dimensions.borrow_mut();
+        let (width, width_message) = parse_dimension(&input_width, "width", dimensions.0);
+        let (height, height_message) = parse_dimension(&input_height, "height", dimensions.1);
+        input_width.set_value(width.to_string().as_str());
+        input_height.set_value(height.to_string().as_str());
+        div_size.set_text_content(
+            match (width_message, height_message) {
+                (Some(width), Some(height)) => Some(format!("{width}; {height}")),
+                (message, None) | (None, message) => message,
+            }
+            .as_deref(),
+        );
+
+        // refuse mazes needing more memory than the budget, which may crash the page
+        let (estimate, budget) = (
+            memory_estimate((width, height)),
+            input_memory_budget
+                .value()
+                .parse()
+                .unwrap_or(DEFAULT_MEMORY_BUDGET),
+        );
+        if estimate > budget.saturating_mul(MEGABYTE) {
+            div_size.set_text_content(Some(&format!(
+                "a maze of {width} by {height} cells needs about {} MB, more than the budget of {budget} MB",
+                estimate.div_ceil(MEGABYTE)
+            )));
+            return Ok(());
+        }
+
+        let confirm_cells = input_confirm_cells
+            .value()
+            .parse()
+            .unwrap_or(DEFAULT_CONFIRM_CELLS);
+        if width.saturating_mul(height) > confirm_cells
+            && !window()
+                .confirm_with_message(&format!(
+                    "A maze of {} cells may take a lot of memory and time to generate. Continue?",
+                    width.saturating_mul(height)
+                ))
+                .unwrap_or(false)
+        {
+            return Ok(());
+        }
+
+        let mut phase = app.phase.borrow_mut();
+        // the same seed generates the same maze, so show a random seed to repeat it
+        let seed = input_seed
+            .value()
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| platform::random_seed());
+        input_seed.set_placeholder(&format!("random (last {seed})"));
+        let generator = select_generator.borrow().value();
+        // the controls of another generator may be shown if it was selected for the user
+        generator_parameters.show(&generator);
+        let values = generator_parameters.values(&generator);
+        begin_maze_change(
+            &app,
+            &controls,
+            &mut phase,
+            "generating maze",
+            create_generator(
+                &generators,
+                &generator,
+                Box::new(Xorshift::new(seed)),
+                &values,
+            )?,
+        );
+        *dimensions = (width, height);
+        {
+            let context = context.borrow();
+            let canvas = context.canvas().ok_or("the maze has no canvas")?;
+            size_canvas(&canvas, &context, *dimensions)?;
+            let theme = app.theme.borrow();
+            theme.apply(&context);
+            theme.fit(&canvas, *dimensions);
+        }
+        *app.cells.borrow_mut() = Cells::new(*dimensions);
+        *app.seed.borrow_mut() = Some(seed);
+        *app.maze_key.borrow_mut() = Some(maze_key(
+            *dimensions,
+            &parameterised(&generators, &generator, &values),
+            seed,
+        ));
+        app.animate();
+        Ok(())
+    }));
+    button_generator.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Complete maze button behaviour; the passages of the maze, as generated, imported or
+/// partly generated, are kept and joined into a whole maze.
+fn complete_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_complete, ..
+    } = &**controls;
+    let controls = controls.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let mut phase = app.phase.borrow_mut();
+        if matches!(*phase, Phase::Import(_)) {
+            return;
+        }
+        begin_maze_change(
+            &app,
+            &controls,
+            &mut phase,
+            "completing maze",
+            Box::new(Kruskal::new(Box::new(Platform))),
+        );
+        // the maze is no longer that of a seed
+        *app.seed.borrow_mut() = None;
+        *app.maze_key.borrow_mut() = None;
+        app.animate();
+    });
+    button_complete.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Shuffle button behaviour; random passages of the maze are swapped for walls, keeping it
+/// perfect without generating it again.
+fn shuffle_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_shuffle,
+        input_shuffle_swaps,
+        ..
+    } = &**controls;
+    let input_shuffle_swaps = input_shuffle_swaps.clone();
+    let controls = controls.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let mut phase = app.phase.borrow_mut();
+        if matches!(*phase, Phase::Import(_)) {
+            return;
+        }
+        let swaps = input_shuffle_swaps
+            .value()
+            .parse()
+            .unwrap_or(DEFAULT_SHUFFLE_SWAPS);
+        begin_maze_change(
+            &app,
+            &controls,
+            &mut phase,
+            "shuffling maze",
+            Box::new(Shuffle::new(Box::new(Platform), swaps)),
+        );
+        // the maze is no longer that of a seed
+        *app.seed.borrow_mut() = None;
+        *app.maze_key.borrow_mut() = None;
+        app.animate();
+    });
+    button_shuffle.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
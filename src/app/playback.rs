@@ -0,0 +1,203 @@
+use super::controls::Controls;
+use super::{
+    animation, download_bytes, draw, stop_recording, App, Phase, DEFAULT_ANIMATION_DELAY,
+    DEFAULT_ANIMATION_STEPS,
+};
+use crate::video::VideoRecorder;
+
+use wasm_bindgen::prelude::*;
+use web_sys::Event;
+
+use std::rc::Rc;
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    record_button(app, controls)?;
+    stop_recording_button(app, controls)?;
+    export_animation_button(app, controls)?;
+    replay_button(app, controls)?;
+    timeline(app, controls)?;
+    demo(app, controls)?;
+    Ok(())
+}
+
+/// Record button behaviour.
+fn record_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_record,
+        button_stop_recording,
+        context,
+        div_playback,
+        ..
+    } = &**controls;
+    let canvas = context.borrow().canvas().ok_or("should have canvas")?;
+    let app = app.clone();
+    let button_record = button_record.clone();
+    let button_stop_recording = button_stop_recording.clone();
+    let div_playback = div_playback.clone();
+    let target = button_record.clone();
+    let closure =
+        Closure::<dyn FnMut(_)>::new(move |_: Event| match VideoRecorder::start(&canvas) {
+            Ok(recorder) => {
+                *app.recorder.borrow_mut() = Some(recorder);
+                button_record.set_disabled(true);
+                button_stop_recording.set_disabled(false);
+                div_playback.set_text_content(Some("recording; stops when the maze is complete"));
+            }
+            Err(error) => div_playback.set_text_content(Some(&format!(
+                "could not record: {}",
+                error.as_string().unwrap_or_default()
+            ))),
+        });
+    target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Stop recording button behaviour.
+fn stop_recording_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_record,
+        button_stop_recording,
+        div_playback,
+        link_recording,
+        ..
+    } = &**controls;
+    let app = app.clone();
+    let button_record = button_record.clone();
+    let button_stop_recording = button_stop_recording.clone();
+    let div_playback = div_playback.clone();
+    let link_recording = link_recording.clone();
+    let target = button_stop_recording.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        stop_recording(
+            &app,
+            &button_record,
+            &button_stop_recording,
+            &div_playback,
+            &link_recording,
+        );
+    });
+    target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Export animation button behaviour.
+fn export_animation_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_export_animation,
+        div_playback,
+        input_animation_delay,
+        input_animation_steps,
+        ..
+    } = &**controls;
+    let input_animation_delay = input_animation_delay.clone();
+    let input_animation_steps = input_animation_steps.clone();
+    let app = app.clone();
+    let div_playback = div_playback.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let steps = input_animation_steps
+            .value()
+            .parse()
+            .unwrap_or(DEFAULT_ANIMATION_STEPS)
+            .max(1);
+        let delay = input_animation_delay
+            .value()
+            .parse()
+            .unwrap_or(DEFAULT_ANIMATION_DELAY);
+        div_playback.set_text_content(Some(&match animation(&app, steps, delay) {
+            None => "nothing to animate; generate a maze first".to_string(),
+            Some((png, frames)) => match download_bytes("maze-animation.png", &png, "image/apng") {
+                Ok(()) => format!("exported animation of {frames} frames"),
+                Err(_) => "could not export animation".to_string(),
+            },
+        }));
+    });
+    button_export_animation
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Replay button behaviour.
+fn replay_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_replay,
+        div_comparison,
+        input_timeline,
+        ..
+    } = &**controls;
+    let target = button_replay.clone();
+    let app = app.clone();
+    let button_replay = button_replay.clone();
+    let input_timeline = input_timeline.clone();
+    let div_comparison = div_comparison.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let mut phase = app.phase.borrow_mut();
+        let mut timeline = app.timeline.borrow_mut();
+        if timeline.solution.is_empty() {
+            return;
+        }
+        button_replay.borrow().set_disabled(true);
+        input_timeline.borrow().set_disabled(true);
+        div_comparison.set_hidden(true);
+        *app.comparison.borrow_mut() = None;
+        let mut cells = app.cells.borrow_mut();
+        timeline.seek_end(&mut cells);
+        let (from, to) = (*app.from.borrow(), *app.to.borrow());
+        cells.reset_solution(from, to);
+        app.overlays.borrow_mut().clear_solution();
+        *app.solver.borrow_mut() = Box::new(timeline.solution.replay());
+        timeline.solution.clear();
+        *phase = Phase::Solve;
+        *app.phase_started.borrow_mut() = None;
+        app.animate();
+    });
+    target
+        .borrow()
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Timeline behaviour.
+fn timeline(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        context,
+        input_timeline,
+        ..
+    } = &**controls;
+    let context = context.clone();
+    let target = input_timeline.clone();
+    let input_timeline = input_timeline.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let mut cells = app.cells.borrow_mut();
+        if let Ok(position) = input_timeline.borrow().value().parse() {
+            app.timeline.borrow_mut().seek(&mut cells, position);
+        }
+        draw(
+            &context.borrow(),
+            *app.dimensions.borrow(),
+            &cells,
+            &app.theme.borrow(),
+            true,
+        );
+    });
+    target
+        .borrow()
+        .add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Demo behaviour, which restarts a complete maze and so needs frames.
+fn demo(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls { input_demo, .. } = &**controls;
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| app.animate());
+    input_demo.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
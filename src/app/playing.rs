@@ -0,0 +1,232 @@
+use super::controls::Controls;
+use super::{create_solver, now, reporting, App, Phase};
+use crate::direction::Direction;
+use crate::movement::Movement;
+use crate::play::{mirrored, Enemy, Player};
+use crate::rng::Platform;
+use crate::solve::{scatter, Tour};
+use crate::{leaderboard, ui};
+
+use wasm_bindgen::prelude::*;
+use web_sys::{Event, KeyboardEvent, Node};
+
+use std::rc::Rc;
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    play_button(app, controls)?;
+    hint_button(app, controls)?;
+    keyboard(app, controls)?;
+    Ok(())
+}
+
+/// Play button behaviour.
+fn play_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_hint,
+        button_play,
+        button_replay,
+        div_comparison,
+        div_daily,
+        div_error,
+        div_leaderboard,
+        leaderboard,
+        div_status,
+        input_enemies,
+        input_items,
+        input_race,
+        input_slide,
+        input_timeline,
+        input_two_players,
+        select_solver,
+        ..
+    } = &**controls;
+    let input_enemies = input_enemies.clone();
+    let input_items = input_items.clone();
+    let input_race = input_race.clone();
+    let input_slide = input_slide.clone();
+    let input_two_players = input_two_players.clone();
+    let select_solver = select_solver.clone();
+    let app = app.clone();
+    let button_hint = button_hint.clone();
+    let button_replay = button_replay.clone();
+    let input_timeline = input_timeline.clone();
+    let div_status = div_status.clone();
+    let div_leaderboard = div_leaderboard.clone();
+    let leaderboard = leaderboard.clone();
+    let div_daily = div_daily.clone();
+    let div_comparison = div_comparison.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(reporting(div_error, move |_: Event| {
+        let mut phase = app.phase.borrow_mut();
+        button_hint.borrow().set_disabled(false);
+        button_replay.borrow().set_disabled(true);
+        input_timeline.borrow().set_disabled(true);
+        div_comparison.set_hidden(true);
+        *app.comparison.borrow_mut() = None;
+        let mut cells = app.cells.borrow_mut();
+        let mut timeline = app.timeline.borrow_mut();
+        timeline.seek_end(&mut cells);
+        timeline.solution.clear();
+        let (from, to) = (*app.from.borrow(), *app.to.borrow());
+        cells.reset_solution(from, to);
+        app.overlays.borrow_mut().clear_solution();
+        let movement = match input_slide.checked() {
+            true => Movement::Slide,
+            false => Movement::Step,
+        };
+        // a second player starts across the maze, and instead of the solver races
+        let two_players = input_two_players.checked();
+        let starts = match two_players {
+            true => vec![
+                from,
+                mirrored(*app.dimensions.borrow(), from, &[to])
+                    .ok_or("there is no start for the second player")?,
+            ],
+            false => vec![from],
+        };
+        let items = scatter(
+            &mut Platform,
+            cells.len(),
+            input_items.value().parse().unwrap_or(0),
+            &[&starts[..], &[to]].concat(),
+        );
+        let solver = create_solver(
+            &app.solvers.borrow(),
+            &select_solver.borrow().value(),
+            &app.solver_parameters,
+        )?;
+        *app.solver.borrow_mut() = match items.is_empty() {
+            true => solver,
+            false => Box::new(Tour::new(solver, items.clone())),
+        };
+        let goal = match items.len() {
+            0 => "the goal".to_string(),
+            1 => "the item then the goal".to_string(),
+            count => format!("the {count} items then the goal"),
+        };
+        // enemies alternately follow the wall and walk at random; only when playing alone,
+        // since enemies belong to a player
+        let enemies = match two_players {
+            true => 0,
+            false => input_enemies.value().parse().unwrap_or(0),
+        };
+        let enemies = scatter(&mut Platform, cells.len(), enemies, &[from, to])
+            .into_iter()
+            .enumerate()
+            .map(|(idx, cell)| Enemy::new(cell, idx % 2 == 1))
+            .collect::<Vec<_>>();
+        let mut players = vec![Player::new(from, movement, items.clone(), enemies)];
+        players.extend(
+            starts[1..]
+                .iter()
+                .map(|start| Player::new(*start, movement, items.clone(), Vec::new())),
+        );
+        *app.players.borrow_mut() = players;
+        div_status.set_text_content(Some(&match (two_players, input_race.checked()) {
+            (true, _) => format!(
+                "Race to {goal}; the first player using the arrow keys, the second W, A, S and D"
+            ),
+            (false, true) => format!("Race the solver to {goal} using the arrow keys"),
+            (false, false) => format!("Reach {goal} using the arrow keys"),
+        }));
+        ui::highlight(&div_status, false);
+        div_daily.set_text_content(None);
+        // times are only kept of playing alone
+        let maze_key = app.maze_key.borrow();
+        div_leaderboard.set_text_content(
+            match (two_players, maze_key.as_deref()) {
+                (false, Some(maze)) => {
+                    Some(leaderboard::describe(leaderboard.borrow().best(maze), None))
+                }
+                _ => None,
+            }
+            .as_deref(),
+        );
+        *phase = Phase::Play {
+            race: input_race.checked() && !two_players,
+        };
+        *app.phase_started.borrow_mut() = Some(now());
+        app.animate();
+        Ok(())
+    }));
+    button_play
+        .borrow()
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Hint button behaviour.
+fn hint_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls { button_hint, .. } = &**controls;
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        if let Some(player) = app.players.borrow_mut().first_mut() {
+            player.hint(
+                *app.dimensions.borrow(),
+                &app.cells.borrow(),
+                *app.to.borrow(),
+            );
+        }
+    });
+    button_hint
+        .borrow()
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Keyboard behaviour, when focus is within this instance or, if nothing has focus,
+/// for the first instance.
+fn keyboard(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        builder,
+        div_app,
+        document,
+        ..
+    } = &**controls;
+    let app = app.clone();
+    let div_app = div_app.clone();
+    let first = builder.is_first();
+    let closure = Closure::<dyn FnMut(_)>::new(move |event: KeyboardEvent| {
+        let target = event
+            .target()
+            .and_then(|target| target.dyn_into::<Node>().ok());
+        let unfocused = target.as_ref().is_none_or(|target| {
+            target.node_name().eq_ignore_ascii_case("body") || target.parent_node().is_none()
+        });
+        let directed = div_app.contains(target.as_ref()) || (unfocused && first);
+        if !directed {
+            return;
+        }
+        if let Phase::Play { .. } = *app.phase.borrow() {
+            // the first player uses the arrow keys, the second W, A, S and D
+            let (idx, direction) = match event.key().to_ascii_lowercase().as_str() {
+                "arrowup" => (0, Direction::North),
+                "arrowright" => (0, Direction::East),
+                "arrowdown" => (0, Direction::South),
+                "arrowleft" => (0, Direction::West),
+                "w" => (1, Direction::North),
+                "d" => (1, Direction::East),
+                "s" => (1, Direction::South),
+                "a" => (1, Direction::West),
+                _ => return,
+            };
+            let mut players = app.players.borrow_mut();
+            let Some(player) = players.get_mut(idx) else {
+                return;
+            };
+            // prevent the page from scrolling
+            event.prevent_default();
+            player.step(
+                *app.dimensions.borrow(),
+                &app.cells.borrow(),
+                direction,
+                *app.to.borrow(),
+            );
+        }
+    });
+    document.add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
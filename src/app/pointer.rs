@@ -0,0 +1,447 @@
+use super::controls::Controls;
+use super::{
+    canvas_row_and_col, redraw, statistics, window, App, Marker, Phase, MAXIMUM_NOTE_CHARACTERS,
+};
+use crate::geometry::row_and_col;
+use crate::path::solve_distance;
+use crate::ALL_WALLS;
+
+use wasm_bindgen::prelude::*;
+use web_sys::{Event, MouseEvent, PointerEvent};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    cell_information(app, controls)?;
+    path_preview(app, controls)?;
+    place_waypoints(app, controls)?;
+    exclusive_clicks(controls)?;
+    clear_waypoints_button(app, controls)?;
+    annotate(app, controls)?;
+    drag_markers(app, controls)?;
+    Ok(())
+}
+
+/// Cell information behaviour.
+fn cell_information(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        context,
+        div_cell,
+        input_cell_details,
+        ..
+    } = &**controls;
+    let input_cell_details = input_cell_details.clone();
+    {
+        let app = app.clone();
+        let div_cell = div_cell.clone();
+        let canvas = context.borrow().canvas().ok_or("should have canvas")?;
+        let target = canvas.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: MouseEvent| {
+            let dimensions = *app.dimensions.borrow();
+            let position =
+                canvas_row_and_col(&target, dimensions, event.offset_x(), event.offset_y());
+            // the note of the cell, if any, is shown as the title of the canvas
+            let note = position.and_then(|(row, col)| {
+                app.overlays
+                    .borrow()
+                    .notes
+                    .get(&(row * dimensions.0 + col))
+                    .cloned()
+            });
+            match note {
+                Some(note) => target.set_title(&note),
+                None => target.remove_attribute("title").unwrap_or_default(),
+            }
+            if !input_cell_details.checked() {
+                div_cell.set_text_content(None);
+                return;
+            }
+            let Some((row, col)) = position else {
+                div_cell.set_text_content(None);
+                return;
+            };
+            let idx = row * dimensions.0 + col;
+            let cells = app.cells.borrow();
+            div_cell.set_text_content(Some(&format!(
+                "cell {idx} (row {row}, column {col}), walls {:#06b}, walk {}, distance {}",
+                cells.walls(idx),
+                cells.walks[idx].map_or("-".to_string(), |walk| walk.to_string()),
+                solve_distance(&cells, *app.from.borrow(), idx)
+                    .map_or("-".to_string(), |distance| distance.to_string()),
+            )));
+        });
+        canvas.add_event_listener_with_callback("mousemove", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    {
+        let div_cell = div_cell.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+            div_cell.set_text_content(None);
+        });
+        let canvas = context.borrow().canvas().ok_or("should have canvas")?;
+        canvas.add_event_listener_with_callback("mouseleave", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
+
+/// Path preview behaviour; hovering over a cell of a complete maze shows the shortest path
+/// to it from the from cell.
+fn path_preview(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        context,
+        context_comparison,
+        ..
+    } = &**controls;
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let canvas = context.borrow().canvas().ok_or("should have canvas")?;
+    let target = canvas.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |event: MouseEvent| {
+        let dimensions = *app.dimensions.borrow();
+        let cell = match *app.phase.borrow() {
+            Phase::Complete if event.type_() != "mouseleave" => {
+                canvas_row_and_col(&target, dimensions, event.offset_x(), event.offset_y())
+                    .map(|(row, col)| row * dimensions.0 + col)
+            }
+            _ => None,
+        };
+        let preview = cell.and_then(|cell| {
+            app.paths
+                .borrow_mut()
+                .get(dimensions, &app.cells.borrow(), *app.from.borrow())
+                .path(cell)
+        });
+        let changed = app
+            .overlays
+            .borrow_mut()
+            .preview(preview.unwrap_or_default());
+        if changed {
+            redraw(&app, &context.borrow(), &context_comparison);
+        }
+    });
+    for kind in ["mousemove", "mouseleave"] {
+        canvas.add_event_listener_with_callback(kind, closure.as_ref().unchecked_ref())?;
+    }
+    closure.forget();
+    Ok(())
+}
+
+/// Place waypoints behaviour; clicking a cell adds it as the next waypoint, or removes
+/// it if already one.
+fn place_waypoints(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        context,
+        context_comparison,
+        input_waypoints,
+        ..
+    } = &**controls;
+    let input_waypoints = input_waypoints.clone();
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let canvas = context.borrow().canvas().ok_or("should have canvas")?;
+    let target = canvas.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |event: MouseEvent| {
+        if !input_waypoints.checked() || matches!(*app.phase.borrow(), Phase::Generate) {
+            return;
+        }
+        let dimensions = *app.dimensions.borrow();
+        let Some((row, col)) =
+            canvas_row_and_col(&target, dimensions, event.offset_x(), event.offset_y())
+        else {
+            return;
+        };
+        let cell = row * dimensions.0 + col;
+        if cell == *app.from.borrow() || cell == *app.to.borrow() {
+            return;
+        }
+        {
+            let waypoints = &mut app.overlays.borrow_mut().waypoints;
+            match waypoints.iter().position(|waypoint| *waypoint == cell) {
+                Some(idx) => {
+                    waypoints.remove(idx);
+                }
+                None => waypoints.push(cell),
+            }
+        }
+        redraw(&app, &context.borrow(), &context_comparison);
+    });
+    canvas.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Place waypoints and annotate checkboxes behaviour; a click on a cell does only one.
+fn exclusive_clicks(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        input_annotate,
+        input_waypoints,
+        ..
+    } = &**controls;
+    for (target, other) in [
+        (input_waypoints, input_annotate),
+        (input_annotate, input_waypoints),
+    ] {
+        let (target_clone, other) = (target.clone(), other.clone());
+        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+            if target_clone.checked() {
+                other.set_checked(false);
+            }
+        });
+        target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
+
+/// Clear waypoints button behaviour.
+fn clear_waypoints_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_clear_waypoints,
+        context,
+        context_comparison,
+        ..
+    } = &**controls;
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        app.overlays.borrow_mut().waypoints.clear();
+        redraw(&app, &context.borrow(), &context_comparison);
+    });
+    button_clear_waypoints
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Annotate behaviour; clicking a cell asks for its note, an empty one removing it.
+fn annotate(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        context,
+        context_comparison,
+        input_annotate,
+        ..
+    } = &**controls;
+    let input_annotate = input_annotate.clone();
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let canvas = context.borrow().canvas().ok_or("should have canvas")?;
+    let target = canvas.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |event: MouseEvent| {
+        if !input_annotate.checked() || matches!(*app.phase.borrow(), Phase::Generate) {
+            return;
+        }
+        let dimensions = *app.dimensions.borrow();
+        let Some((row, col)) =
+            canvas_row_and_col(&target, dimensions, event.offset_x(), event.offset_y())
+        else {
+            return;
+        };
+        let cell = row * dimensions.0 + col;
+        let previous = app.overlays.borrow().notes.get(&cell).cloned();
+        let Ok(Some(note)) = window().prompt_with_message_and_default(
+            &format!(
+                "Note for row {}, column {} (empty to remove)",
+                row + 1,
+                col + 1
+            ),
+            previous.as_deref().unwrap_or_default(),
+        ) else {
+            return;
+        };
+        let note = note
+            .trim()
+            .chars()
+            .take(MAXIMUM_NOTE_CHARACTERS)
+            .collect::<String>();
+        {
+            let notes = &mut app.overlays.borrow_mut().notes;
+            match note.is_empty() {
+                true => notes.remove(&cell),
+                false => notes.insert(cell, note),
+            };
+        }
+        redraw(&app, &context.borrow(), &context_comparison);
+    });
+    canvas.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Drag markers behaviour; the from or to marker of a complete maze is dragged to another
+/// carved cell, moving it there and starting the solve again.
+fn drag_markers(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_hint,
+        button_replay,
+        context,
+        context_comparison,
+        div_announcement,
+        div_comparison,
+        div_difficulty,
+        div_solution,
+        div_solve_time,
+        div_status,
+        input_precompute_paths,
+        input_timeline,
+        ..
+    } = &**controls;
+    // marker being dragged, if any, and whether one was just dropped so that the click
+    // ending the drag does nothing else
+    let dragging = Rc::new(RefCell::new(None::<Marker>));
+    let dropped = Rc::new(RefCell::new(false));
+    let canvas = context.borrow().canvas().ok_or("should have canvas")?;
+    {
+        let dragging = dragging.clone();
+        let dropped = dropped.clone();
+        let app = app.clone();
+        let target = canvas.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: PointerEvent| {
+            *dropped.borrow_mut() = false;
+            if !matches!(*app.phase.borrow(), Phase::Complete) {
+                return;
+            }
+            let dimensions = *app.dimensions.borrow();
+            let Some((row, col)) =
+                canvas_row_and_col(&target, dimensions, event.offset_x(), event.offset_y())
+            else {
+                return;
+            };
+            let cell = row * dimensions.0 + col;
+            let marker = match cell {
+                cell if cell == *app.from.borrow() => Marker::From,
+                cell if cell == *app.to.borrow() => Marker::To,
+                _ => return,
+            };
+            *dragging.borrow_mut() = Some(marker);
+            let _ = target.set_pointer_capture(event.pointer_id());
+            let _ = target.style().set_property("cursor", "grabbing");
+            event.prevent_default();
+        });
+        canvas.add_event_listener_with_callback("pointerdown", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    {
+        let dragging = dragging.clone();
+        let dropped = dropped.clone();
+        let button_hint = button_hint.clone();
+        let button_replay = button_replay.clone();
+        let input_timeline = input_timeline.clone();
+        let div_status = div_status.clone();
+        let div_difficulty = div_difficulty.clone();
+        let input_precompute_paths = input_precompute_paths.clone();
+        let div_solution = div_solution.clone();
+        let div_solve_time = div_solve_time.clone();
+        let div_comparison = div_comparison.clone();
+        let context = context.clone();
+        let context_comparison = context_comparison.clone();
+        let app = app.clone();
+        let target = canvas.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: PointerEvent| {
+            let dimensions = *app.dimensions.borrow();
+            let position =
+                canvas_row_and_col(&target, dimensions, event.offset_x(), event.offset_y());
+            let cell = position.map(|(row, col)| row * dimensions.0 + col);
+            let (from, to) = (*app.from.borrow(), *app.to.borrow());
+            let Some(marker) = *dragging.borrow() else {
+                // a marker of a complete maze can be grabbed
+                let grab = matches!(*app.phase.borrow(), Phase::Complete)
+                    && cell.is_some_and(|cell| cell == from || cell == to);
+                let _ = target
+                    .style()
+                    .set_property("cursor", if grab { "grab" } else { "" });
+                return;
+            };
+            // only a carved cell other than that of the other marker is valid; one walled
+            // on every side, as masked out of an imported maze, cannot be reached
+            let Some(cell) = cell.filter(|cell| {
+                let cells = app.cells.borrow();
+                *cell != from
+                    && *cell != to
+                    && cells.walks[*cell].is_some()
+                    && cells.walls(*cell) != ALL_WALLS
+            }) else {
+                return;
+            };
+            {
+                let mut cells = app.cells.borrow_mut();
+                let mut timeline = app.timeline.borrow_mut();
+                timeline.seek_end(&mut cells);
+                timeline.solution.clear();
+                let (from, to) = match marker {
+                    Marker::From => (cell, to),
+                    Marker::To => (from, cell),
+                };
+                (*app.from.borrow_mut(), *app.to.borrow_mut()) = (from, to);
+                cells.reset_solution(from, to);
+                let precompute = input_precompute_paths.checked();
+                let path = app.shortest_path(dimensions, &cells, from, to, precompute);
+                div_difficulty.set_text_content(Some(&statistics(
+                    dimensions,
+                    &cells,
+                    path.as_deref(),
+                )));
+            }
+            app.overlays.borrow_mut().clear_solution();
+            *app.comparison.borrow_mut() = None;
+            div_comparison.set_hidden(true);
+            button_hint.borrow().set_disabled(true);
+            button_replay.borrow().set_disabled(true);
+            input_timeline.borrow().set_disabled(true);
+            div_status.set_text_content(None);
+            div_solution.set_text_content(None);
+            div_solve_time.set_text_content(None);
+            *dropped.borrow_mut() = true;
+            redraw(&app, &context.borrow(), &context_comparison);
+        });
+        canvas.add_event_listener_with_callback("pointermove", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    {
+        let dragging = dragging.clone();
+        let div_announcement = div_announcement.clone();
+        let app = app.clone();
+        let target = canvas.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: PointerEvent| {
+            let Some(marker) = dragging.borrow_mut().take() else {
+                return;
+            };
+            let _ = target.release_pointer_capture(event.pointer_id());
+            let _ = target.style().set_property("cursor", "");
+            let (cell, name) = match marker {
+                Marker::From => (*app.from.borrow(), "start"),
+                Marker::To => (*app.to.borrow(), "goal"),
+            };
+            let (row, col) = row_and_col(*app.dimensions.borrow(), cell);
+            div_announcement.set_text_content(Some(&format!(
+                "{name} in row {}, column {}",
+                row + 1,
+                col + 1
+            )));
+        });
+        for kind in ["pointerup", "pointercancel"] {
+            canvas.add_event_listener_with_callback(kind, closure.as_ref().unchecked_ref())?;
+        }
+        closure.forget();
+    }
+    {
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: Event| {
+            if std::mem::take(&mut *dropped.borrow_mut()) {
+                event.stop_immediate_propagation();
+            }
+        });
+        canvas.add_event_listener_with_callback_and_bool(
+            "click",
+            closure.as_ref().unchecked_ref(),
+            true,
+        )?;
+        closure.forget();
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use super::controls::Controls;
+use super::{fill_library, App, Phase};
+use crate::format::binary::{from_base64, to_base64};
+
+use wasm_bindgen::prelude::*;
+use web_sys::Event;
+
+use std::rc::Rc;
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    save_button(app, controls)?;
+    load_button(app, controls)?;
+    delete_button(controls)?;
+    gallery_thumbnails(app, controls)?;
+    Ok(())
+}
+
+/// Save maze button behaviour.
+fn save_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_save_maze,
+        div_library,
+        input_maze_name,
+        library,
+        select_library,
+        ..
+    } = &**controls;
+    let input_maze_name = input_maze_name.clone();
+    let select_library = select_library.clone();
+    let div_library = div_library.clone();
+    let library = library.clone();
+    let controls = controls.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(data) = app.capture(&controls) else {
+            div_library.set_text_content(Some("the maze is not yet generated"));
+            return;
+        };
+        let mut library = library.borrow_mut();
+        match library.insert(&input_maze_name.value(), to_base64(&data)) {
+            Some(name) => {
+                let _ = fill_library(&select_library, &library);
+                select_library.set_value(&name);
+                div_library.set_text_content(Some(&format!("saved \"{name}\"")));
+            }
+            None => div_library.set_text_content(Some("could not save; enter a name")),
+        }
+    });
+    button_save_maze.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Load maze button behaviour.
+fn load_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_load_maze,
+        div_library,
+        library,
+        select_library,
+        ..
+    } = &**controls;
+    let select_library = select_library.clone();
+    let div_library = div_library.clone();
+    let library = library.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let name = select_library.value();
+        match library.borrow().get(&name).map(|code| from_base64(code)) {
+            Some(Ok(data)) => {
+                div_library.set_text_content(None);
+                *app.phase.borrow_mut() = Phase::Import(data);
+                app.animate();
+            }
+            Some(Err(message)) => {
+                div_library.set_text_content(Some(&format!("could not load \"{name}\": {message}")))
+            }
+            None => div_library.set_text_content(Some("no maze selected")),
+        }
+    });
+    button_load_maze.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Delete maze button behaviour.
+fn delete_button(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_delete_maze,
+        div_library,
+        library,
+        select_library,
+        ..
+    } = &**controls;
+    let select_library = select_library.clone();
+    let div_library = div_library.clone();
+    let library = library.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let name = select_library.value();
+        let mut library = library.borrow_mut();
+        match library.remove(&name) {
+            true => {
+                let _ = fill_library(&select_library, &library);
+                div_library.set_text_content(Some(&format!("deleted \"{name}\"")));
+            }
+            false => div_library.set_text_content(Some("no maze deleted")),
+        }
+    });
+    button_delete_maze
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Gallery behaviour, restoring the maze of a thumbnail.
+fn gallery_thumbnails(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        gallery,
+        thumbnails,
+        ..
+    } = &**controls;
+    for (idx, (button, _)) in thumbnails.iter().enumerate() {
+        let gallery = gallery.clone();
+        let app = app.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+            if let Some(data) = gallery.borrow().get(idx) {
+                *app.phase.borrow_mut() = Phase::Import(data.clone());
+                app.animate();
+            }
+        });
+        button.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
@@ -0,0 +1,638 @@
+use super::controls::Controls;
+use super::{
+    copy_to_clipboard, download, download_url, draw, draw_qr_code, image_pixels, permalink, render,
+    render_tile, window, App, Phase, DEFAULT_IMAGE_THRESHOLD, DEFAULT_PRINT_SCALE,
+    MAXIMUM_PRINT_SCALE, MAZE_FILE_EXTENSION, PRINT_FILENAME,
+};
+use crate::embed::Embedding;
+use crate::format::ascii::to_ascii;
+use crate::format::binary::{from_base64, to_base64};
+use crate::format::dot::to_dot;
+use crate::format::image::{from_pixels, ImageOptions};
+use crate::format::json::{from_json, to_json};
+use crate::format::poster::{tiles, DEFAULT_TILE_CELLS, DEFAULT_TILE_OVERLAP};
+use crate::format::solution::{to_coordinates, to_moves};
+use crate::format::svg::to_svg_tile;
+use crate::format::MazeData;
+use crate::path::shortest_path;
+use crate::qr::QrCode;
+use crate::ui;
+
+use js_sys::{Array, Reflect};
+use wasm_bindgen::prelude::*;
+use web_sys::{Event, HtmlImageElement, MessageEvent, Url};
+
+use std::rc::Rc;
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    export_buttons(app, controls)?;
+    qr_code_button(app, controls)?;
+    download_button(app, controls)?;
+    print_button(app, controls)?;
+    worksheet_button(app, controls)?;
+    poster_buttons(app, controls)?;
+    after_printing(controls)?;
+    upload(app, controls)?;
+    image(app, controls)?;
+    import_buttons(app, controls)?;
+    copy_button(controls)?;
+    embedding_commands(app, controls)?;
+    Ok(())
+}
+
+impl App {
+    /// Capture of the complete maze for export; None if not yet generated.
+    pub(super) fn capture(&self, controls: &Controls) -> Option<MazeData> {
+        if matches!(*self.phase.borrow(), Phase::Generate | Phase::Import(_)) {
+            return None;
+        }
+        let mut cells = self.cells.borrow_mut();
+        let mut timeline = self.timeline.borrow_mut();
+        timeline.seek_end(&mut cells);
+        controls
+            .input_timeline
+            .borrow()
+            .set_value(&timeline.len().to_string());
+        draw(
+            &controls.context.borrow(),
+            *self.dimensions.borrow(),
+            &cells,
+            &self.theme.borrow(),
+            true,
+        );
+        let mut data = MazeData::new(
+            *self.dimensions.borrow(),
+            &cells,
+            *self.from.borrow(),
+            *self.to.borrow(),
+        );
+        data.notes = self.overlays.borrow().notes.clone();
+        Some(data)
+    }
+}
+
+/// Export buttons behaviour.
+fn export_buttons(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_copy_ascii,
+        button_copy_json,
+        button_copy_link,
+        button_export_code,
+        button_export_dot,
+        button_export_json,
+        button_export_moves,
+        button_export_path,
+        div_share,
+        textarea_maze,
+        ..
+    } = &**controls;
+    for (button, encode, copy) in [
+        (
+            button_export_json,
+            (|data| Ok(to_json(data))) as fn(&MazeData) -> Result<String, String>,
+            false,
+        ),
+        (button_export_code, |data| Ok(to_base64(data)), false),
+        (button_copy_link, |data| Ok(permalink(data)), true),
+        (button_copy_ascii, |data| Ok(to_ascii(data)), true),
+        (button_copy_json, |data| Ok(to_json(data)), true),
+        (button_export_path, to_coordinates, false),
+        (button_export_moves, to_moves, false),
+        (button_export_dot, |data| Ok(to_dot(data)), false),
+    ] {
+        let controls = controls.clone();
+        let textarea_maze = textarea_maze.clone();
+        let div_share = div_share.clone();
+        let app = app.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+            let Some(data) = app.capture(&controls) else {
+                div_share.set_text_content(Some("the maze is not yet generated"));
+                return;
+            };
+            let text = match encode(&data) {
+                Ok(text) => text,
+                Err(message) => {
+                    div_share.set_text_content(Some(&message));
+                    return;
+                }
+            };
+            div_share.set_text_content(None);
+            textarea_maze.set_value(&text);
+            if copy {
+                copy_to_clipboard(&text, &div_share);
+            }
+        });
+        button.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
+
+/// QR code button behaviour.
+fn qr_code_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_qr_code,
+        canvas_qr_code,
+        div_share,
+        ..
+    } = &**controls;
+    let canvas_qr_code = canvas_qr_code.clone();
+    let controls = controls.clone();
+    let div_share = div_share.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(data) = app.capture(&controls) else {
+            div_share.set_text_content(Some("the maze is not yet generated"));
+            return;
+        };
+        match QrCode::encode(permalink(&data).as_bytes()) {
+            Ok(code) => {
+                div_share.set_text_content(None);
+                let _ = draw_qr_code(&canvas_qr_code, &code);
+                canvas_qr_code.set_hidden(false);
+            }
+            Err(message) => {
+                div_share.set_text_content(Some(&format!("could not show QR code: {message}")));
+                canvas_qr_code.set_hidden(true);
+            }
+        }
+    });
+    button_qr_code.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Download button behaviour.
+fn download_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_download,
+        div_share,
+        ..
+    } = &**controls;
+    let controls = controls.clone();
+    let div_share = div_share.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(data) = app.capture(&controls) else {
+            div_share.set_text_content(Some("the maze is not yet generated"));
+            return;
+        };
+        div_share.set_text_content(
+            download(
+                &format!("maze.{MAZE_FILE_EXTENSION}"),
+                &to_json(&data),
+                "application/json",
+            )
+            .err()
+            .map(|_| "could not download the maze"),
+        );
+    });
+    button_download.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Render for print button behaviour, leaving out the search and solution.
+fn print_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_print,
+        div_share,
+        input_print_scale,
+        ..
+    } = &**controls;
+    let controls = controls.clone();
+    let input_print_scale = input_print_scale.clone();
+    let div_share = div_share.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(mut data) = app.capture(&controls) else {
+            div_share.set_text_content(Some("the maze is not yet generated"));
+            return;
+        };
+        data.solution = None;
+        let scale = input_print_scale
+            .value()
+            .parse()
+            .unwrap_or(DEFAULT_PRINT_SCALE)
+            .clamp(1, MAXIMUM_PRINT_SCALE);
+        let rendered =
+            render(&data, &app.theme.borrow(), scale as f64).and_then(|(canvas, scale)| {
+                download_url(PRINT_FILENAME, &canvas.to_data_url()?)?;
+                Ok(scale)
+            });
+        div_share.set_text_content(Some(&match rendered {
+            Ok(used) if used < scale as f64 => {
+                format!("rendered at {used:.1} times, the most for a maze this large")
+            }
+            Ok(_) => format!("rendered at {scale} times"),
+            Err(_) => "could not render the maze".to_string(),
+        }));
+    });
+    button_print.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Print worksheet button behaviour; the unsolved maze on one page and the solved maze on
+/// the next, each captioned.
+fn worksheet_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_worksheet,
+        div_share,
+        div_worksheet,
+        document,
+        input_print_scale,
+        pages,
+        ..
+    } = &**controls;
+    let controls = controls.clone();
+    let input_print_scale = input_print_scale.clone();
+    let div_share = div_share.clone();
+    let div_worksheet = div_worksheet.clone();
+    let document = document.clone();
+    let pages = pages.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(mut data) = app.capture(&controls) else {
+            div_share.set_text_content(Some("the maze is not yet generated"));
+            return;
+        };
+        let solved = data
+            .solution
+            .take()
+            .or_else(|| shortest_path(data.dimensions, &data.cells(), data.from, data.to));
+        let scale = input_print_scale
+            .value()
+            .parse()
+            .unwrap_or(DEFAULT_PRINT_SCALE)
+            .clamp(1, MAXIMUM_PRINT_SCALE);
+        let (width, height) = data.dimensions;
+        let fingerprint = data.fingerprint();
+        let title = match *app.seed.borrow() {
+            Some(seed) => {
+                format!("Maze {width} by {height}, seed {seed}, fingerprint {fingerprint}")
+            }
+            None => format!("Maze {width} by {height}, fingerprint {fingerprint}"),
+        };
+        let printed = (|| {
+            for (solution, (caption, image)) in [None, solved].into_iter().zip(&pages) {
+                caption.set_text_content(Some(&match solution {
+                    Some(_) => format!("{title}: solution"),
+                    None => title.clone(),
+                }));
+                data.solution = solution;
+                let (canvas, _) = render(&data, &app.theme.borrow(), scale as f64)?;
+                image.set_src(&canvas.to_data_url()?);
+            }
+            ui::set_printing(&document, true)?;
+            div_worksheet.set_hidden(false);
+            window().print()
+        })();
+        div_share.set_text_content(match printed {
+            Ok(()) => None,
+            Err(_) => Some("could not print the worksheet"),
+        });
+    });
+    button_worksheet.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Poster tiles buttons behaviour; each tile of the maze downloaded as a page to print,
+/// leaving out the search and solution.
+fn poster_buttons(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_tiles_png,
+        button_tiles_svg,
+        div_share,
+        input_print_scale,
+        input_tile_height,
+        input_tile_overlap,
+        input_tile_width,
+        ..
+    } = &**controls;
+    for (button, png) in [(&button_tiles_svg, false), (&button_tiles_png, true)] {
+        let controls = controls.clone();
+        let input_print_scale = input_print_scale.clone();
+        let input_tile_width = input_tile_width.clone();
+        let input_tile_height = input_tile_height.clone();
+        let input_tile_overlap = input_tile_overlap.clone();
+        let div_share = div_share.clone();
+        let app = app.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+            let Some(mut data) = app.capture(&controls) else {
+                div_share.set_text_content(Some("the maze is not yet generated"));
+                return;
+            };
+            data.solution = None;
+            let cells = (
+                input_tile_width
+                    .value()
+                    .parse()
+                    .unwrap_or(DEFAULT_TILE_CELLS.0),
+                input_tile_height
+                    .value()
+                    .parse()
+                    .unwrap_or(DEFAULT_TILE_CELLS.1),
+            );
+            let overlap = input_tile_overlap
+                .value()
+                .parse()
+                .unwrap_or(DEFAULT_TILE_OVERLAP);
+            let scale = input_print_scale
+                .value()
+                .parse()
+                .unwrap_or(DEFAULT_PRINT_SCALE)
+                .clamp(1, MAXIMUM_PRINT_SCALE);
+            let tiles = tiles(data.dimensions, cells, overlap);
+            let downloaded = tiles.iter().try_for_each(|tile| match png {
+                true => {
+                    let canvas = render_tile(&data, &app.theme.borrow(), scale as f64, tile)?;
+                    download_url(&tile.filename("png"), &canvas.to_data_url()?)
+                }
+                false => download(
+                    &tile.filename("svg"),
+                    &to_svg_tile(&data, tile),
+                    "image/svg+xml",
+                ),
+            });
+            div_share.set_text_content(Some(&match downloaded {
+                Ok(()) => {
+                    let (rows, cols) = tiles
+                        .last()
+                        .map_or((0, 0), |tile| (tile.row + 1, tile.col + 1));
+                    format!("downloaded {} tiles, {rows} rows of {cols}", tiles.len())
+                }
+                Err(_) => "could not download the poster tiles".to_string(),
+            }));
+        });
+        button.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
+
+/// After printing, show the document again in place of the worksheet.
+fn after_printing(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        div_worksheet,
+        document,
+        ..
+    } = &**controls;
+    let div_worksheet = div_worksheet.clone();
+    let document = document.clone();
+    let closure = Closure::<dyn FnMut()>::new(move || {
+        if !div_worksheet.hidden() {
+            div_worksheet.set_hidden(true);
+            let _ = ui::set_printing(&document, false);
+        }
+    });
+    window().add_event_listener_with_callback("afterprint", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Upload behaviour.
+fn upload(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        div_share,
+        input_upload,
+        ..
+    } = &**controls;
+    let target = input_upload.clone();
+    let div_share = div_share.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(file) = target.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        target.set_value("");
+        let loaded = {
+            let div_share = div_share.clone();
+            let app = app.clone();
+            Closure::<dyn FnMut(_)>::new(move |text: JsValue| {
+                match from_json(&text.as_string().unwrap_or_default()) {
+                    Ok(data) => {
+                        div_share.set_text_content(None);
+                        *app.phase.borrow_mut() = Phase::Import(data);
+                        app.animate();
+                    }
+                    Err(message) => {
+                        div_share.set_text_content(Some(&format!("could not load file: {message}")))
+                    }
+                }
+            })
+        };
+        let failed = {
+            let div_share = div_share.clone();
+            Closure::<dyn FnMut(_)>::new(move |_: JsValue| {
+                div_share.set_text_content(Some("could not read file"));
+            })
+        };
+        let _ = file.text().then2(&loaded, &failed);
+        loaded.forget();
+        failed.forget();
+    });
+    input_upload.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Image behaviour.
+fn image(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        div_share,
+        input_image,
+        input_image_cell_pixels,
+        input_image_threshold,
+        ..
+    } = &**controls;
+    let input_image_cell_pixels = input_image_cell_pixels.clone();
+    let input_image_threshold = input_image_threshold.clone();
+    let target = input_image.clone();
+    let div_share = div_share.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(file) = target.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        target.set_value("");
+        let (Ok(url), Ok(image)) = (
+            Url::create_object_url_with_blob(&file),
+            HtmlImageElement::new(),
+        ) else {
+            div_share.set_text_content(Some("could not read image"));
+            return;
+        };
+        let options = ImageOptions {
+            cell_pixels: match input_image_cell_pixels.value().parse() {
+                Ok(0) | Err(_) => None,
+                Ok(cell_pixels) => Some(cell_pixels),
+            },
+            threshold: input_image_threshold
+                .value()
+                .parse()
+                .unwrap_or(DEFAULT_IMAGE_THRESHOLD),
+        };
+        let loaded = {
+            let image = image.clone();
+            let div_share = div_share.clone();
+            let app = app.clone();
+            let url = url.clone();
+            Closure::<dyn FnMut(_)>::new(move |_: Event| {
+                let _ = Url::revoke_object_url(&url);
+                match image_pixels(&image)
+                    .and_then(|(pixels, size)| from_pixels(&pixels, size, &options))
+                {
+                    Ok(data) => {
+                        div_share.set_text_content(None);
+                        *app.phase.borrow_mut() = Phase::Import(data);
+                        app.animate();
+                    }
+                    Err(message) => div_share
+                        .set_text_content(Some(&format!("could not load image: {message}"))),
+                }
+            })
+        };
+        let failed = {
+            let div_share = div_share.clone();
+            let url = url.clone();
+            Closure::<dyn FnMut(_)>::new(move |_: Event| {
+                let _ = Url::revoke_object_url(&url);
+                div_share.set_text_content(Some("could not read image"));
+            })
+        };
+        image.set_onload(Some(loaded.as_ref().unchecked_ref()));
+        image.set_onerror(Some(failed.as_ref().unchecked_ref()));
+        image.set_src(&url);
+        loaded.forget();
+        failed.forget();
+    });
+    input_image.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Import buttons behaviour.
+fn import_buttons(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_import_code,
+        button_import_json,
+        div_share,
+        textarea_maze,
+        ..
+    } = &**controls;
+    for (button, decode) in [
+        (
+            &button_import_json,
+            from_json as fn(&str) -> Result<MazeData, String>,
+        ),
+        (&button_import_code, from_base64),
+    ] {
+        let textarea_maze = textarea_maze.clone();
+        let div_share = div_share.clone();
+        let app = app.clone();
+        let closure =
+            Closure::<dyn FnMut(_)>::new(move |_: Event| match decode(&textarea_maze.value()) {
+                Ok(data) => {
+                    div_share.set_text_content(None);
+                    *app.phase.borrow_mut() = Phase::Import(data);
+                    app.animate();
+                }
+                Err(message) => {
+                    div_share.set_text_content(Some(&format!("could not import: {message}")))
+                }
+            });
+        button.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
+
+/// Copy button behaviour.
+fn copy_button(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_copy,
+        div_share,
+        textarea_maze,
+        ..
+    } = &**controls;
+    let textarea_maze = textarea_maze.clone();
+    let div_share = div_share.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        copy_to_clipboard(&textarea_maze.value(), &div_share);
+    });
+    button_copy.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Commands from the embedding page, if embedded in an iframe.
+fn embedding_commands(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_generator,
+        button_solver,
+        input_height,
+        input_width,
+        ..
+    } = &**controls;
+    let embedding = Embedding::new();
+    if let Some(embedding) = embedding.clone() {
+        let input_width = input_width.clone();
+        let input_height = input_height.clone();
+        let button_generator = button_generator.clone();
+        let button_solver = button_solver.clone();
+        let controls = controls.clone();
+        let app = app.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: MessageEvent| {
+            let origin = event.origin();
+            if !embedding.allows(&origin) {
+                return;
+            }
+            let data = event.data();
+            let get = |key: &str| Reflect::get(&data, &JsValue::from_str(key)).unwrap_or_default();
+            let command = get("command").as_string().unwrap_or_default();
+            let result = match command.as_str() {
+                "generate" => {
+                    button_generator.click();
+                    Ok(JsValue::UNDEFINED)
+                }
+                "solve" => match button_solver.borrow().disabled() {
+                    true => Err("maze is not generated".to_string()),
+                    false => {
+                        button_solver.borrow().click();
+                        Ok(JsValue::UNDEFINED)
+                    }
+                },
+                "pause" => {
+                    let paused = get("paused").as_bool().unwrap_or(!*app.paused.borrow());
+                    *app.paused.borrow_mut() = paused;
+                    if !paused {
+                        app.animate();
+                    }
+                    Ok(paused.into())
+                }
+                "setSize" => match (get("width").as_f64(), get("height").as_f64()) {
+                    (Some(width), Some(height)) => {
+                        input_width.set_value(&width.to_string());
+                        input_height.set_value(&height.to_string());
+                        button_generator.click();
+                        let dimensions = *app.dimensions.borrow();
+                        Ok(Array::of2(&dimensions.0.into(), &dimensions.1.into()).into())
+                    }
+                    _ => Err("width and height must be numbers".to_string()),
+                },
+                "exportJson" => app
+                    .capture(&controls)
+                    .map(|data| to_json(&data).into())
+                    .ok_or_else(|| "maze is not generated".to_string()),
+                _ => Err(format!("unknown command \"{command}\"")),
+            };
+            embedding.reply(&origin, &command, result);
+        });
+        window().add_event_listener_with_callback("message", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use super::controls::Controls;
+use super::{
+    create_solver, fill_algorithms, now, reporting, select_value, size_canvas, statistics, window,
+    App, Phase,
+};
+use crate::compare::Comparison;
+use crate::platform::random;
+use crate::solve::{self, Tour};
+
+use wasm_bindgen::prelude::*;
+use web_sys::Event;
+
+use std::rc::Rc;
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    show_solver_parameters(app, controls)?;
+    registered_solvers(app, controls)?;
+    solve_button(app, controls)?;
+    compare_button(app, controls)?;
+    resume_button(app, controls)?;
+    Ok(())
+}
+
+/// Show the parameters of the solver on change of solver.
+fn show_solver_parameters(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls { select_solver, .. } = &**controls;
+    let select_solver_clone = select_solver.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        app.solver_parameters
+            .show(&select_solver_clone.borrow().value());
+    });
+    select_solver
+        .borrow()
+        .add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Include solvers registered from JavaScript.
+fn registered_solvers(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        select_comparison,
+        select_solver,
+        ..
+    } = &**controls;
+    let select_solver = select_solver.clone();
+    let select_comparison = select_comparison.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        *app.solvers.borrow_mut() = solve::solvers();
+        for select in [&*select_solver.borrow(), &select_comparison] {
+            let value = select.value();
+            let _ = fill_algorithms(select, &app.solvers.borrow());
+            select_value(select, &value);
+        }
+    });
+    window().add_event_listener_with_callback(
+        solve::SOLVERS_CHANGED_EVENT,
+        closure.as_ref().unchecked_ref(),
+    )?;
+    closure.forget();
+    Ok(())
+}
+
+/// Solve button behaviour.
+fn solve_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_hint,
+        button_replay,
+        button_solver,
+        div_announcement,
+        div_comparison,
+        div_difficulty,
+        div_error,
+        div_solution,
+        div_solve_time,
+        div_status,
+        input_from_to,
+        input_precompute_paths,
+        input_timeline,
+        select_solver,
+        ..
+    } = &**controls;
+    let input_from_to = input_from_to.clone();
+    let select_solver = select_solver.clone();
+    let app = app.clone();
+    let button_hint = button_hint.clone();
+    let button_replay = button_replay.clone();
+    let input_timeline = input_timeline.clone();
+    let div_status = div_status.clone();
+    let div_comparison = div_comparison.clone();
+    let div_difficulty = div_difficulty.clone();
+    let input_precompute_paths = input_precompute_paths.clone();
+    let div_announcement = div_announcement.clone();
+    let div_solution = div_solution.clone();
+    let div_solve_time = div_solve_time.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(reporting(div_error, move |_: Event| {
+        let mut phase = app.phase.borrow_mut();
+        button_hint.borrow().set_disabled(true);
+        button_replay.borrow().set_disabled(true);
+        input_timeline.borrow().set_disabled(true);
+        div_status.set_text_content(None);
+        div_solve_time.set_text_content(None);
+        div_announcement.set_text_content(Some("solving maze"));
+        div_solution.set_text_content(None);
+        div_comparison.set_hidden(true);
+        *app.comparison.borrow_mut() = None;
+        app.players.borrow_mut().clear();
+        let mut cells = app.cells.borrow_mut();
+        let mut timeline = app.timeline.borrow_mut();
+        timeline.seek_end(&mut cells);
+        timeline.solution.clear();
+        let (mut from, mut to) = (app.from.borrow_mut(), app.to.borrow_mut());
+        if input_from_to.checked() {
+            (*from, *to) = (
+                (random() * cells.len() as f64) as usize,
+                (random() * cells.len() as f64) as usize,
+            );
+            while *from == *to {
+                *to = (random() * cells.len() as f64) as usize;
+            }
+            let dimensions = *app.dimensions.borrow();
+            let precompute = input_precompute_paths.checked();
+            let path = app.shortest_path(dimensions, &cells, *from, *to, precompute);
+            div_difficulty.set_text_content(Some(&statistics(dimensions, &cells, path.as_deref())));
+        }
+        cells.reset_solution(*from, *to);
+        let waypoints = {
+            let mut overlays = app.overlays.borrow_mut();
+            overlays.clear_solution();
+            // waypoints at the from or to cells are passed anyway
+            overlays
+                .waypoints
+                .retain(|waypoint| *waypoint != *from && *waypoint != *to);
+            overlays.waypoints.clone()
+        };
+        // the controls of another solver may be shown if it was selected for the user
+        app.solver_parameters.show(&select_solver.borrow().value());
+        let solver = create_solver(
+            &app.solvers.borrow(),
+            &select_solver.borrow().value(),
+            &app.solver_parameters,
+        )?;
+        *app.solver.borrow_mut() = match waypoints.is_empty() {
+            true => solver,
+            false => {
+                div_announcement.set_text_content(Some(&format!(
+                    "solving maze through {} waypoints",
+                    waypoints.len()
+                )));
+                Box::new(Tour::through(solver, waypoints))
+            }
+        };
+        *phase = Phase::Solve;
+        *app.phase_started.borrow_mut() = Some(now());
+        app.animate();
+        Ok(())
+    }));
+    button_solver
+        .borrow()
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Compare button behaviour.
+fn compare_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_compare,
+        button_hint,
+        button_replay,
+        context_comparison,
+        div_comparison,
+        div_error,
+        div_solve_time,
+        div_status,
+        input_timeline,
+        select_comparison,
+        select_solver,
+        ..
+    } = &**controls;
+    let select_comparison = select_comparison.clone();
+    let context_comparison = context_comparison.clone();
+    let select_solver = select_solver.clone();
+    let app = app.clone();
+    let button_hint = button_hint.clone();
+    let button_replay = button_replay.clone();
+    let input_timeline = input_timeline.clone();
+    let div_status = div_status.clone();
+    let div_comparison = div_comparison.clone();
+    let div_solve_time = div_solve_time.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(reporting(div_error, move |_: Event| {
+        let mut phase = app.phase.borrow_mut();
+        button_hint.borrow().set_disabled(true);
+        button_replay.borrow().set_disabled(true);
+        input_timeline.borrow().set_disabled(true);
+        div_status.set_text_content(None);
+        div_solve_time.set_text_content(None);
+        app.players.borrow_mut().clear();
+        let mut cells = app.cells.borrow_mut();
+        let mut timeline = app.timeline.borrow_mut();
+        timeline.seek_end(&mut cells);
+        timeline.solution.clear();
+        let (from, to) = (*app.from.borrow(), *app.to.borrow());
+        cells.reset_solution(from, to);
+        app.overlays.borrow_mut().clear_solution();
+        let solvers = app.solvers.borrow();
+        *app.solver.borrow_mut() = create_solver(
+            &solvers,
+            &select_solver.borrow().value(),
+            &app.solver_parameters,
+        )?;
+        *app.comparison.borrow_mut() = Some(Comparison::new(
+            cells.clone(),
+            create_solver(&solvers, &select_comparison.value(), &app.solver_parameters)?,
+        ));
+        {
+            let dimensions = app.dimensions.borrow();
+            let canvas = context_comparison
+                .canvas()
+                .ok_or("the comparison has no canvas")?;
+            size_canvas(&canvas, &context_comparison, *dimensions)?;
+            let theme = app.theme.borrow();
+            theme.apply(&context_comparison);
+            theme.fit(&canvas, *dimensions);
+        }
+        div_comparison.set_hidden(false);
+        *phase = Phase::Solve;
+        *app.phase_started.borrow_mut() = Some(now());
+        app.animate();
+        Ok(())
+    }));
+    button_compare
+        .borrow()
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Resume button behaviour, continuing from a decision point.
+fn resume_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls { button_resume, .. } = &**controls;
+    let target = button_resume.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        target.set_disabled(true);
+        *app.paused.borrow_mut() = false;
+        app.animate();
+    });
+    button_resume.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
@@ -0,0 +1,283 @@
+use super::controls::Controls;
+use super::{draw, draw_legend, draw_players, redraw, theme, window, App, Overlays, Phase};
+use crate::error::Error;
+use crate::{Cells, Dimensions};
+
+use wasm_bindgen::prelude::*;
+use web_sys::{Element, Event, HtmlImageElement, Url};
+
+use std::rc::Rc;
+
+/// Add the behaviours of an instance.
+pub(super) fn behaviours(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    analysis_buttons(app, controls)?;
+    sound(app, controls)?;
+    theme_controls(app, controls)?;
+    legend(controls)?;
+    diagnostics(controls)?;
+    backdrop(app, controls)?;
+    clear_backdrop_button(app, controls)?;
+    scroll_and_resize(app, controls)?;
+    Ok(())
+}
+
+/// Analysis buttons behaviour, showing or hiding each over the maze.
+fn analysis_buttons(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_heat,
+        button_junctions,
+        button_spine,
+        context,
+        div_overlays,
+        input_timeline,
+        ..
+    } = &**controls;
+    for (button, toggle) in [
+        (
+            button_spine,
+            Overlays::toggle_spine as fn(&mut Overlays, Dimensions, &Cells) -> Option<String>,
+        ),
+        (button_junctions, Overlays::toggle_junctions),
+        (button_heat, Overlays::toggle_heat),
+    ] {
+        let context = context.clone();
+        let input_timeline = input_timeline.clone();
+        let app = app.clone();
+        let div_overlays = div_overlays.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+            if matches!(*app.phase.borrow(), Phase::Generate | Phase::Import(_)) {
+                div_overlays.set_text_content(Some("the maze is not yet generated"));
+                return;
+            }
+            let dimensions = *app.dimensions.borrow();
+            let mut cells = app.cells.borrow_mut();
+            let mut timeline = app.timeline.borrow_mut();
+            timeline.seek_end(&mut cells);
+            input_timeline
+                .borrow()
+                .set_value(&timeline.len().to_string());
+            let mut overlays = app.overlays.borrow_mut();
+            div_overlays.set_text_content(toggle(&mut overlays, dimensions, &cells).as_deref());
+            let context = context.borrow();
+            draw(&context, dimensions, &cells, &app.theme.borrow(), true);
+            overlays.draw(&context, dimensions);
+            draw_players(&context, dimensions, &app.players.borrow());
+        });
+        button.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+    Ok(())
+}
+
+/// Sound behaviour, which falls silent when turned off.
+fn sound(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls { input_sound, .. } = &**controls;
+    let input_sound_clone = input_sound.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        if !input_sound_clone.checked() {
+            *app.sonification.borrow_mut() = None;
+        }
+    });
+    input_sound.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Theme behaviour, redrawing in the new style.
+fn theme_controls(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        context,
+        context_comparison,
+        input_passage_width,
+        input_wall_width,
+        legend_swatches,
+        select_line_cap,
+        select_line_join,
+        ..
+    } = &**controls;
+    let legend_swatches = legend_swatches.clone();
+    let targets: [&Element; 4] = [
+        input_wall_width,
+        input_passage_width,
+        select_line_cap,
+        select_line_join,
+    ];
+    let targets = targets.map(Element::clone);
+    let input_wall_width = input_wall_width.clone();
+    let input_passage_width = input_passage_width.clone();
+    let select_line_cap = select_line_cap.clone();
+    let select_line_join = select_line_join.clone();
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let theme = self::theme(
+            &input_wall_width,
+            &input_passage_width,
+            &select_line_cap,
+            &select_line_join,
+            &app.theme.borrow(),
+        );
+        let dimensions = *app.dimensions.borrow();
+        let context = context.borrow();
+        for context in [&*context, &context_comparison] {
+            theme.apply(context);
+            if let Some(canvas) = context.canvas() {
+                theme.fit(&canvas, dimensions);
+            }
+        }
+        let _ = draw_legend(&legend_swatches, &theme);
+        *app.theme.borrow_mut() = theme;
+        redraw(&app, &context, &context_comparison);
+    });
+    for target in targets {
+        target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    }
+    closure.forget();
+    Ok(())
+}
+
+/// Legend checkbox behaviour.
+fn legend(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        div_legend,
+        input_legend,
+        ..
+    } = &**controls;
+    let div_legend = div_legend.clone();
+    let target = input_legend.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        div_legend.set_hidden(!target.checked());
+    });
+    input_legend.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Diagnostics checkbox behaviour; the rates are shown once measured.
+fn diagnostics(controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        div_diagnostics,
+        input_diagnostics,
+        ..
+    } = &**controls;
+    let target = input_diagnostics.clone();
+    let div_diagnostics = div_diagnostics.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        div_diagnostics.set_hidden(!target.checked());
+    });
+    input_diagnostics
+        .add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Backdrop behaviour, showing the picture within the carved cells once loaded.
+fn backdrop(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_clear_backdrop,
+        context,
+        context_comparison,
+        div_error,
+        input_backdrop,
+        ..
+    } = &**controls;
+    let target = input_backdrop.clone();
+    let button_clear_backdrop = button_clear_backdrop.clone();
+    let div_error = div_error.clone();
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        let Some(file) = target.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        target.set_value("");
+        let (Ok(url), Ok(image)) = (
+            Url::create_object_url_with_blob(&file),
+            HtmlImageElement::new(),
+        ) else {
+            Error::from("could not read picture").report(&div_error);
+            return;
+        };
+        let loaded = {
+            let image = image.clone();
+            let button_clear_backdrop = button_clear_backdrop.clone();
+            let context = context.clone();
+            let context_comparison = context_comparison.clone();
+            let app = app.clone();
+            let url = url.clone();
+            Closure::<dyn FnMut(_)>::new(move |_: Event| {
+                let _ = Url::revoke_object_url(&url);
+                app.theme.borrow_mut().backdrop = Some(image.clone());
+                button_clear_backdrop.set_disabled(false);
+                redraw(&app, &context.borrow(), &context_comparison);
+            })
+        };
+        let failed = {
+            let div_error = div_error.clone();
+            let url = url.clone();
+            Closure::<dyn FnMut(_)>::new(move |_: Event| {
+                let _ = Url::revoke_object_url(&url);
+                Error::from("could not read picture").report(&div_error);
+            })
+        };
+        image.set_onload(Some(loaded.as_ref().unchecked_ref()));
+        image.set_onerror(Some(failed.as_ref().unchecked_ref()));
+        image.set_src(&url);
+        loaded.forget();
+        failed.forget();
+    });
+    input_backdrop.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Clear backdrop button behaviour.
+fn clear_backdrop_button(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        button_clear_backdrop,
+        context,
+        context_comparison,
+        ..
+    } = &**controls;
+    let target = button_clear_backdrop.clone();
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
+        app.theme.borrow_mut().backdrop = None;
+        target.set_disabled(true);
+        redraw(&app, &context.borrow(), &context_comparison);
+    });
+    button_clear_backdrop
+        .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// Scroll and resize behaviour; cells are only drawn within the viewport, so those
+/// coming into view are drawn.
+fn scroll_and_resize(app: &Rc<App>, controls: &Rc<Controls>) -> Result<(), JsValue> {
+    let Controls {
+        context,
+        context_comparison,
+        ..
+    } = &**controls;
+    let context = context.clone();
+    let context_comparison = context_comparison.clone();
+    let app = app.clone();
+    let closure = Closure::<dyn FnMut()>::new(move || {
+        redraw(&app, &context.borrow(), &context_comparison);
+    });
+    // scrolling of any element may move the canvases, so listen when capturing
+    window().add_event_listener_with_callback_and_bool(
+        "scroll",
+        closure.as_ref().unchecked_ref(),
+        true,
+    )?;
+    window().add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
@@ -0,0 +1,304 @@
+// Benchmark harness running the generators and solvers natively and reporting the numbers
+// of steps and timings.
+
+use crate::generate::generators;
+use crate::parallel;
+use crate::parameter::Values;
+use crate::platform::Level;
+use crate::registry::Registered;
+use crate::rng::Xorshift;
+use crate::solve::solvers;
+use crate::{platform, Cells, Dimensions, MINIMUM_DIMENSION};
+
+use std::time::Instant;
+
+// Default number of runs of each algorithm for each size
+const DEFAULT_RUNS: usize = 20;
+
+// Default numbers of cells in each dimension of the square mazes
+const DEFAULT_SIZES: [usize; 3] = [10, 30, 100];
+
+// Percentiles reported in addition to the mean
+const PERCENTILES: [usize; 3] = [50, 90, 99];
+
+const USAGE: &str = "\
+Run each generator and solver a number of times across maze sizes and report the mean and
+percentiles of the numbers of steps and timings.
+
+Usage: maze-bench [OPTIONS]
+
+Options:
+  --runs <NUMBER>       runs of each algorithm for each size [default: 20]
+  --sizes <CELLS,...>   cells in each dimension of the square mazes [default: 10,30,100]
+  --seed <NUMBER>       seed of the first run; each run uses the next [default: 0]
+  --format <FORMAT>     json or csv [default: json]
+  --log <LEVEL>         log off, info, debug or trace messages to standard error
+  --help                show this help";
+
+/// Output formats.
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+/// Options from the command line arguments.
+struct Options {
+    runs: usize,
+    sizes: Vec<usize>,
+    seed: u64,
+    format: Format,
+}
+
+/// Mean and percentiles of a measure over the runs.
+struct Summary {
+    mean: f64,
+    // values at each of `PERCENTILES`
+    percentiles: [f64; PERCENTILES.len()],
+}
+
+impl Summary {
+    /// Summary of values; not empty.
+    fn new(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        // nearest rank
+        let percentile =
+            |percent: usize| values[(percent * values.len()).div_ceil(100).saturating_sub(1)];
+        Self {
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            percentiles: PERCENTILES.map(percentile),
+        }
+    }
+}
+
+/// Measurements of an algorithm for a size.
+struct Measurement {
+    phase: &'static str,
+    algorithm: String,
+    size: usize,
+    steps: Summary,
+    microseconds: Summary,
+}
+
+/// Run the benchmarks with arguments, excluding the program name, and print the results.
+pub fn run(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let Some(options) = parse(args)? else {
+        return Ok(());
+    };
+
+    let mut results = Vec::new();
+    for size in &options.sizes {
+        let dimensions = (*size, *size);
+        for generator in generators().into_values() {
+            let (steps, microseconds) = (0..options.runs)
+                .map(|run| {
+                    let rng = Xorshift::new(options.seed + run as u64);
+                    let mut cells = Cells::new(dimensions);
+                    let mut generator = generator.create(Box::new(rng), &Values::default());
+                    measure(|| generator.step(dimensions, &mut cells).is_continue())
+                })
+                .unzip();
+            results.push(Measurement {
+                phase: "generate",
+                algorithm: generator.name().to_string(),
+                size: *size,
+                steps: Summary::new(steps),
+                microseconds: Summary::new(microseconds),
+            });
+        }
+
+        // each solver is given the same mazes, from the first generator; not being timed,
+        // they are generated in parallel
+        let seeds = (0..options.runs)
+            .map(|run| options.seed + run as u64)
+            .collect::<Vec<_>>();
+        let mazes = parallel::map(&seeds, |seed| (*seed, maze(dimensions, *seed)));
+        for solver in solvers().into_values() {
+            let (steps, microseconds) = mazes
+                .iter()
+                .map(|(seed, maze)| {
+                    let mut cells = maze.clone();
+                    let mut solver =
+                        solver.create(Box::new(Xorshift::new(*seed)), &Values::default());
+                    measure(|| {
+                        solver
+                            .step(dimensions, &mut cells, 0, size * size - 1)
+                            .is_continue()
+                    })
+                })
+                .unzip();
+            results.push(Measurement {
+                phase: "solve",
+                algorithm: solver.name().to_string(),
+                size: *size,
+                steps: Summary::new(steps),
+                microseconds: Summary::new(microseconds),
+            });
+        }
+    }
+
+    match options.format {
+        Format::Json => print!("{}", to_json(&results)),
+        Format::Csv => print!("{}", to_csv(&results)),
+    }
+    Ok(())
+}
+
+/// Number of steps and microseconds taken stepping until done.
+fn measure(mut step: impl FnMut() -> bool) -> (f64, f64) {
+    let start = Instant::now();
+    let mut steps = 1;
+    while step() {
+        steps += 1;
+    }
+    (steps as f64, start.elapsed().as_secs_f64() * 1e6)
+}
+
+/// Cells of a maze from the first generator, with the from and to cells in opposite corners.
+fn maze(dimensions: Dimensions, seed: u64) -> Cells {
+    let mut cells = Cells::new(dimensions);
+    let mut generator = generators()
+        .into_values()
+        .next()
+        .expect("should have generator")
+        .create(Box::new(Xorshift::new(seed)), &Values::default());
+    while generator.step(dimensions, &mut cells).is_continue() {}
+    let to = cells.len() - 1;
+    cells.reset_solution(0, to);
+    cells
+}
+
+/// Results as a JSON array with an object for each algorithm and size.
+fn to_json(results: &[Measurement]) -> String {
+    let summary = |summary: &Summary| {
+        let percentiles = PERCENTILES
+            .iter()
+            .zip(summary.percentiles)
+            .map(|(percent, value)| format!(",\"p{percent}\":{value:.1}"))
+            .collect::<String>();
+        format!("{{\"mean\":{:.1}{percentiles}}}", summary.mean)
+    };
+    let objects = results
+        .iter()
+        .map(|result| {
+            format!(
+                "  {{\"phase\":\"{}\",\"algorithm\":\"{}\",\"size\":{},\"steps\":{},\"microseconds\":{}}}",
+                result.phase,
+                result.algorithm.replace('\\', "\\\\").replace('"', "\\\""),
+                result.size,
+                summary(&result.steps),
+                summary(&result.microseconds)
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+/// Results as CSV with a header row and a row for each algorithm and size.
+fn to_csv(results: &[Measurement]) -> String {
+    let columns = |measure: &str| {
+        std::iter::once(format!("{measure}_mean"))
+            .chain(PERCENTILES.map(|percent| format!("{measure}_p{percent}")))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let values = |summary: &Summary| {
+        std::iter::once(summary.mean)
+            .chain(summary.percentiles)
+            .map(|value| format!("{value:.1}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut csv = format!(
+        "phase,algorithm,size,{},{}\n",
+        columns("steps"),
+        columns("microseconds")
+    );
+    for result in results {
+        csv += &format!(
+            "{},\"{}\",{},{},{}\n",
+            result.phase,
+            result.algorithm.replace('"', "\"\""),
+            result.size,
+            values(&result.steps),
+            values(&result.microseconds)
+        );
+    }
+    csv
+}
+
+/// Options from arguments. `None` if only help was asked for.
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        runs: DEFAULT_RUNS,
+        sizes: DEFAULT_SIZES.to_vec(),
+        seed: 0,
+        format: Format::Json,
+    };
+
+    while let Some(arg) = args.next() {
+        // flags may be given as "--name value" or "--name=value"
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("missing value for {name}"))
+        };
+        match name.as_str() {
+            "--runs" => {
+                let value = value()?;
+                options.runs = value
+                    .parse()
+                    .ok()
+                    .filter(|runs| *runs > 0)
+                    .ok_or_else(|| format!("invalid number of runs \"{value}\""))?;
+            }
+            "--sizes" => {
+                let value = value()?;
+                options.sizes = value
+                    .split(',')
+                    .map(|size| {
+                        size.trim()
+                            .parse()
+                            .ok()
+                            .filter(|size| *size >= MINIMUM_DIMENSION)
+                            .ok_or_else(|| {
+                                format!(
+                                    "sizes must be at least {MINIMUM_DIMENSION}, not \"{size}\""
+                                )
+                            })
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            "--seed" => {
+                let value = value()?;
+                options.seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed \"{value}\""))?;
+            }
+            "--format" => {
+                options.format = match value()?.to_ascii_lowercase().as_str() {
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    other => return Err(format!("unknown format \"{other}\"")),
+                }
+            }
+            "--log" => {
+                let value = value()?;
+                platform::set_level(
+                    Level::parse(&value).ok_or(format!("unknown log level \"{value}\""))?,
+                );
+            }
+            "--help" => {
+                println!("{USAGE}");
+                return Ok(None);
+            }
+            _ => return Err(format!("unknown argument \"{name}\"; see --help")),
+        }
+    }
+    Ok(Some(options))
+}
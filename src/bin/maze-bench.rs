@@ -0,0 +1,8 @@
+// Benchmark harness for the generators and solvers; see `maze-bench --help`.
+
+fn main() {
+    if let Err(error) = wasm_maze::bench::run(std::env::args().skip(1)) {
+        eprintln!("maze-bench: {error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,8 @@
+// Command line tool generating mazes natively; see `maze-cli --help`.
+
+fn main() {
+    if let Err(error) = wasm_maze::cli::run(std::env::args().skip(1)) {
+        eprintln!("maze-cli: {error}");
+        std::process::exit(1);
+    }
+}
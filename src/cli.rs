@@ -0,0 +1,298 @@
+// Command line interface generating mazes natively, with the same algorithms as the
+// WebAssembly build.
+
+use crate::format::ascii::to_ascii;
+use crate::format::png::to_png;
+use crate::format::svg::to_svg;
+use crate::format::MazeData;
+use crate::generate::generators;
+use crate::parameter::{Parameter, Values};
+use crate::platform::Level;
+use crate::registry::{resolve, Registered};
+use crate::rng::{Draws, Rng, Xorshift};
+use crate::solve::solvers;
+use crate::{platform, Cells, MINIMUM_DIMENSION};
+
+use std::io::Write;
+
+// Default number of cells in each dimension
+const DEFAULT_DIMENSION: usize = 20;
+
+const USAGE: &str = "\
+Generate a maze, optionally solved, and write it as ASCII, SVG or PNG.
+
+Usage: maze-cli [OPTIONS]
+
+Options:
+  --width <CELLS>       number of cells across [default: 20]
+  --height <CELLS>      number of cells down [default: 20]
+  --generator <NAME>    generating algorithm, by its id or the start of its name
+  --solver <NAME>       solving algorithm, by its id or the start of its name; implies --solve
+  --solve               solve from the top left cell to the bottom right cell
+  --parameter <KEY=VALUE>
+                        value of a parameter of the generator or solver, such as order=ESWN or
+                        max-steps=1000; may be given more than once
+  --seed <NUMBER>       seed for repeatable mazes
+  --record <FILE>       write the random draws of generating and solving to a file
+  --replay <FILE>       replay the random draws of a file written by --record
+  --format <FORMAT>     ascii, svg or png [default: from the output file, otherwise ascii]
+  --output <FILE>       file to write; standard output if not given
+  --list                list the ids, categories and names of the generating and solving
+                        algorithms
+  --log <LEVEL>         log off, info, debug or trace messages to standard error
+  --help                show this help";
+
+/// Output formats.
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    Svg,
+    Png,
+}
+
+impl Format {
+    /// Format by name or file extension.
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ascii" | "txt" => Some(Self::Ascii),
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+}
+
+/// Options from the command line arguments.
+struct Options {
+    width: usize,
+    height: usize,
+    generator: String,
+    // solver; if None then not solved
+    solver: Option<String>,
+    seed: Option<u64>,
+    // file to write the random draws to
+    record: Option<String>,
+    // file to read the random draws to replay from
+    replay: Option<String>,
+    format: Option<Format>,
+    output: Option<String>,
+    // values of parameters of the generator and solver, by key
+    parameters: Vec<(String, String)>,
+}
+
+/// Run the command line interface with arguments, excluding the program name.
+pub fn run(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let Some(options) = parse(args)? else {
+        return Ok(());
+    };
+
+    let seed = options.seed.unwrap_or_else(platform::random_seed);
+
+    // draws replayed from a file, or recorded from the seeded generator if a file is given
+    let draws = match &options.replay {
+        Some(replay) => Draws::from_base64(
+            &std::fs::read_to_string(replay)
+                .map_err(|error| format!("could not read \"{replay}\": {error}"))?,
+        )
+        .map_err(|error| format!("could not replay \"{replay}\": {error}"))?,
+        None => Draws::default(),
+    };
+    let rng = || -> Box<dyn Rng> {
+        match (&options.replay, &options.record) {
+            (Some(_), _) => draws.replayer(),
+            (None, Some(_)) => draws.recorder(Box::new(Xorshift::new(seed))),
+            (None, None) => Box::new(Xorshift::new(seed)),
+        }
+    };
+
+    let dimensions = (options.width, options.height);
+    let mut cells = Cells::new((options.width, options.height));
+    let generators = generators();
+    let generator = generators
+        .get(&options.generator)
+        .ok_or_else(|| format!("unknown generator \"{}\"", options.generator))?;
+    let solvers = solvers();
+    let solver = match &options.solver {
+        Some(name) => Some(
+            solvers
+                .get(name)
+                .ok_or_else(|| format!("unknown solver \"{name}\""))?,
+        ),
+        None => None,
+    };
+    if let Some((key, _)) = options.parameters.iter().find(|(key, _)| {
+        !generator
+            .parameters()
+            .iter()
+            .chain(solver.map_or(&[][..], |solver| solver.parameters()))
+            .any(|parameter| parameter.key == key)
+    }) {
+        return Err(format!("unknown parameter \"{key}\""));
+    }
+
+    let mut generator =
+        generator.create(rng(), &values(generator.parameters(), &options.parameters)?);
+    while generator.step(dimensions, &mut cells).is_continue() {}
+
+    let (from, to) = (0, cells.len() - 1);
+    cells.reset_solution(from, to);
+    if let Some(solver) = solver {
+        let mut solver = solver.create(rng(), &values(solver.parameters(), &options.parameters)?);
+        while solver.step(dimensions, &mut cells, from, to).continuing()? {}
+    }
+
+    if let Some(record) = &options.record {
+        std::fs::write(record, draws.to_base64())
+            .map_err(|error| format!("could not write \"{record}\": {error}"))?;
+    }
+
+    let data = MazeData::new(dimensions, &cells, from, to);
+    let format = options
+        .format
+        .or_else(|| {
+            options
+                .output
+                .as_deref()
+                .and_then(|output| output.rsplit_once('.'))
+                .and_then(|(_, extension)| Format::parse(extension))
+        })
+        .unwrap_or(Format::Ascii);
+    let bytes = match format {
+        Format::Ascii => to_ascii(&data).into_bytes(),
+        Format::Svg => to_svg(&data).into_bytes(),
+        Format::Png => to_png(&data),
+    };
+
+    match &options.output {
+        Some(output) => std::fs::write(output, bytes)
+            .map_err(|error| format!("could not write \"{output}\": {error}")),
+        None => std::io::stdout()
+            .write_all(&bytes)
+            .map_err(|error| format!("could not write output: {error}")),
+    }
+}
+
+/// Options from arguments. `None` if only help or the list of algorithms was asked for.
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        width: DEFAULT_DIMENSION,
+        height: DEFAULT_DIMENSION,
+        generator: generators()
+            .into_keys()
+            .next()
+            .expect("should have generator"),
+        solver: None,
+        seed: None,
+        record: None,
+        replay: None,
+        format: None,
+        output: None,
+        parameters: Vec::new(),
+    };
+    let mut solve = false;
+
+    while let Some(arg) = args.next() {
+        // flags may be given as "--name value" or "--name=value"
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("missing value for {name}"))
+        };
+        match name.as_str() {
+            "--width" => options.width = dimension(&name, &value()?)?,
+            "--height" => options.height = dimension(&name, &value()?)?,
+            "--generator" | "-g" => options.generator = resolve(&generators(), &value()?),
+            "--solver" | "-s" => options.solver = Some(resolve(&solvers(), &value()?)),
+            "--solve" => solve = true,
+            "--parameter" | "-p" => {
+                let value = value()?;
+                let (key, value) = value
+                    .split_once('=')
+                    .ok_or_else(|| format!("parameter \"{value}\" is not KEY=VALUE"))?;
+                options
+                    .parameters
+                    .push((key.to_string(), value.to_string()));
+            }
+            "--seed" => {
+                let value = value()?;
+                options.seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid seed \"{value}\""))?,
+                );
+            }
+            "--record" => options.record = Some(value()?),
+            "--replay" => options.replay = Some(value()?),
+            "--format" | "-f" => {
+                let value = value()?;
+                options.format =
+                    Some(Format::parse(&value).ok_or(format!("unknown format \"{value}\""))?);
+            }
+            "--output" | "-o" => options.output = Some(value()?),
+            "--list" => {
+                println!("Generators:");
+                for (id, generator) in generators() {
+                    let category = generator.category().id();
+                    println!("  {id:<24} {category:<14} {}", generator.name());
+                }
+                println!("Solvers:");
+                for (id, solver) in solvers() {
+                    let category = solver.category().id();
+                    println!("  {id:<24} {category:<14} {}", solver.name());
+                }
+                return Ok(None);
+            }
+            "--log" => {
+                let value = value()?;
+                platform::set_level(
+                    Level::parse(&value).ok_or(format!("unknown log level \"{value}\""))?,
+                );
+            }
+            "--help" => {
+                println!("{USAGE}");
+                return Ok(None);
+            }
+            _ => return Err(format!("unknown argument \"{name}\"; see --help")),
+        }
+    }
+
+    if options.record.is_some() && options.replay.is_some() {
+        return Err("--record and --replay cannot be given together".to_string());
+    }
+    if solve && options.solver.is_none() {
+        options.solver = solvers().into_keys().next();
+    }
+    Ok(Some(options))
+}
+
+/// Number of cells in a dimension from the value of a flag.
+fn dimension(name: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|cells| *cells >= MINIMUM_DIMENSION)
+        .ok_or_else(|| {
+            format!("{name} must be a number of at least {MINIMUM_DIMENSION}, not \"{value}\"")
+        })
+}
+
+/// Values of parameters from those given by key; keys of other parameters are ignored.
+fn values(parameters: &[Parameter], given: &[(String, String)]) -> Result<Values, String> {
+    let mut values = Values::default();
+    for parameter in parameters {
+        for (_, value) in given.iter().filter(|(key, _)| key == parameter.key) {
+            values.set(
+                parameter.key,
+                parameter.parse(value).ok_or_else(|| {
+                    format!("invalid value \"{value}\" for parameter {}", parameter.key)
+                })?,
+            );
+        }
+    }
+    Ok(values)
+}
@@ -0,0 +1,63 @@
+use crate::solve::Solver;
+use crate::{Cells, Dimensions, ALL_WALLS};
+
+/// A type for a solver running on a copy of the maze, for comparison with the main solver.
+pub(crate) struct Comparison {
+    pub(crate) cells: Cells,
+    solver: Box<dyn Solver>,
+    // number of steps applied
+    steps: usize,
+    solving: bool,
+}
+
+impl Comparison {
+    /// Create a `Comparison` of solver on cells.
+    pub(crate) fn new(cells: Cells, solver: Box<dyn Solver>) -> Self {
+        Self {
+            cells,
+            solver,
+            steps: 0,
+            solving: true,
+        }
+    }
+
+    /// Apply a step of the solver, if still solving. Returns whether still solving.
+    pub(crate) fn step(&mut self, dimensions: Dimensions, from: usize, to: usize) -> bool {
+        if self.solving {
+            self.steps += 1;
+            self.solving = self
+                .solver
+                .step(dimensions, &mut self.cells, from, to)
+                .is_continue();
+        }
+        self.solving
+    }
+
+    /// Summary of the solve so far.
+    pub(crate) fn summary(&self) -> String {
+        summary(self.steps, &self.cells)
+    }
+}
+
+/// Summary of a solve of cells after a number of steps, with the share of the carved cells
+/// explored.
+pub(crate) fn summary(steps: usize, cells: &Cells) -> String {
+    let explored = cells
+        .solutions
+        .values()
+        .filter(|solution| solution.previous.is_some())
+        .count();
+    let path = cells
+        .solutions
+        .values()
+        .filter(|solution| solution.result)
+        .count();
+    // cells enclosed by all their walls cannot be explored
+    let carved = (0..cells.len())
+        .filter(|cell| cells.walls(*cell) != ALL_WALLS)
+        .count();
+    format!(
+        "{steps} steps, {explored} cells explored ({:.1}% of carved cells), path of {path} cells",
+        100.0 * explored as f64 / carved.max(1) as f64
+    )
+}
@@ -0,0 +1,51 @@
+// Milliseconds over which the rates are measured; a longer gap between frames, as when
+// nothing is animated, starts measuring again
+const WINDOW_MILLISECONDS: f64 = 1000.0;
+
+/// A type measuring the frames of the visualisation, for judging the performance of the
+/// hardware.
+#[derive(Default)]
+pub(crate) struct Diagnostics {
+    // time at which the current window started; if None then not started
+    started: Option<f64>,
+    // time of the last frame
+    last: f64,
+    frames: usize,
+    // number of algorithm steps applied in the frames of the window
+    steps: usize,
+    // milliseconds spent stepping and drawing in the frames of the window
+    busy: f64,
+}
+
+impl Diagnostics {
+    /// Record a frame at time, applying steps in milliseconds. Returns a description of the
+    /// rates each time a window is complete.
+    pub(crate) fn frame(&mut self, time: f64, steps: usize, milliseconds: f64) -> Option<String> {
+        if time - self.last > WINDOW_MILLISECONDS {
+            *self = Self::default();
+        }
+        self.last = time;
+        let started = *self.started.get_or_insert(time);
+        self.frames += 1;
+        self.steps += steps;
+        self.busy += milliseconds;
+
+        let elapsed = time - started;
+        if elapsed < WINDOW_MILLISECONDS {
+            return None;
+        }
+        let seconds = elapsed / 1000.0;
+        let description = format!(
+            "{:.0} frames/s, {:.0} steps/s, {:.1} ms per frame",
+            self.frames as f64 / seconds,
+            self.steps as f64 / seconds,
+            self.busy / self.frames as f64
+        );
+        *self = Self {
+            started: Some(time),
+            last: time,
+            ..Self::default()
+        };
+        Some(description)
+    }
+}
@@ -1,75 +1,197 @@
 use crate::Dimensions;
 
-/// A type providing directions.
+/// Trait for the directions between neighbouring cells of a kind of grid, so that grids with
+/// other numbers of neighbours, such as 3, 6 or 8, may define their own.
+pub(crate) trait Directions: Copy + PartialEq + 'static {
+    /// Every direction, in order around a cell.
+    const ALL: &'static [Self];
+
+    /// Opposite to this direction.
+    fn opposite(&self) -> Self;
+
+    /// Determines neighbouring cell in this direction. `None` if outside of dimensions.
+    fn neighbour(&self, dimensions: Dimensions, cell: usize) -> Option<usize>;
+}
+
+/// A type providing the compass directions of a grid of square cells, each also the bit of
+/// its wall in a mask of walls.
 #[derive(Clone, Copy, PartialEq)]
 #[repr(u8)]
 pub(crate) enum Direction {
-    First = 0b1,
-    Second = 0b10,
-    Third = 0b100,
-    Forth = 0b1000,
+    North = 0b1,
+    East = 0b10,
+    South = 0b100,
+    West = 0b1000,
 }
 
 impl Direction {
     /// Next to this `Direction`.
     pub(crate) fn next(&self) -> Direction {
         match self {
-            Self::First => Self::Second,
-            Self::Second => Self::Third,
-            Self::Third => Self::Forth,
-            Self::Forth => Self::First,
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
         }
     }
 
     /// Previous to this `Direction`.
     pub(crate) fn prev(&self) -> Self {
         match self {
-            Self::First => Self::Forth,
-            Self::Second => Self::First,
-            Self::Third => Self::Second,
-            Self::Forth => Self::Third,
+            Self::North => Self::West,
+            Self::East => Self::North,
+            Self::South => Self::East,
+            Self::West => Self::South,
         }
     }
 
-    /// Determines neighbouring cell in this `Direction`. `None` if outside of dimensions.
-    pub(crate) fn neighbour(&self, dimensions: Dimensions, cell: usize) -> Option<usize> {
+    /// Compass name of this `Direction`.
+    #[cfg(any(feature = "wasm", test))]
+    pub(crate) fn name(&self) -> &'static str {
         match self {
-            Direction::First => cell.checked_sub(dimensions.0),
-            Direction::Second => cell.checked_add(1).filter(|val| val % dimensions.0 != 0),
-            Direction::Third => cell
-                .checked_add(dimensions.0)
-                .filter(|val| *val < dimensions.0 * dimensions.1),
-            Direction::Forth => cell.checked_sub(1).filter(|_| cell % dimensions.0 != 0),
+            Self::North => "north",
+            Self::East => "east",
+            Self::South => "south",
+            Self::West => "west",
         }
     }
 
+    /// Compass letter of this `Direction`.
+    pub(crate) fn letter(&self) -> char {
+        match self {
+            Self::North => 'N',
+            Self::East => 'E',
+            Self::South => 'S',
+            Self::West => 'W',
+        }
+    }
+
+    /// Order of every `Direction` from their compass letters, each once in any case, ignoring
+    /// spaces and commas; e.g. "N, E, S, W". `None` if not every direction is given once.
+    pub(crate) fn parse_order(text: &str) -> Option<[Direction; 4]> {
+        let mut order = Vec::with_capacity(Self::ALL.len());
+        for letter in text.chars().filter(|c| !c.is_whitespace() && *c != ',') {
+            let direction = *Self::ALL
+                .iter()
+                .find(|direction| direction.letter() == letter.to_ascii_uppercase())?;
+            if order.contains(&direction) {
+                return None;
+            }
+            order.push(direction);
+        }
+        order.try_into().ok()
+    }
+
+    /// Number of orders of every `Direction`.
+    pub(crate) const ORDERS: usize = 24;
+
+    /// Index of order among every order of every `Direction`, counting in the lexicographic
+    /// order of north, east, south then west; 0 for that order itself.
+    pub(crate) fn order_index(order: [Direction; 4]) -> usize {
+        let mut remaining = Self::ALL.to_vec();
+        order.iter().fold(0, |index, direction| {
+            let position = remaining
+                .iter()
+                .position(|remaining| remaining == direction)
+                .unwrap_or_default();
+            remaining.remove(position);
+            index * (remaining.len() + 1) + position
+        })
+    }
+
+    /// Order at index among every order of every `Direction`, as counted by `order_index`;
+    /// indexes past the last wrap around.
+    pub(crate) fn order_at(index: usize) -> [Direction; 4] {
+        let mut remaining = Self::ALL.to_vec();
+        let mut index = index % Self::ORDERS;
+        let mut order = [Direction::North; 4];
+        for direction in &mut order {
+            let orders = (1..remaining.len()).product::<usize>();
+            *direction = remaining.remove(index / orders);
+            index %= orders;
+        }
+        order
+    }
+
     /// Determines `Direction` from cell to neighbouring cell. `None` if cells are not neighbours.
     pub(crate) fn between(dimensions: Dimensions, from: usize, to: usize) -> Option<Direction> {
         from.checked_sub(dimensions.0)
             .filter(|val| *val == to)
-            .map(|_| Self::First)
+            .map(|_| Self::North)
             .or_else(|| {
                 from.checked_add(dimensions.0)
                     .filter(|val| *val == to)
-                    .map(|_| Self::Third)
+                    .map(|_| Self::South)
             })
             .or_else(|| {
                 from.checked_add(1)
-                    .filter(|val| *val == to && to % dimensions.0 != 0)
-                    .map(|_| Self::Second)
+                    .filter(|val| *val == to && !to.is_multiple_of(dimensions.0))
+                    .map(|_| Self::East)
             })
             .or_else(|| {
                 from.checked_sub(1)
-                    .filter(|val| *val == to && from % dimensions.0 != 0)
-                    .map(|_| Self::Forth)
+                    .filter(|val| *val == to && !from.is_multiple_of(dimensions.0))
+                    .map(|_| Self::West)
             })
     }
 }
 
+impl Directions for Direction {
+    const ALL: &'static [Self] = &[Self::North, Self::East, Self::South, Self::West];
+
+    fn opposite(&self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    fn neighbour(&self, dimensions: Dimensions, cell: usize) -> Option<usize> {
+        match self {
+            Direction::North => cell.checked_sub(dimensions.0),
+            Direction::East => cell
+                .checked_add(1)
+                .filter(|val| !val.is_multiple_of(dimensions.0)),
+            Direction::South => cell
+                .checked_add(dimensions.0)
+                .filter(|val| *val < dimensions.0 * dimensions.1),
+            Direction::West => cell
+                .checked_sub(1)
+                .filter(|_| !cell.is_multiple_of(dimensions.0)),
+        }
+    }
+}
+
 /// Array of all `Direction`s.
-pub(crate) const DIRECTIONS: &[Direction] = &[
-    Direction::First,
-    Direction::Second,
-    Direction::Third,
-    Direction::Forth,
-];
+pub(crate) const DIRECTIONS: &[Direction] = Direction::ALL;
+
+/// A type holding the neighbouring cells in up to each of N directions, by default
+/// `DIRECTIONS`, without allocating.
+pub(crate) struct Neighbours<const N: usize = { DIRECTIONS.len() }> {
+    cells: [usize; N],
+    len: usize,
+}
+
+impl<const N: usize> FromIterator<usize> for Neighbours<N> {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        let mut neighbours = Self {
+            cells: [0; N],
+            len: 0,
+        };
+        for cell in iter {
+            neighbours.cells[neighbours.len] = cell;
+            neighbours.len += 1;
+        }
+        neighbours
+    }
+}
+
+impl<const N: usize> std::ops::Deref for Neighbours<N> {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        &self.cells[..self.len]
+    }
+}
@@ -0,0 +1,71 @@
+use crate::app::{build, initial_settings, window};
+
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlElement;
+
+// Tag of the custom element
+pub(crate) const TAG: &str = "wasm-maze";
+
+// Attributes of the custom element setting the initial settings
+const ATTRIBUTES: [&str; 10] = [
+    "width",
+    "height",
+    "generator",
+    "solver",
+    "seed",
+    "speed",
+    "wall-width",
+    "passage-width",
+    "line-cap",
+    "line-join",
+];
+
+#[wasm_bindgen(inline_js = "
+export function define_element(tag, mount) {
+    if (customElements.get(tag) === undefined) {
+        customElements.define(tag, class extends HTMLElement {
+            connectedCallback() {
+                if (!this.mounted) {
+                    this.mounted = true;
+                    mount(this);
+                }
+            }
+        });
+    }
+}
+")]
+extern "C" {
+    fn define_element(tag: &str, mount: &Closure<dyn FnMut(HtmlElement)>);
+}
+
+/// Define the custom element, which builds the user interface into itself when first
+/// added to the document.
+///
+/// The attributes `width`, `height`, `generator`, `solver`, `seed`, `speed`, `wall-width`,
+/// `passage-width`, `line-cap` and `line-join` override the remembered settings and query
+/// parameters, and `autoplay` starts in demo mode.
+pub(crate) fn define() {
+    let mount = Closure::<dyn FnMut(_)>::new(|element: HtmlElement| {
+        let mut settings = initial_settings();
+        for name in ATTRIBUTES {
+            if let Some(value) = element.get_attribute(name) {
+                settings.set(name, &value);
+            }
+        }
+        if element
+            .get_attribute("autoplay")
+            .is_some_and(|value| value != "false")
+        {
+            settings.demo = true;
+        }
+        let built = match window().document() {
+            Some(document) => build(&document, &element, settings),
+            None => Err("no document".into()),
+        };
+        if let Err(error) = built {
+            web_sys::console::error_1(&error);
+        }
+    });
+    define_element(TAG, &mount);
+    mount.forget();
+}
@@ -0,0 +1,79 @@
+use crate::app::{query_parameters, window};
+use crate::maze::object;
+
+use wasm_bindgen::prelude::*;
+use web_sys::Window;
+
+// Query parameter of the origins allowed to send commands, separated by commas
+const ORIGINS_PARAMETER: &str = "origins";
+
+/// A type for messages exchanged with the page embedding the application in an `iframe`.
+#[derive(Clone)]
+pub(crate) struct Embedding {
+    parent: Window,
+    // origins allowed to send commands and receive messages; if empty then any
+    origins: Vec<String>,
+}
+
+impl Embedding {
+    /// Create if the application is embedded. `None` if not.
+    pub(crate) fn new() -> Option<Self> {
+        let parent = window().parent().ok().flatten()?;
+        if js_sys::Object::is(&parent, &window()) {
+            return None;
+        }
+        let origins = query_parameters()
+            .into_iter()
+            .filter(|(name, _)| name == ORIGINS_PARAMETER)
+            .flat_map(|(_, value)| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|origin| !origin.is_empty())
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Some(Self { parent, origins })
+    }
+
+    /// Whether commands from origin are accepted.
+    pub(crate) fn allows(&self, origin: &str) -> bool {
+        self.origins.is_empty() || self.origins.iter().any(|allowed| allowed == origin)
+    }
+
+    /// Post the result of a command to the origin which sent it.
+    pub(crate) fn reply(&self, origin: &str, command: &str, result: Result<JsValue, String>) {
+        let message = match result {
+            Ok(value) => object(&[
+                ("command", command.into()),
+                ("ok", true.into()),
+                ("result", value),
+            ]),
+            Err(error) => object(&[
+                ("command", command.into()),
+                ("ok", false.into()),
+                ("error", error.into()),
+            ]),
+        };
+        let _ = self.parent.post_message(&message, origin);
+    }
+
+    /// Post an event with details to the allowed origins, or any origin if not limited.
+    pub(crate) fn notify(&self, event: &str, details: &[(&str, JsValue)]) {
+        let message = object(&[("event", event.into())]);
+        for (key, value) in details {
+            let _ = js_sys::Reflect::set(&message, &JsValue::from_str(key), value);
+        }
+        match self.origins.is_empty() {
+            true => {
+                let _ = self.parent.post_message(&message, "*");
+            }
+            false => {
+                for origin in &self.origins {
+                    let _ = self.parent.post_message(&message, origin);
+                }
+            }
+        }
+    }
+}
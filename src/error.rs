@@ -0,0 +1,47 @@
+// Errors of the user interface, reported on the page and the console rather than
+// failing silently.
+
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlElement;
+
+/// A type for an error of the user interface, with a message for the user.
+pub(crate) struct Error(String);
+
+impl Error {
+    /// Report on banner, which is shown until dismissed, and the console.
+    pub(crate) fn report(&self, banner: &HtmlElement) {
+        web_sys::console::error_1(&JsValue::from_str(&self.0));
+        banner.set_text_content(Some(&format!("Something went wrong: {}", self.0)));
+        banner.set_hidden(false);
+    }
+}
+
+impl From<JsValue> for Error {
+    fn from(value: JsValue) -> Self {
+        Self(
+            value
+                .dyn_ref::<js_sys::Error>()
+                .map(|error| String::from(error.message()))
+                .or_else(|| value.as_string())
+                .unwrap_or_else(|| format!("{value:?}")),
+        )
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Self(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Self(message.to_string())
+    }
+}
+
+impl From<Error> for JsValue {
+    fn from(error: Error) -> Self {
+        js_sys::Error::new(&error.0).into()
+    }
+}
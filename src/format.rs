@@ -0,0 +1,173 @@
+pub(crate) mod ascii;
+pub(crate) mod binary;
+#[cfg(feature = "wasm")]
+pub(crate) mod dot;
+#[cfg(feature = "wasm")]
+pub(crate) mod image;
+#[cfg(feature = "wasm")]
+pub(crate) mod json;
+pub(crate) mod png;
+#[cfg(feature = "wasm")]
+pub(crate) mod poster;
+#[cfg(feature = "wasm")]
+pub(crate) mod solution;
+pub(crate) mod svg;
+
+use crate::path::solution;
+use crate::{Cells, Dimensions};
+#[cfg(any(feature = "wasm", test))]
+use crate::{Direction, Directions, ALL_WALLS, DIRECTIONS, MINIMUM_DIMENSION};
+
+#[cfg(any(feature = "wasm", test))]
+use std::collections::BTreeMap;
+
+// Offset basis and prime of the 64 bit FNV-1a hash of a fingerprint
+#[cfg(feature = "wasm")]
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+#[cfg(feature = "wasm")]
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A type for the state of a maze which can be exported and imported.
+#[derive(Clone)]
+pub(crate) struct MazeData {
+    pub(crate) dimensions: Dimensions,
+    // walls of each cell; bit per wall as for `Cells`
+    pub(crate) walls: Vec<u8>,
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    // cells of the solution, including from and to; if None then not solved
+    pub(crate) solution: Option<Vec<usize>>,
+    // notes attached to cells, by cell index
+    #[cfg(any(feature = "wasm", test))]
+    pub(crate) notes: BTreeMap<usize, String>,
+}
+
+impl MazeData {
+    /// Capture the state of cells.
+    pub(crate) fn new(dimensions: Dimensions, cells: &Cells, from: usize, to: usize) -> Self {
+        Self {
+            dimensions,
+            walls: cells.all_walls(),
+            from,
+            to,
+            solution: solution(cells, from, to),
+            #[cfg(any(feature = "wasm", test))]
+            notes: BTreeMap::new(),
+        }
+    }
+
+    /// Validate the dimensions, walls, locations and solution describe a maze.
+    #[cfg(any(feature = "wasm", test))]
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let (width, height) = self.dimensions;
+        if width < MINIMUM_DIMENSION || height < MINIMUM_DIMENSION {
+            return Err(format!(
+                "dimensions {width} by {height} are less than the minimum of {MINIMUM_DIMENSION}"
+            ));
+        }
+        if width.checked_mul(height) != Some(self.walls.len()) {
+            return Err(format!(
+                "{} cells of walls do not match dimensions {width} by {height}",
+                self.walls.len()
+            ));
+        }
+
+        for (cell, walls) in self.walls.iter().enumerate() {
+            if walls & !ALL_WALLS != 0 {
+                return Err(format!("cell {cell} has invalid walls {walls:#06b}"));
+            }
+            for direction in DIRECTIONS {
+                let present = walls & *direction as u8 != 0;
+                match direction.neighbour(self.dimensions, cell) {
+                    None if !present => {
+                        return Err(format!(
+                            "cell {cell} is missing its {} boundary wall",
+                            direction.name()
+                        ))
+                    }
+                    Some(neighbour)
+                        if present != (self.walls[neighbour] & direction.opposite() as u8 != 0) =>
+                    {
+                        return Err(format!(
+                            "{} wall of cell {cell} does not match neighbour {neighbour}",
+                            direction.name()
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for (name, cell) in [("from", self.from), ("to", self.to)] {
+            if cell >= self.walls.len() {
+                return Err(format!("{name} cell {cell} is outside the maze"));
+            }
+        }
+        if self.from == self.to {
+            return Err("from and to cells are the same".to_string());
+        }
+
+        if let Some(path) = &self.solution {
+            if path.first() != Some(&self.from) || path.last() != Some(&self.to) {
+                return Err("solution does not lead from the from cell to the to cell".to_string());
+            }
+            if let Some(cell) = path.iter().find(|cell| **cell >= self.walls.len()) {
+                return Err(format!("solution cell {cell} is outside the maze"));
+            }
+            for pair in path.windows(2) {
+                if Direction::between(self.dimensions, pair[0], pair[1])
+                    .is_none_or(|direction| self.walls[pair[0]] & direction as u8 != 0)
+                {
+                    return Err(format!(
+                        "solution passes through a wall between cells {} and {}",
+                        pair[0], pair[1]
+                    ));
+                }
+            }
+        }
+
+        if let Some(cell) = self.notes.keys().find(|cell| **cell >= self.walls.len()) {
+            return Err(format!("note cell {cell} is outside the maze"));
+        }
+
+        Ok(())
+    }
+
+    /// Fingerprint of the walls, as `fingerprint`.
+    #[cfg(feature = "wasm")]
+    pub(crate) fn fingerprint(&self) -> String {
+        fingerprint(
+            self.dimensions,
+            &Cells::from_walls(self.dimensions, &self.walls).all_walls(),
+        )
+    }
+
+    /// Cells of the maze, including the solution if any.
+    pub(crate) fn cells(&self) -> Cells {
+        let mut cells = Cells::from_walls(self.dimensions, &self.walls);
+        cells.walks = (0..self.walls.len()).map(Some).collect();
+        cells.reset_solution(self.from, self.to);
+        if let Some(path) = &self.solution {
+            cells.solution_mut(path[0]).result = true;
+            for pair in path.windows(2) {
+                cells.solution_mut(pair[1]).previous = Some(pair[0]);
+                cells.solution_mut(pair[1]).result = true;
+            }
+        }
+        cells
+    }
+}
+
+/// Short hash of the dimensions and the walls of each cell, bit per wall; eight hexadecimal
+/// digits, the same for mazes with the same walls however they were produced.
+#[cfg(feature = "wasm")]
+pub(crate) fn fingerprint(dimensions: Dimensions, walls: &[u8]) -> String {
+    let bytes = [dimensions.0 as u32, dimensions.1 as u32]
+        .into_iter()
+        .flat_map(u32::to_le_bytes)
+        .chain(walls.iter().copied());
+    let hash = bytes.fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{:08x}", (hash ^ hash >> 32) as u32)
+}
@@ -0,0 +1,39 @@
+use super::MazeData;
+use crate::Direction;
+
+/// Drawing of the maze in ASCII characters.
+///
+/// Corners are `+`, walls are `---` and `|`, and the from and to cells are `S` and `E`.
+/// Cells of the solution, if any, are `*`.
+pub(crate) fn to_ascii(data: &MazeData) -> String {
+    let (width, height) = data.dimensions;
+    let path = data.solution.as_deref().unwrap_or_default();
+    let wall = |cell: usize, direction: Direction| data.walls[cell] & direction as u8 != 0;
+
+    let mut ascii = String::new();
+    for row in 0..height {
+        for col in 0..width {
+            ascii += match wall(row * width + col, Direction::North) {
+                true => "+---",
+                false => "+   ",
+            };
+        }
+        ascii += "+\n";
+        for col in 0..width {
+            let cell = row * width + col;
+            ascii += match wall(cell, Direction::West) {
+                true => "|",
+                false => " ",
+            };
+            ascii += match cell {
+                _ if cell == data.from => " S ",
+                _ if cell == data.to => " E ",
+                _ if path.contains(&cell) => " * ",
+                _ => "   ",
+            };
+        }
+        ascii += "|\n";
+    }
+    ascii += &"+---".repeat(width);
+    ascii + "+\n"
+}
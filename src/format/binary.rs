@@ -0,0 +1,217 @@
+#[cfg(feature = "wasm")]
+use super::json::{from_json, to_json};
+#[cfg(feature = "wasm")]
+use super::MazeData;
+#[cfg(feature = "wasm")]
+use crate::{Direction, Directions, DIRECTIONS};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "wasm")]
+use std::collections::BTreeMap;
+
+// Version of the format
+#[cfg(feature = "wasm")]
+const VERSION: u8 = 1;
+
+// Number of bytes of the header; version then width, height, from, to and number of
+// solution cells as little endian `u32`s
+#[cfg(feature = "wasm")]
+const HEADER_BYTES: usize = 1 + 5 * 4;
+
+// URL safe base64 alphabet, used without padding
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode a maze given as JSON into base64 of the compact binary format.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn encode_maze(json: &str) -> Result<String, JsValue> {
+    Ok(to_base64(&from_json(json)?))
+}
+
+/// Decode a maze given as base64 of the compact binary format into JSON.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn decode_maze(base64: &str) -> Result<String, JsValue> {
+    Ok(to_json(&from_base64(base64)?))
+}
+
+/// Serialise as base64 of the compact binary format.
+#[cfg(feature = "wasm")]
+pub(crate) fn to_base64(data: &MazeData) -> String {
+    encode_base64(&to_bytes(data))
+}
+
+/// Deserialise from base64 of the compact binary format, validating the maze.
+#[cfg(feature = "wasm")]
+pub(crate) fn from_base64(base64: &str) -> Result<MazeData, String> {
+    from_bytes(&decode_base64(base64.trim())?)
+}
+
+/// Serialise into bytes.
+///
+/// Only the east and south walls of each cell are stored, as 2 bits per cell, since the
+/// north and west walls are those of neighbours or the boundary. The solution is stored as
+/// 2 bits per move, indexing `DIRECTIONS`.
+#[cfg(feature = "wasm")]
+pub(crate) fn to_bytes(data: &MazeData) -> Vec<u8> {
+    let path = data.solution.as_deref().unwrap_or_default();
+    let mut bytes = vec![VERSION];
+    for value in [
+        data.dimensions.0,
+        data.dimensions.1,
+        data.from,
+        data.to,
+        path.len(),
+    ] {
+        bytes.extend_from_slice(&(value as u32).to_le_bytes());
+    }
+
+    pack(
+        &mut bytes,
+        data.walls.iter().map(|walls| {
+            (walls & Direction::East as u8 != 0) as u8
+                | ((walls & Direction::South as u8 != 0) as u8) << 1
+        }),
+    );
+    pack(
+        &mut bytes,
+        path.windows(2).map(|pair| {
+            Direction::between(data.dimensions, pair[0], pair[1])
+                .map_or(0, |direction| (direction as u8).trailing_zeros() as u8)
+        }),
+    );
+    bytes
+}
+
+/// Deserialise from bytes, validating the maze.
+#[cfg(feature = "wasm")]
+pub(crate) fn from_bytes(bytes: &[u8]) -> Result<MazeData, String> {
+    if bytes.len() < HEADER_BYTES {
+        return Err("too short".to_string());
+    }
+    if bytes[0] != VERSION {
+        return Err(format!("version {} is not supported", bytes[0]));
+    }
+    let header = bytes[1..HEADER_BYTES]
+        .chunks(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()) as usize)
+        .collect::<Vec<_>>();
+    let (width, height, from, to, path_len) =
+        (header[0], header[1], header[2], header[3], header[4]);
+
+    let cells = width
+        .checked_mul(height)
+        .ok_or("dimensions are too large")?;
+    let moves = path_len.saturating_sub(1);
+    if bytes.len() != HEADER_BYTES + cells.div_ceil(4) + moves.div_ceil(4) {
+        return Err(format!(
+            "length does not match dimensions {width} by {height}"
+        ));
+    }
+    let (walls, directions) = bytes[HEADER_BYTES..].split_at(cells.div_ceil(4));
+    let stored = unpack(walls, cells);
+
+    let walls = (0..cells)
+        .map(|cell| {
+            let (row, col) = (cell / width, cell % width);
+            let north = row == 0 || stored[cell - width] & 0b10 != 0;
+            let west = col == 0 || stored[cell - 1] & 0b01 != 0;
+            (north as u8 * Direction::North as u8)
+                | ((stored[cell] & 0b01) * Direction::East as u8)
+                | ((stored[cell] >> 1) * Direction::South as u8)
+                | (west as u8 * Direction::West as u8)
+        })
+        .collect();
+
+    let solution = match path_len {
+        0 => None,
+        _ => {
+            let mut path = vec![from];
+            for direction in unpack(directions, moves) {
+                let cell = path[path.len() - 1];
+                path.push(
+                    DIRECTIONS[direction as usize]
+                        .neighbour((width, height), cell)
+                        .filter(|_| cell < cells)
+                        .ok_or("solution leaves the maze")?,
+                );
+            }
+            Some(path)
+        }
+    };
+
+    let data = MazeData {
+        dimensions: (width, height),
+        walls,
+        from,
+        to,
+        solution,
+        notes: BTreeMap::new(),
+    };
+    data.validate()?;
+    Ok(data)
+}
+
+/// Append 2 bit values, 4 per byte.
+#[cfg(feature = "wasm")]
+fn pack(bytes: &mut Vec<u8>, values: impl Iterator<Item = u8>) {
+    let mut byte = 0;
+    let mut count = 0;
+    for value in values {
+        byte |= value << (2 * (count % 4));
+        count += 1;
+        if count % 4 == 0 {
+            bytes.push(byte);
+            byte = 0;
+        }
+    }
+    if count % 4 != 0 {
+        bytes.push(byte);
+    }
+}
+
+/// Extract a number of 2 bit values, 4 per byte.
+#[cfg(feature = "wasm")]
+fn unpack(bytes: &[u8], count: usize) -> Vec<u8> {
+    (0..count)
+        .map(|idx| (bytes[idx / 4] >> (2 * (idx % 4))) & 0b11)
+        .collect()
+}
+
+/// Encode bytes as URL safe base64 without padding.
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    let mut base64 = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (idx, byte)| {
+            value | (*byte as u32) << (16 - 8 * idx)
+        });
+        for idx in 0..=chunk.len() {
+            base64.push(ALPHABET[(value >> (18 - 6 * idx) & 0b111111) as usize] as char);
+        }
+    }
+    base64
+}
+
+/// Decode URL safe base64 without padding into bytes.
+pub(crate) fn decode_base64(base64: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(base64.len() * 3 / 4);
+    for chunk in base64.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return Err("not valid base64".to_string());
+        }
+        let mut value = 0u32;
+        for (idx, char) in chunk.iter().enumerate() {
+            let digit = ALPHABET
+                .iter()
+                .position(|c| c == char)
+                .ok_or("not valid base64")?;
+            value |= (digit as u32) << (18 - 6 * idx);
+        }
+        for idx in 0..chunk.len() - 1 {
+            bytes.push((value >> (16 - 8 * idx)) as u8);
+        }
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,46 @@
+use super::MazeData;
+use crate::geometry::row_and_col;
+use crate::{Direction, Directions};
+
+use std::collections::HashSet;
+
+/// Connectivity of the maze as a [Graphviz](https://graphviz.org/) DOT graph.
+///
+/// Cells are nodes, positioned by row and column for layout with `neato -n`, and removed
+/// walls are edges. The from and to cells and the edges of the solution are highlighted.
+pub(crate) fn to_dot(data: &MazeData) -> String {
+    let solution = data
+        .solution
+        .as_ref()
+        .map(|path| {
+            path.windows(2)
+                .map(|pair| (pair[0].min(pair[1]), pair[0].max(pair[1])))
+                .collect::<HashSet<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut dot = String::from("graph maze {\n    node [shape=point];\n");
+    for cell in 0..data.walls.len() {
+        let (row, col) = row_and_col(data.dimensions, cell);
+        dot += &format!("    c{cell} [pos=\"{col},{}\"", data.dimensions.1 - 1 - row);
+        if cell == data.from || cell == data.to {
+            dot += ", shape=circle, width=0.2, color=red";
+        }
+        dot += "];\n";
+    }
+    for cell in 0..data.walls.len() {
+        for direction in [Direction::East, Direction::South] {
+            if data.walls[cell] & direction as u8 != 0 {
+                continue;
+            }
+            if let Some(neighbour) = direction.neighbour(data.dimensions, cell) {
+                dot += &format!("    c{cell} -- c{neighbour}");
+                if solution.contains(&(cell, neighbour)) {
+                    dot += " [color=red, penwidth=3]";
+                }
+                dot += ";\n";
+            }
+        }
+    }
+    dot + "}\n"
+}
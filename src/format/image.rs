@@ -0,0 +1,177 @@
+use super::MazeData;
+use crate::{Direction, ALL_WALLS, MINIMUM_DIMENSION};
+
+use std::collections::BTreeMap;
+
+// Minimum number of pixels in each cell dimension when detecting the cell size
+const MINIMUM_CELL_PIXELS: usize = 3;
+
+// Minimum proportion of the ends of walls near the lines of the grid for a detected cell size
+const GRID_LINE_ENDS: f64 = 0.95;
+
+/// Options for reading a maze from an image.
+pub(crate) struct ImageOptions {
+    // number of pixels in each cell dimension; if None then detected
+    pub(crate) cell_pixels: Option<usize>,
+    // luminance below which a pixel is part of a wall
+    pub(crate) threshold: u8,
+}
+
+/// A type for an image of a maze, with walls dark and passages light.
+struct Image<'a> {
+    // RGBA bytes of each pixel by row
+    pixels: &'a [u8],
+    width: usize,
+    threshold: u8,
+}
+
+impl Image<'_> {
+    /// Whether pixel is part of a wall.
+    fn is_dark(&self, x: usize, y: usize) -> bool {
+        let idx = (y * self.width + x) * 4;
+        let [r, g, b, a] = [0, 1, 2, 3].map(|offset| self.pixels[idx + offset] as u32);
+        // transparent pixels are taken as background
+        let luminance = (299 * r + 587 * g + 114 * b) / 1000 * a / 255 + (255 - a);
+        luminance < self.threshold as u32
+    }
+
+    /// Whether any pixel within a rectangle is part of a wall.
+    fn any_dark(&self, (x0, y0): (usize, usize), (x1, y1): (usize, usize)) -> bool {
+        (y0..=y1).any(|y| (x0..=x1).any(|x| self.is_dark(x, y)))
+    }
+}
+
+/// Read a maze from the RGBA pixels of an image of the maze drawn as lines on a grid.
+///
+/// The grid spans the bounding box of the dark pixels. A wall is present where any pixel
+/// near the middle of the line between cells is dark. From and to are the top left and
+/// bottom right cells.
+pub(crate) fn from_pixels(
+    pixels: &[u8],
+    (width, height): (usize, usize),
+    options: &ImageOptions,
+) -> Result<MazeData, String> {
+    if pixels.len() != width * height * 4 {
+        return Err("pixels do not match the image size".to_string());
+    }
+    let image = Image {
+        pixels,
+        width,
+        threshold: options.threshold,
+    };
+
+    // bounding box of the dark pixels
+    let dark = |x: usize, y: usize| image.is_dark(x, y);
+    let mut columns = (0..width).filter(|x| (0..height).any(|y| dark(*x, y)));
+    let mut rows = (0..height).filter(|y| (0..width).any(|x| dark(x, *y)));
+    let (Some(left), Some(top)) = (columns.next(), rows.next()) else {
+        return Err("no walls found; try a higher threshold".to_string());
+    };
+    let (right, bottom) = (
+        columns.next_back().unwrap_or(left),
+        rows.next_back().unwrap_or(top),
+    );
+    let (span_x, span_y) = (right - left, bottom - top);
+
+    let (cols, rows) = match options.cell_pixels {
+        Some(0) => return Err("cell size must be at least 1 pixel".to_string()),
+        Some(cell_pixels) => (
+            (span_x as f64 / cell_pixels as f64).round() as usize,
+            (span_y as f64 / cell_pixels as f64).round() as usize,
+        ),
+        None => {
+            // offsets of the ends of the runs of dark pixels along each row and column
+            let (mut ends_x, mut ends_y) = (vec![0; span_x + 1], vec![0; span_y + 1]);
+            for y in top..=bottom {
+                run_ends(&mut ends_x, |x| dark(left + x, y));
+            }
+            for x in left..=right {
+                run_ends(&mut ends_y, |y| dark(x, top + y));
+            }
+            (detect_cells(&ends_x), detect_cells(&ends_y))
+        }
+    };
+    if cols < MINIMUM_DIMENSION || rows < MINIMUM_DIMENSION {
+        return Err(format!(
+            "found {cols} by {rows} cells; try setting the cell size"
+        ));
+    }
+
+    // pixel position of grid line
+    let grid_x = |col: usize| left + col * span_x / cols;
+    let grid_y = |row: usize| top + row * span_y / rows;
+
+    let mut walls = vec![ALL_WALLS; cols * rows];
+    for row in 0..rows {
+        for col in 0..cols {
+            let cell = row * cols + col;
+            let (x0, x1, y0, y1) = (grid_x(col), grid_x(col + 1), grid_y(row), grid_y(row + 1));
+            let (margin_x, margin_y) = ((x1 - x0) / 4, (y1 - y0) / 4);
+            if col + 1 < cols
+                && !image.any_dark(
+                    (x1.saturating_sub(margin_x), y0 + margin_y + 1),
+                    ((x1 + margin_x).min(right), y1 - margin_y - 1),
+                )
+            {
+                walls[cell] &= !(Direction::East as u8);
+                walls[cell + 1] &= !(Direction::West as u8);
+            }
+            if row + 1 < rows
+                && !image.any_dark(
+                    (x0 + margin_x + 1, y1.saturating_sub(margin_y)),
+                    (x1 - margin_x - 1, (y1 + margin_y).min(bottom)),
+                )
+            {
+                walls[cell] &= !(Direction::South as u8);
+                walls[cell + cols] &= !(Direction::North as u8);
+            }
+        }
+    }
+
+    let data = MazeData {
+        dimensions: (cols, rows),
+        walls,
+        from: 0,
+        to: cols * rows - 1,
+        solution: None,
+        notes: BTreeMap::new(),
+    };
+    data.validate()?;
+    Ok(data)
+}
+
+/// Count the offsets of the first and last pixels of each run of dark pixels.
+fn run_ends(ends: &mut [usize], dark: impl Fn(usize) -> bool) {
+    let span = ends.len() - 1;
+    let mut previous = false;
+    for (offset, count) in ends.iter_mut().enumerate() {
+        let current = dark(offset);
+        if current && (!previous || offset == span || !dark(offset + 1)) {
+            *count += 1;
+        }
+        previous = current;
+    }
+}
+
+/// Number of cells along a span of pixels, detected as the fewest cells whose grid lines
+/// are near almost all of the offsets at which runs of dark pixels end. Walls, being drawn
+/// along the grid lines, start and end at them.
+fn detect_cells(ends: &[usize]) -> usize {
+    let span = ends.len() - 1;
+    let offsets = (0..=span)
+        .filter(|offset| ends[*offset] > 0)
+        .collect::<Vec<_>>();
+    (MINIMUM_DIMENSION..=span / MINIMUM_CELL_PIXELS)
+        .find(|cells| {
+            let tolerance = (span / cells / 6).max(1);
+            let near = offsets
+                .iter()
+                .filter(|offset| {
+                    let nearest = ((**offset * cells) as f64 / span as f64).round() as usize;
+                    (nearest * span / cells).abs_diff(**offset) <= tolerance
+                })
+                .count();
+            near as f64 >= offsets.len() as f64 * GRID_LINE_ENDS
+        })
+        .unwrap_or(1)
+}
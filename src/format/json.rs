@@ -0,0 +1,356 @@
+use super::MazeData;
+
+use std::collections::BTreeMap;
+
+// Identifier and version of the format
+const FORMAT: &str = "wasm-maze";
+const VERSION: usize = 1;
+
+/// Serialise as JSON.
+pub(crate) fn to_json(data: &MazeData) -> String {
+    let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(",");
+    let mut json = format!(
+        "{{\"format\":\"{FORMAT}\",\"version\":{VERSION},\"width\":{},\"height\":{},\"walls\":[{}],\"from\":{},\"to\":{},\"fingerprint\":\"{}\"",
+        data.dimensions.0,
+        data.dimensions.1,
+        join(&mut data.walls.iter().map(|walls| walls.to_string())),
+        data.from,
+        data.to,
+        data.fingerprint(),
+    );
+    if let Some(path) = &data.solution {
+        json += &format!(
+            ",\"solution\":[{}]",
+            join(&mut path.iter().map(|cell| cell.to_string()))
+        );
+    }
+    if !data.notes.is_empty() {
+        json +=
+            &format!(
+                ",\"notes\":[{}]",
+                join(&mut data.notes.iter().map(|(cell, text)| {
+                    format!("{{\"cell\":{cell},\"text\":{}}}", quote(text))
+                }))
+            );
+    }
+    json + "}"
+}
+
+/// Deserialise from JSON, validating the maze and, if given, its fingerprint.
+pub(crate) fn from_json(json: &str) -> Result<MazeData, String> {
+    let Value::Object(object) = parse(json).ok_or("not valid JSON")? else {
+        return Err("not a JSON object".to_string());
+    };
+
+    if !matches!(get(&object, "format"), Value::String(format) if format == FORMAT) {
+        return Err(format!("format is not \"{FORMAT}\""));
+    }
+    let version = number(&object, "version")?;
+    if version > VERSION {
+        return Err(format!("version {version} is newer than supported"));
+    }
+
+    let data = MazeData {
+        dimensions: (number(&object, "width")?, number(&object, "height")?),
+        walls: numbers(&object, "walls")?
+            .ok_or("walls are missing")?
+            .into_iter()
+            .map(|walls| u8::try_from(walls).map_err(|_| format!("walls {walls} are invalid")))
+            .collect::<Result<_, _>>()?,
+        from: number(&object, "from")?,
+        to: number(&object, "to")?,
+        solution: numbers(&object, "solution")?,
+        notes: notes(&object)?,
+    };
+    data.validate()?;
+    // the fingerprint, if present, confirms the walls are those exported
+    if let Value::String(fingerprint) = get(&object, "fingerprint") {
+        if *fingerprint != data.fingerprint() {
+            return Err(format!("walls do not match fingerprint {fingerprint}"));
+        }
+    }
+    Ok(data)
+}
+
+/// Get the notes of object, each of a cell and its text. Empty if not present.
+fn notes(object: &Object) -> Result<BTreeMap<usize, String>, String> {
+    let notes = match get(object, "notes") {
+        Value::Null => return Ok(BTreeMap::new()),
+        Value::Array(notes) => notes,
+        _ => return Err("notes is not an array".to_string()),
+    };
+    notes
+        .iter()
+        .map(|note| {
+            let Value::Object(note) = note else {
+                return Err("note is not an object".to_string());
+            };
+            let Value::String(text) = get(note, "text") else {
+                return Err("note text is not a string".to_string());
+            };
+            Ok((number(note, "cell")?, text.clone()))
+        })
+        .collect()
+}
+
+/// Get property of object, null if not present.
+fn get<'a>(object: &'a Object, key: &str) -> &'a Value {
+    object.get(key).unwrap_or(&Value::Null)
+}
+
+/// Convert value to a non-negative integer.
+fn to_number(key: &str, value: &Value) -> Result<usize, String> {
+    match value {
+        Value::Number(number)
+            if number.fract() == 0.0 && *number >= 0.0 && *number <= usize::MAX as f64 =>
+        {
+            Ok(*number as usize)
+        }
+        _ => Err(format!("{key} is not a non-negative integer")),
+    }
+}
+
+/// Get property of object as a non-negative integer.
+fn number(object: &Object, key: &str) -> Result<usize, String> {
+    to_number(key, get(object, key))
+}
+
+/// Get property of object as an array of non-negative integers. `None` if not present.
+fn numbers(object: &Object, key: &str) -> Result<Option<Vec<usize>>, String> {
+    match get(object, key) {
+        Value::Null => Ok(None),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| to_number(key, value))
+            .collect::<Result<_, _>>()
+            .map(Some),
+        _ => Err(format!("{key} is not an array")),
+    }
+}
+
+/// Text as a JSON string.
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\r' => quoted += "\\r",
+            '\t' => quoted += "\\t",
+            c if c < ' ' => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Parsed JSON value; numbers as `f64` as in JavaScript.
+enum Value {
+    Null,
+    Bool,
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Object),
+}
+
+/// Members of a JSON object. Of repeated keys, the last is kept as in JavaScript.
+type Object = BTreeMap<String, Value>;
+
+/// Parse JSON text into a value. `None` if not valid JSON.
+fn parse(json: &str) -> Option<Value> {
+    let mut parser = Parser {
+        bytes: json.as_bytes(),
+        idx: 0,
+    };
+    let value = parser.value()?;
+    parser.whitespace();
+    (parser.idx == parser.bytes.len()).then_some(value)
+}
+
+/// Recursive descent over the bytes of JSON text.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    idx: usize,
+}
+
+impl Parser<'_> {
+    /// Next byte, after any whitespace, without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        self.whitespace();
+        self.bytes.get(self.idx).copied()
+    }
+
+    /// Consume the literal, after any whitespace, if next.
+    fn eat(&mut self, literal: &str) -> bool {
+        self.whitespace();
+        let found = self.bytes[self.idx..].starts_with(literal.as_bytes());
+        if found {
+            self.idx += literal.len();
+        }
+        found
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.bytes.get(self.idx), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.idx += 1;
+        }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        match self.peek()? {
+            b'{' => self.object(),
+            b'[' => self.array(),
+            b'"' => self.string().map(Value::String),
+            b'-' | b'0'..=b'9' => self.number(),
+            _ if self.eat("null") => Some(Value::Null),
+            _ if self.eat("true") || self.eat("false") => Some(Value::Bool),
+            _ => None,
+        }
+    }
+
+    fn object(&mut self) -> Option<Value> {
+        self.eat("{");
+        let mut object = Object::new();
+        if self.eat("}") {
+            return Some(Value::Object(object));
+        }
+        loop {
+            if self.peek()? != b'"' {
+                return None;
+            }
+            let key = self.string()?;
+            if !self.eat(":") {
+                return None;
+            }
+            object.insert(key, self.value()?);
+            if self.eat("}") {
+                return Some(Value::Object(object));
+            }
+            if !self.eat(",") {
+                return None;
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<Value> {
+        self.eat("[");
+        let mut array = Vec::new();
+        if self.eat("]") {
+            return Some(Value::Array(array));
+        }
+        loop {
+            array.push(self.value()?);
+            if self.eat("]") {
+                return Some(Value::Array(array));
+            }
+            if !self.eat(",") {
+                return None;
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let start = self.idx;
+        let digits = |parser: &mut Self| {
+            let start = parser.idx;
+            while parser.bytes.get(parser.idx).is_some_and(u8::is_ascii_digit) {
+                parser.idx += 1;
+            }
+            parser.idx > start
+        };
+        if self.bytes[self.idx] == b'-' {
+            self.idx += 1;
+        }
+        // no leading zeros
+        if self.bytes.get(self.idx) == Some(&b'0') {
+            self.idx += 1;
+        } else if !digits(self) {
+            return None;
+        }
+        if self.bytes.get(self.idx) == Some(&b'.') {
+            self.idx += 1;
+            if !digits(self) {
+                return None;
+            }
+        }
+        if matches!(self.bytes.get(self.idx), Some(b'e' | b'E')) {
+            self.idx += 1;
+            if matches!(self.bytes.get(self.idx), Some(b'+' | b'-')) {
+                self.idx += 1;
+            }
+            if !digits(self) {
+                return None;
+            }
+        }
+        std::str::from_utf8(&self.bytes[start..self.idx])
+            .ok()?
+            .parse()
+            .ok()
+            .map(Value::Number)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.idx += 1;
+        let mut string = String::new();
+        loop {
+            let end = self.bytes[self.idx..]
+                .iter()
+                .position(|byte| matches!(byte, b'"' | b'\\' | 0..=0x1f))?;
+            // the text is a `str` and the delimiters ASCII, so the run is whole characters
+            string += std::str::from_utf8(&self.bytes[self.idx..self.idx + end]).ok()?;
+            self.idx += end + 1;
+            match self.bytes[self.idx - 1] {
+                b'"' => return Some(string),
+                b'\\' => {
+                    let escape = *self.bytes.get(self.idx)?;
+                    self.idx += 1;
+                    string.push(match escape {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode()?,
+                        _ => return None,
+                    });
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Character of a `\u` escape, combining a surrogate pair. Unpaired surrogates, which
+    /// a Rust string cannot hold, are replaced.
+    fn unicode(&mut self) -> Option<char> {
+        let high = self.hex()?;
+        if (0xd800..0xdc00).contains(&high) && self.bytes[self.idx..].starts_with(b"\\u") {
+            let idx = self.idx;
+            self.idx += 2;
+            let low = self.hex()?;
+            if (0xdc00..0xe000).contains(&low) {
+                let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return char::from_u32(code);
+            }
+            self.idx = idx;
+        }
+        Some(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Value of 4 hexadecimal digits.
+    fn hex(&mut self) -> Option<u32> {
+        let digits = std::str::from_utf8(self.bytes.get(self.idx..self.idx + 4)?).ok()?;
+        self.idx += 4;
+        // `from_str_radix` would accept a sign
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(digits, 16).ok()
+    }
+}
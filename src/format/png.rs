@@ -0,0 +1,477 @@
+use super::MazeData;
+use crate::geometry::row_and_col;
+use crate::{Cells, Dimensions, DIRECTIONS};
+
+use std::collections::HashMap;
+
+// Number of pixels in each cell dimension
+const CELL_PIXELS: usize = 10;
+
+// Colours of the palette; the background, walls and cells not yet generated, the from and
+// to cells and solution, and cells searched by a solver
+const PALETTE: [[u8; 3]; 4] = [
+    [0xff, 0xff, 0xff],
+    [0x00, 0x00, 0x00],
+    [0xff, 0x00, 0x00],
+    [0xff, 0xbf, 0x7f],
+];
+const BACKGROUND: u8 = 0;
+const WALL: u8 = 1;
+const SOLUTION: u8 = 2;
+const SEARCH: u8 = 3;
+
+// Number of bytes searched back for repeats when compressing, as allowed by deflate
+const WINDOW: usize = 32768;
+
+// Number of earlier positions with the same next bytes tried when compressing
+const CHAIN: usize = 32;
+
+// Lengths of repeats at the start of each deflate length code from 257, and their extra bits
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+// Distances of repeats at the start of each deflate distance code, and their extra bits
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// A type for an image of palette indexes.
+struct Frame {
+    width: usize,
+    height: usize,
+    // palette index of each pixel by row
+    pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Draw the state of cells, as generated and solved so far.
+    fn new(dimensions: Dimensions, cells: &Cells) -> Self {
+        let (width, height) = size(dimensions);
+        let mut frame = Self {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width * height],
+        };
+        let corner = |cell: usize| {
+            let (row, col) = row_and_col(dimensions, cell);
+            (col * CELL_PIXELS, row * CELL_PIXELS)
+        };
+        let centre = |cell: usize| {
+            let (x, y) = corner(cell);
+            (x + CELL_PIXELS / 2, y + CELL_PIXELS / 2)
+        };
+
+        // solver paths first, searched then found, so that walls are drawn over them
+        for (colour, half_width) in [(SEARCH, 0), (SOLUTION, 1)] {
+            for (cell, solution) in &cells.solutions {
+                let Some(previous) = solution.previous else {
+                    continue;
+                };
+                if (colour == SOLUTION) != solution.result {
+                    continue;
+                }
+                let ((x0, y0), (x1, y1)) = (centre(previous), centre(*cell));
+                frame.fill(
+                    (x0.min(x1) - half_width, y0.min(y1) - half_width),
+                    (x0.max(x1) + half_width, y0.max(y1) + half_width),
+                    colour,
+                );
+            }
+        }
+
+        for cell in 0..cells.len() {
+            let (x, y) = corner(cell);
+            if cells.walks[cell].is_none() {
+                frame.fill((x, y), (x + CELL_PIXELS, y + CELL_PIXELS), WALL);
+                continue;
+            }
+            for direction in DIRECTIONS {
+                if cells.has_wall(cell, *direction) {
+                    // corners of the cell, clockwise from the top left
+                    let corners = [
+                        (x, y),
+                        (x + CELL_PIXELS, y),
+                        (x + CELL_PIXELS, y + CELL_PIXELS),
+                        (x, y + CELL_PIXELS),
+                    ];
+                    let side = DIRECTIONS
+                        .iter()
+                        .position(|other| other == direction)
+                        .expect("should be a direction");
+                    let (start, end) = (corners[side], corners[(side + 1) % 4]);
+                    frame.fill(
+                        (start.0.min(end.0), start.1.min(end.1)),
+                        (start.0.max(end.0), start.1.max(end.1)),
+                        WALL,
+                    );
+                }
+            }
+            let solution = cells.solution(cell);
+            if solution.from || solution.to {
+                let (x, y) = centre(cell);
+                frame.fill((x - 2, y - 2), (x + 2, y + 2), SOLUTION);
+            }
+        }
+        frame
+    }
+
+    /// Fill the rectangle between corners, inclusive, with colour.
+    fn fill(&mut self, (x0, y0): (usize, usize), (x1, y1): (usize, usize), colour: u8) {
+        for y in y0..=y1 {
+            self.pixels[y * self.width + x0..=y * self.width + x1].fill(colour);
+        }
+    }
+
+    /// Compressed image data, each row starting with filter type 0 (none).
+    fn compressed(&self) -> Vec<u8> {
+        let raw = self
+            .pixels
+            .chunks(self.width)
+            .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+            .collect::<Vec<_>>();
+        zlib(&raw)
+    }
+}
+
+/// Drawing of the maze as a PNG image.
+///
+/// Walls are black and the from and to cells and solution, if any, are red.
+pub(crate) fn to_png(data: &MazeData) -> Vec<u8> {
+    let frame = Frame::new(data.dimensions, &data.cells());
+    let mut png = header(frame.width, frame.height);
+    chunk(&mut png, b"IDAT", &frame.compressed());
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// A type building an animated PNG (APNG) from the states of cells at each frame.
+#[cfg(feature = "wasm")]
+pub(crate) struct Animation {
+    dimensions: Dimensions,
+    // milliseconds each frame is shown
+    delay: u16,
+    // compressed image data of each frame
+    frames: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "wasm")]
+impl Animation {
+    /// Create for cells of dimensions, showing each frame for milliseconds.
+    pub(crate) fn new(dimensions: Dimensions, delay: u16) -> Self {
+        Self {
+            dimensions,
+            delay,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Add a frame of the state of cells.
+    pub(crate) fn frame(&mut self, cells: &Cells) {
+        self.frames
+            .push(Frame::new(self.dimensions, cells).compressed());
+    }
+
+    /// Number of frames added.
+    pub(crate) fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The animated PNG, playing the frames in a loop.
+    pub(crate) fn to_apng(&self) -> Vec<u8> {
+        let (width, height) = size(self.dimensions);
+        let mut png = header(width, height);
+
+        // number of frames, and plays where 0 is forever
+        let mut control = (self.frames.len() as u32).to_be_bytes().to_vec();
+        control.extend_from_slice(&0u32.to_be_bytes());
+        chunk(&mut png, b"acTL", &control);
+
+        // frame control and data chunks share a sequence
+        let mut sequence = 0u32;
+        for (idx, data) in self.frames.iter().enumerate() {
+            let mut control = sequence.to_be_bytes().to_vec();
+            control.extend_from_slice(&(width as u32).to_be_bytes());
+            control.extend_from_slice(&(height as u32).to_be_bytes());
+            // offsets of the frame
+            control.extend_from_slice(&[0; 8]);
+            // delay as a fraction of seconds
+            control.extend_from_slice(&self.delay.to_be_bytes());
+            control.extend_from_slice(&1000u16.to_be_bytes());
+            // no disposal and no blending; each frame replaces the whole image
+            control.extend_from_slice(&[0, 0]);
+            chunk(&mut png, b"fcTL", &control);
+            sequence += 1;
+
+            match idx {
+                0 => chunk(&mut png, b"IDAT", data),
+                _ => {
+                    let mut frame_data = sequence.to_be_bytes().to_vec();
+                    frame_data.extend_from_slice(data);
+                    chunk(&mut png, b"fdAT", &frame_data);
+                    sequence += 1;
+                }
+            }
+        }
+        chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+/// Width and height in pixels of an image of cells of dimensions.
+fn size(dimensions: Dimensions) -> (usize, usize) {
+    (
+        dimensions.0 * CELL_PIXELS + 1,
+        dimensions.1 * CELL_PIXELS + 1,
+    )
+}
+
+/// Signature, header and palette of a PNG image of width and height pixels.
+fn header(width: usize, height: usize) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // bit depth 8, colour type 3 (palette), default compression, filter and no interlace
+    header.extend_from_slice(&[8, 3, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"PLTE", PALETTE.as_flattened());
+    png
+}
+
+/// Append a chunk of type with data to png.
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// A type writing bits, least significant first, as deflate does.
+#[derive(Default)]
+struct Bits {
+    bytes: Vec<u8>,
+    // bits not yet written to bytes, and their number
+    pending: u32,
+    count: u32,
+}
+
+impl Bits {
+    /// Write the count least significant bits of value.
+    fn write(&mut self, value: u32, count: u32) {
+        self.pending |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.pending as u8);
+            self.pending >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Write a Huffman code of count bits, which are written most significant first.
+    fn write_code(&mut self, code: u32, count: u32) {
+        self.write(code.reverse_bits() >> (32 - count), count);
+    }
+
+    /// Write a literal byte or end of block (256) with the fixed Huffman codes.
+    fn write_symbol(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    /// Write a repeat of length bytes from distance back.
+    fn write_repeat(&mut self, length: usize, distance: usize) {
+        let code = LENGTH_BASES
+            .iter()
+            .rposition(|base| *base as usize <= length)
+            .expect("should be at least the shortest length");
+        self.write_symbol(257 + code as u16);
+        self.write(
+            (length - LENGTH_BASES[code] as usize) as u32,
+            LENGTH_EXTRA_BITS[code],
+        );
+
+        let code = DISTANCE_BASES
+            .iter()
+            .rposition(|base| *base as usize <= distance)
+            .expect("should be at least the shortest distance");
+        self.write_code(code as u32, 5);
+        self.write(
+            (distance - DISTANCE_BASES[code] as usize) as u32,
+            DISTANCE_EXTRA_BITS[code],
+        );
+    }
+
+    /// Bytes written, with any pending bits padded to a byte.
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.pending as u8);
+        }
+        self.bytes
+    }
+}
+
+/// A zlib stream of bytes compressed in a single deflate block with the fixed Huffman codes,
+/// finding repeats of earlier bytes by hashing each three bytes.
+fn zlib(bytes: &[u8]) -> Vec<u8> {
+    let (shortest, longest) = (LENGTH_BASES[0] as usize, LENGTH_BASES[28] as usize);
+    let hash = |idx: usize| {
+        (bytes[idx] as usize) << 16 | (bytes[idx + 1] as usize) << 8 | bytes[idx + 2] as usize
+    };
+    // latest position of each three bytes, and the previous position of the same three
+    // bytes as each position
+    let mut latest = HashMap::new();
+    let mut previous = vec![usize::MAX; bytes.len()];
+    let insert = |idx: usize, latest: &mut HashMap<usize, usize>, previous: &mut [usize]| {
+        if idx + shortest <= bytes.len() {
+            previous[idx] = latest.insert(hash(idx), idx).unwrap_or(usize::MAX);
+        }
+    };
+
+    let mut bits = Bits::default();
+    // final block, with the fixed Huffman codes
+    bits.write(1, 1);
+    bits.write(1, 2);
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let mut best = (0, 0);
+        if idx + shortest <= bytes.len() {
+            let limit = longest.min(bytes.len() - idx);
+            let mut candidate = latest.get(&hash(idx)).copied().unwrap_or(usize::MAX);
+            for _ in 0..CHAIN {
+                if candidate == usize::MAX || idx - candidate > WINDOW {
+                    break;
+                }
+                let length = (0..limit)
+                    .take_while(|offset| bytes[candidate + offset] == bytes[idx + offset])
+                    .count();
+                if length > best.0 {
+                    best = (length, idx - candidate);
+                }
+                if length == limit {
+                    break;
+                }
+                candidate = previous[candidate];
+            }
+        }
+
+        match best {
+            (length, distance) if length >= shortest => {
+                bits.write_repeat(length, distance);
+                for skipped in idx..idx + length {
+                    insert(skipped, &mut latest, &mut previous);
+                }
+                idx += length;
+            }
+            _ => {
+                bits.write_symbol(bytes[idx] as u16);
+                insert(idx, &mut latest, &mut previous);
+                idx += 1;
+            }
+        }
+    }
+    bits.write_symbol(256);
+
+    // deflate with a 32K window and no preset dictionary
+    let mut stream = vec![0x78, 0x01];
+    stream.extend(bits.finish());
+    stream.extend_from_slice(&adler32(bytes).to_be_bytes());
+    stream
+}
+
+/// [CRC-32](https://en.wikipedia.org/wiki/Cyclic_redundancy_check) of bytes as used by PNG.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| match crc & 1 {
+            1 => (crc >> 1) ^ 0xedb8_8320,
+            _ => crc >> 1,
+        })
+    })
+}
+
+/// [Adler-32](https://en.wikipedia.org/wiki/Adler-32) checksum of bytes as used by zlib.
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::tests::generate;
+
+    /// Type and data of each chunk of png following the signature, checking the CRC of each.
+    fn chunks(png: &[u8]) -> Vec<(&[u8], &[u8])> {
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..4].try_into().expect("should have length"));
+            let (typed, after) = rest[4..].split_at(4 + len as usize);
+            let crc = u32::from_be_bytes(after[..4].try_into().expect("should have CRC"));
+            assert_eq!(crc32(typed), crc, "{:?}", &typed[..4]);
+            chunks.push((&typed[..4], &typed[4..]));
+            rest = &after[4..];
+        }
+        chunks
+    }
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn images_have_header_palette_data_and_end_chunks() {
+        let dimensions = (3, 2);
+        let cells = generate("kruskal", dimensions, 0);
+        let data = MazeData::new(dimensions, &cells, 0, cells.len() - 1);
+        let png = to_png(&data);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let chunks = chunks(&png);
+        let kinds = chunks.iter().map(|(kind, _)| *kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [&b"IHDR"[..], b"PLTE", b"IDAT", b"IEND"]);
+        let mut header = 31u32.to_be_bytes().to_vec();
+        header.extend_from_slice(&21u32.to_be_bytes());
+        header.extend_from_slice(&[8, 3, 0, 0, 0]);
+        assert_eq!(chunks[0].1, header);
+        assert_eq!(chunks[1].1, PALETTE.as_flattened());
+        assert!(chunks[3].1.is_empty());
+
+        // a zlib stream of the rows, each of a filter byte and a byte per pixel
+        let image = chunks[2].1;
+        assert_eq!(&image[..2], [0x78, 0x01]);
+        let frame = Frame::new(dimensions, &data.cells());
+        let raw = frame
+            .pixels
+            .chunks(frame.width)
+            .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+            .collect::<Vec<_>>();
+        assert_eq!(raw.len(), 21 * 32);
+        assert_eq!(image[image.len() - 4..], adler32(&raw).to_be_bytes());
+    }
+}
@@ -0,0 +1,51 @@
+use crate::Dimensions;
+
+// Default number of cells across and down each tile, about the shape of a portrait page
+pub(crate) const DEFAULT_TILE_CELLS: Dimensions = (20, 28);
+
+// Default number of cells by which each tile overlaps its neighbours
+pub(crate) const DEFAULT_TILE_OVERLAP: usize = 1;
+
+/// A part of the maze to print on a page, to be trimmed at its crop marks and assembled
+/// with the others into a poster.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct Tile {
+    pub(crate) row: usize,
+    pub(crate) col: usize,
+    // column and row of the top left cell within the crop marks
+    pub(crate) origin: (usize, usize),
+    // cells across and down within the crop marks; fewer for the last tiles of a row or
+    // column where the maze does not fill them
+    pub(crate) cells: Dimensions,
+    // cells shown beyond the crop marks on each side, so that the tiles overlap when assembled
+    pub(crate) overlap: usize,
+}
+
+impl Tile {
+    /// Name of the file of this `Tile`, by row and column from 1, with extension.
+    pub(crate) fn filename(&self, extension: &str) -> String {
+        format!("maze-tile-{}-{}.{extension}", self.row + 1, self.col + 1)
+    }
+}
+
+/// Tiles of at most cells across and down covering a maze of dimensions, row by row, each
+/// overlapping its neighbours by overlap cells.
+pub(crate) fn tiles(dimensions: Dimensions, cells: Dimensions, overlap: usize) -> Vec<Tile> {
+    let (across, down) = (cells.0.max(1), cells.1.max(1));
+    let mut tiles = Vec::new();
+    for (row, top) in (0..dimensions.1).step_by(down).enumerate() {
+        for (col, left) in (0..dimensions.0).step_by(across).enumerate() {
+            tiles.push(Tile {
+                row,
+                col,
+                origin: (left, top),
+                cells: (
+                    across.min(dimensions.0 - left),
+                    down.min(dimensions.1 - top),
+                ),
+                overlap,
+            });
+        }
+    }
+    tiles
+}
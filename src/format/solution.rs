@@ -0,0 +1,28 @@
+use super::MazeData;
+use crate::geometry::row_and_col;
+use crate::Direction;
+
+/// Solution as a JSON array of `[row, column]` pairs, from the from cell to the to cell.
+pub(crate) fn to_coordinates(data: &MazeData) -> Result<String, String> {
+    let path = data.solution.as_ref().ok_or("the maze is not solved")?;
+    Ok(format!(
+        "[{}]",
+        path.iter()
+            .map(|cell| {
+                let (row, col) = row_and_col(data.dimensions, *cell);
+                format!("[{row},{col}]")
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    ))
+}
+
+/// Solution as a string of the compass letter of each move, e.g. "NNEESW".
+pub(crate) fn to_moves(data: &MazeData) -> Result<String, String> {
+    let path = data.solution.as_ref().ok_or("the maze is not solved")?;
+    Ok(path
+        .windows(2)
+        .filter_map(|pair| Direction::between(data.dimensions, pair[0], pair[1]))
+        .map(|direction| direction.letter())
+        .collect())
+}
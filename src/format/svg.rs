@@ -0,0 +1,121 @@
+#[cfg(feature = "wasm")]
+use super::poster::Tile;
+use super::MazeData;
+use crate::geometry::row_and_col;
+use crate::Direction;
+
+// Number of pixels in each cell dimension
+const CELL_PIXELS: usize = 20;
+
+/// Drawing of the maze as an SVG image.
+///
+/// Walls are black lines and the from and to cells are red circles. The solution, if any,
+/// is a red line through the centres of its cells.
+pub(crate) fn to_svg(data: &MazeData) -> String {
+    let (width, height) = data.dimensions;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"-1 -1 {0} {1}\">\n",
+        width * CELL_PIXELS + 2,
+        height * CELL_PIXELS + 2
+    );
+    svg += "<rect x=\"-1\" y=\"-1\" width=\"100%\" height=\"100%\" fill=\"white\"/>\n";
+    svg + &drawing(data) + "</svg>\n"
+}
+
+/// Drawing of the part of the maze of tile as an SVG image, for printing on a page of a
+/// poster. As `to_svg`, with crop marks at the corners of the cells within the tile, drawn
+/// out into the cells it overlaps.
+#[cfg(feature = "wasm")]
+pub(crate) fn to_svg_tile(data: &MazeData, tile: &Tile) -> String {
+    let overlap = tile.overlap * CELL_PIXELS;
+    let (left, top) = (tile.origin.0 * CELL_PIXELS, tile.origin.1 * CELL_PIXELS);
+    let (right, bottom) = (
+        left + tile.cells.0 * CELL_PIXELS,
+        top + tile.cells.1 * CELL_PIXELS,
+    );
+    // the viewed area, with a pixel more on each side for the walls on the boundary
+    let (x, y) = (
+        left as isize - overlap as isize - 1,
+        top as isize - overlap as isize - 1,
+    );
+    let (width, height) = (
+        right - left + 2 * overlap + 2,
+        bottom - top + 2 * overlap + 2,
+    );
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"{x} {y} {width} {height}\">\n"
+    );
+    svg += &format!(
+        "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"white\"/>\n"
+    );
+    svg += &drawing(data);
+
+    let length = overlap.max(CELL_PIXELS / 2);
+    let mut marks = String::new();
+    for (x, dx) in [(left, -1), (right, 1)] {
+        for (y, dy) in [(top, -1), (bottom, 1)] {
+            marks += &format!(
+                "M{x} {y}h{0}M{x} {y}v{1}",
+                dx * length as isize,
+                dy * length as isize
+            );
+        }
+    }
+    svg += &format!("<path d=\"{marks}\" stroke=\"blue\" stroke-width=\"0.5\" fill=\"none\"/>\n");
+    svg + "</svg>\n"
+}
+
+/// Walls, solution and from and to cells of the maze as SVG elements.
+fn drawing(data: &MazeData) -> String {
+    let (width, height) = data.dimensions;
+    let centre = |cell: usize| {
+        let (row, col) = row_and_col(data.dimensions, cell);
+        (
+            col * CELL_PIXELS + CELL_PIXELS / 2,
+            row * CELL_PIXELS + CELL_PIXELS / 2,
+        )
+    };
+
+    let mut svg = String::new();
+
+    // north and west walls of each cell, then the south and east boundary
+    let mut walls = String::new();
+    for cell in 0..data.walls.len() {
+        let (row, col) = row_and_col(data.dimensions, cell);
+        let (x, y) = (col * CELL_PIXELS, row * CELL_PIXELS);
+        if data.walls[cell] & Direction::North as u8 != 0 {
+            walls += &format!("M{x} {y}h{CELL_PIXELS}");
+        }
+        if data.walls[cell] & Direction::West as u8 != 0 {
+            walls += &format!("M{x} {y}v{CELL_PIXELS}");
+        }
+    }
+    walls += &format!("M0 {0}H{1}V0", height * CELL_PIXELS, width * CELL_PIXELS);
+    svg += &format!(
+        "<path d=\"{walls}\" stroke=\"black\" stroke-width=\"2\" stroke-linecap=\"round\" fill=\"none\"/>\n"
+    );
+
+    if let Some(path) = &data.solution {
+        let points = path
+            .iter()
+            .map(|cell| {
+                let (x, y) = centre(*cell);
+                format!("{x},{y}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        svg += &format!(
+            "<polyline points=\"{points}\" stroke=\"red\" stroke-width=\"4\" stroke-linejoin=\"round\" fill=\"none\"/>\n"
+        );
+    }
+
+    for cell in [data.from, data.to] {
+        let (x, y) = centre(cell);
+        svg += &format!(
+            "<circle cx=\"{x}\" cy=\"{y}\" r=\"{}\" fill=\"red\"/>\n",
+            CELL_PIXELS * 3 / 10
+        );
+    }
+    svg
+}
@@ -1,7 +1,332 @@
 pub(crate) mod generator;
+pub(crate) mod kruskal;
+#[cfg(feature = "wasm")]
+pub(crate) mod morph;
 pub(crate) mod randomised_depth_first_search;
+#[cfg(feature = "wasm")]
+pub(crate) mod shuffle;
 pub(crate) mod wilson;
 
 pub(crate) use generator::*;
+pub(crate) use kruskal::*;
 pub(crate) use randomised_depth_first_search::*;
 pub(crate) use wilson::*;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    #[cfg(feature = "wasm")]
+    use crate::analysis::{betweenness, longest_path};
+    #[cfg(feature = "wasm")]
+    use crate::format::binary::{from_bytes, to_bytes};
+    #[cfg(feature = "wasm")]
+    use crate::format::{self, MazeData};
+    use crate::parallel;
+    use crate::parameter::Values;
+    use crate::passage::carve;
+    #[cfg(feature = "wasm")]
+    use crate::path::distances;
+    use crate::path::shortest_path;
+    use crate::registry::{resolve, Registered};
+    use crate::rng::{Draws, Xorshift};
+    use crate::{Cells, Dimensions, Direction, Directions, DIRECTIONS};
+
+    #[cfg(feature = "wasm")]
+    use std::collections::BTreeMap;
+
+    /// Sizes and seeds of the randomised cases, small enough for every combination to run.
+    pub(crate) fn cases() -> impl Iterator<Item = (Dimensions, u64)> {
+        (2..=9)
+            .flat_map(|width| (2..=9).map(move |height| (width, height)))
+            .flat_map(|dimensions| (0..8).map(move |seed| (dimensions, seed)))
+    }
+
+    /// Cells of a maze generated with the named algorithm and seed.
+    pub(crate) fn generate(name: &str, dimensions: Dimensions, seed_value: u64) -> Cells {
+        let mut cells = Cells::new(dimensions);
+        let mut generator =
+            generators()[name].create(Box::new(Xorshift::new(seed_value)), &Values::default());
+        while generator.step(dimensions, &mut cells).is_continue() {}
+        cells
+    }
+
+    /// Assert the cells are a perfect maze; connected, without loops and with matching walls.
+    fn assert_perfect(dimensions: Dimensions, cells: &Cells, case: &str) {
+        let mut passages = 0;
+        for cell in 0..cells.len() {
+            for direction in DIRECTIONS {
+                match direction.neighbour(dimensions, cell) {
+                    None => assert!(
+                        cells.has_wall(cell, *direction),
+                        "{case}: boundary wall of {cell}"
+                    ),
+                    Some(neighbour) => {
+                        assert_eq!(
+                            cells.has_wall(cell, *direction),
+                            cells.has_wall(neighbour, direction.opposite()),
+                            "{case}: wall between {cell} and {neighbour}"
+                        );
+                        passages += usize::from(!cells.has_wall(cell, *direction));
+                    }
+                }
+            }
+        }
+        // each passage is counted from both of its cells; a tree has one fewer than cells
+        assert_eq!(passages / 2, cells.len() - 1, "{case}: number of passages");
+        for cell in 1..cells.len() {
+            assert!(
+                shortest_path(dimensions, cells, 0, cell).is_some(),
+                "{case}: cell {cell} is not connected"
+            );
+        }
+    }
+
+    #[test]
+    fn generated_mazes_are_perfect() {
+        for name in generators().into_keys() {
+            for (dimensions, seed) in cases() {
+                let cells = generate(&name, dimensions, seed);
+                let case = format!("{name} {dimensions:?} seed {seed}");
+                assert_perfect(dimensions, &cells, &case);
+                assert!(cells.walks.iter().all(Option::is_some), "{case}: walk");
+            }
+        }
+    }
+
+    #[test]
+    fn generating_with_the_same_seed_is_repeatable() {
+        for name in generators().into_keys() {
+            assert!(
+                generate(&name, (12, 8), 42).all_walls()
+                    == generate(&name, (12, 8), 42).all_walls(),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn fingerprints_tell_mazes_apart_by_their_walls() {
+        let dimensions = (12, 8);
+        let mut seen = BTreeMap::new();
+        for name in generators().into_keys() {
+            for seed in 0..4 {
+                let cells = generate(&name, dimensions, seed);
+                let data = MazeData::new(dimensions, &cells, 0, cells.len() - 1);
+                let fingerprint = data.fingerprint();
+                let case = format!("{name} seed {seed}");
+                assert_eq!(fingerprint.len(), 8, "{case}");
+                let imported = from_bytes(&to_bytes(&data)).expect("should import");
+                assert_eq!(imported.fingerprint(), fingerprint, "{case}: imported");
+                let walls = seen.entry(fingerprint).or_insert_with(|| cells.all_walls());
+                assert!(*walls == cells.all_walls(), "{case}: collision");
+            }
+        }
+        let walls = Cells::new((3, 2)).all_walls();
+        assert_ne!(
+            format::fingerprint((3, 2), &walls),
+            format::fingerprint((2, 3), &walls)
+        );
+    }
+
+    #[test]
+    fn completing_a_partial_maze_keeps_its_passages() {
+        let dimensions = (9, 7);
+        for seed in 0..8 {
+            // a corridor along the top row and another down the first column
+            let mut cells = Cells::new(dimensions);
+            let passages = (1..dimensions.0)
+                .map(|cell| (cell - 1, cell))
+                .chain((1..dimensions.1).map(|row| ((row - 1) * dimensions.0, row * dimensions.0)))
+                .collect::<Vec<_>>();
+            for (a, b) in &passages {
+                carve(&mut cells, dimensions, *a, *b);
+            }
+            let mut generator = Kruskal::new(Box::new(Xorshift::new(seed)));
+            while generator.step(dimensions, &mut cells).is_continue() {}
+            assert_perfect(dimensions, &cells, &format!("seed {seed}"));
+            for (a, b) in passages {
+                let direction = Direction::between(dimensions, a, b).expect("should neighbour");
+                assert!(
+                    !cells.has_wall(a, direction),
+                    "seed {seed}: passage {a} to {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn morphing_stays_perfect_and_ends_as_the_target() {
+        for name in generators().into_keys() {
+            for (dimensions, seed) in cases().step_by(5) {
+                let mut cells = generate(&name, dimensions, seed);
+                let target = generate(&name, dimensions, seed + 1);
+                let case = format!("{name} {dimensions:?} seed {seed}");
+                let walls = target.all_walls();
+                let mut morph = morph::Morph::new(Box::new(Xorshift::new(seed)), target);
+                while morph.step(dimensions, &mut cells).is_continue() {
+                    assert_perfect(dimensions, &cells, &case);
+                }
+                assert!(cells.all_walls() == walls, "{case}");
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn shuffling_stays_perfect_and_changes_the_maze_each_swap() {
+        for name in generators().into_keys() {
+            for (dimensions, seed) in cases().step_by(5) {
+                let mut cells = generate(&name, dimensions, seed);
+                let case = format!("{name} {dimensions:?} seed {seed}");
+                let mut shuffle = shuffle::Shuffle::new(Box::new(Xorshift::new(seed)), 4);
+                let mut walls = cells.all_walls();
+                while shuffle.step(dimensions, &mut cells).is_continue() {
+                    assert_perfect(dimensions, &cells, &case);
+                    assert!(cells.all_walls() != walls, "{case}: swap");
+                    walls = cells.all_walls();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn chosen_parameters_are_given_to_the_generator() {
+        let generators = generators();
+        let dimensions = (12, 8);
+        let create = |name: &str, values: &Values| {
+            let mut cells = Cells::new(dimensions);
+            let mut generator = generators[name].create(Box::new(Xorshift::new(42)), values);
+            while generator.step(dimensions, &mut cells).is_continue() {}
+            cells.all_walls()
+        };
+        let mut values = Values::default();
+        values.set("river", 0.8);
+        let id = "depth-first";
+        assert!(create(id, &values) != create(id, &Values::default()));
+        assert!(create(id, &values) == create("long-corridors", &Values::default()));
+    }
+
+    #[test]
+    fn generators_are_resolved_by_id_or_unique_name() {
+        let generators = generators();
+        for (id, generator) in &generators {
+            assert_eq!(
+                generators
+                    .values()
+                    .filter(|other| other.name() == generator.name())
+                    .count(),
+                1,
+                "{id}"
+            );
+            assert_eq!(&resolve(&generators, generator.name()), id);
+            assert_eq!(&resolve(&generators, id), id);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn longest_path_of_generated_mazes_is_their_diameter() {
+        for name in generators().into_keys() {
+            for (dimensions, seed) in cases().step_by(7) {
+                let cells = generate(&name, dimensions, seed);
+                let diameter = (0..cells.len())
+                    .flat_map(|from| distances(dimensions, &cells, from))
+                    .flatten()
+                    .max();
+                let path = longest_path(dimensions, &cells).expect("should have path");
+                let case = format!("{name} {dimensions:?} seed {seed}");
+                assert_eq!(Some(path.len() - 1), diameter, "{case}");
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn dead_ends_are_not_between_other_cells() {
+        for name in generators().into_keys() {
+            for (dimensions, seed) in cases().step_by(7) {
+                let cells = generate(&name, dimensions, seed);
+                let betweenness =
+                    betweenness(dimensions, &cells, &mut Xorshift::new(seed), cells.len());
+                for (cell, value) in betweenness.iter().enumerate() {
+                    let passages = DIRECTIONS
+                        .iter()
+                        .filter(|direction| !cells.has_wall(cell, **direction))
+                        .count();
+                    if passages == 1 {
+                        let case = format!("{name} {dimensions:?} seed {seed} cell {cell}");
+                        assert_eq!(*value, 0.0, "{case}");
+                    }
+                }
+                let most = betweenness.iter().copied().fold(0.0, f64::max);
+                assert_eq!(most, 1.0, "{name} {dimensions:?} seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn river_factor_shapes_corridors() {
+        // cells passed straight through, from west to east or north to south
+        let straight = |river: f64| {
+            let dimensions = (20, 20);
+            (0..8)
+                .map(|seed| {
+                    let mut cells = Cells::new(dimensions);
+                    let mut generator =
+                        RandomisedDepthFirstSearch::new(Box::new(Xorshift::new(seed)))
+                            .with_river(river);
+                    while generator.step(dimensions, &mut cells).is_continue() {}
+                    (0..cells.len())
+                        .filter(|cell| {
+                            let open = |direction| !cells.has_wall(*cell, direction);
+                            open(Direction::East) && open(Direction::West)
+                                || open(Direction::North) && open(Direction::South)
+                        })
+                        .count()
+                })
+                .sum::<usize>()
+        };
+        assert!(straight(0.8) > straight(0.0));
+        assert!(straight(0.0) > straight(-0.8));
+    }
+
+    #[test]
+    fn replaying_recorded_draws_is_repeatable() {
+        let dimensions = (12, 8);
+        for name in generators().into_keys() {
+            let draws = Draws::default();
+            let mut cells = Cells::new(dimensions);
+            let mut generator = generators()[&name].create(
+                draws.recorder(Box::new(Xorshift::new(7))),
+                &Values::default(),
+            );
+            while generator.step(dimensions, &mut cells).is_continue() {}
+
+            let draws = Draws::from_base64(&draws.to_base64()).expect("should deserialise");
+            let mut replayed = Cells::new(dimensions);
+            let mut generator = generators()[&name].create(draws.replayer(), &Values::default());
+            while generator.step(dimensions, &mut replayed).is_continue() {}
+            assert!(cells.all_walls() == replayed.all_walls(), "{name}");
+            assert_eq!(draws.len(), 0, "{name}: draws left");
+        }
+    }
+
+    #[test]
+    fn mazes_generated_in_parallel_are_those_generated_one_by_one() {
+        let dimensions = (16, 12);
+        let seeds = (0..16).collect::<Vec<u64>>();
+        for name in generators().into_keys() {
+            let mazes = parallel::map(&seeds, |seed| generate(&name, dimensions, *seed));
+            assert_eq!(mazes.len(), seeds.len(), "{name}");
+            for (seed, cells) in seeds.iter().zip(&mazes) {
+                let expected = generate(&name, dimensions, *seed);
+                assert!(
+                    cells.all_walls() == expected.all_walls(),
+                    "{name} seed {seed}"
+                );
+            }
+        }
+    }
+}
@@ -1,6 +1,158 @@
-use crate::{Cell, Dimensions};
+use crate::generate::{Kruskal, RandomisedDepthFirstSearch, Wilson};
+use crate::parameter::{Control, Parameter, Values};
+use crate::registry::{Category, Registered};
+use crate::rng::Rng;
+use crate::step::StepResult;
+use crate::{Cells, Dimensions};
+
+use std::collections::BTreeMap;
+
+// River factor of the randomised depth first search generating long corridors
+const LONG_CORRIDORS_RIVER: f64 = 0.8;
+
+// Keys of the river factor, and of starting from the corner, of the randomised depth first
+// search
+const RIVER: &str = "river";
+const CORNER: &str = "corner";
 
 pub(crate) trait Generator {
     /// Apply a step of the algorithm.
-    fn step(&mut self, dimensions: Dimensions, cells: &mut Vec<Cell>) -> bool;
+    fn step(&mut self, dimensions: Dimensions, cells: &mut Cells) -> StepResult<'_>;
+
+    /// Cells the algorithm is working from, to follow it while generating.
+    #[cfg(feature = "wasm")]
+    fn frontier(&self) -> &[usize] {
+        &[]
+    }
+}
+
+/// A type for a generator which can be created, with its display name, category and the
+/// parameters it declares.
+#[derive(Clone, Copy)]
+pub(crate) struct GeneratorKind {
+    name: &'static str,
+    category: Category,
+    parameters: &'static [Parameter],
+    create: fn(Box<dyn Rng>, &Values) -> Box<dyn Generator>,
+}
+
+impl GeneratorKind {
+    /// Create with display name and category from a function creating the generator using a
+    /// source of random numbers.
+    fn new(
+        name: &'static str,
+        category: Category,
+        create: fn(Box<dyn Rng>, &Values) -> Box<dyn Generator>,
+    ) -> Self {
+        Self {
+            name,
+            category,
+            parameters: &[],
+            create,
+        }
+    }
+
+    /// Set the parameters, whose values are given to the function creating the generator.
+    fn with_parameters(mut self, parameters: &'static [Parameter]) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Parameters declared by the generator.
+    pub(crate) fn parameters(&self) -> &'static [Parameter] {
+        self.parameters
+    }
+
+    /// Create the generator using rng, with the values chosen for its parameters; defaults
+    /// for any not chosen.
+    pub(crate) fn create(&self, rng: Box<dyn Rng>, chosen: &Values) -> Box<dyn Generator> {
+        (self.create)(rng, &Values::of(self.parameters, chosen))
+    }
+}
+
+impl Registered for GeneratorKind {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn category(&self) -> Category {
+        self.category
+    }
+}
+
+/// Generators by id.
+pub(crate) type Generators = BTreeMap<String, GeneratorKind>;
+
+// Parameter of starting the randomised depth first search from the corner
+const CORNER_PARAMETER: Parameter = Parameter {
+    key: CORNER,
+    label: "start from the top left corner",
+    control: Control::Checkbox,
+    default: 0.0,
+};
+
+/// Parameter of the river factor of the randomised depth first search, with default.
+const fn river(default: f64) -> Parameter {
+    Parameter {
+        key: RIVER,
+        label: "river factor, from winding to straight",
+        control: Control::Number {
+            minimum: -1.0,
+            maximum: 1.0,
+            step: 0.1,
+        },
+        default,
+    }
+}
+
+// Parameters of the randomised depth first search, evenly and with long corridors
+const RIVER_PARAMETERS: [Parameter; 2] = [river(0.0), CORNER_PARAMETER];
+const LONG_CORRIDORS_PARAMETERS: [Parameter; 2] = [river(LONG_CORRIDORS_RIVER), CORNER_PARAMETER];
+
+/// Available generators by id.
+pub(crate) fn generators() -> Generators {
+    let mut generators = Generators::new();
+    generators.insert(
+        "wilson".to_string(),
+        GeneratorKind::new("Wilson's algorithm", Category::SpanningTree, |rng, _| {
+            Box::new(Wilson::new(rng))
+        }),
+    );
+    generators.insert(
+        "kruskal".to_string(),
+        GeneratorKind::new("Kruskal's algorithm", Category::SpanningTree, |rng, _| {
+            Box::new(Kruskal::new(rng))
+        }),
+    );
+    generators.insert(
+        "depth-first".to_string(),
+        GeneratorKind::new(
+            "Randomised depth first search algorithm",
+            Category::DepthFirst,
+            |rng, values| {
+                Box::new(
+                    RandomisedDepthFirstSearch::new(rng)
+                        .with_river(values.number(RIVER))
+                        .with_corner(values.flag(CORNER)),
+                )
+            },
+        )
+        .with_parameters(&RIVER_PARAMETERS),
+    );
+    generators.insert(
+        "long-corridors".to_string(),
+        GeneratorKind::new(
+            "Randomised depth first search algorithm with long corridors",
+            Category::DepthFirst,
+            |rng, values| {
+                Box::new(
+                    RandomisedDepthFirstSearch::new(rng)
+                        .with_river(values.number(RIVER))
+                        .with_corner(values.flag(CORNER)),
+                )
+            },
+        )
+        .with_parameters(&LONG_CORRIDORS_PARAMETERS),
+    );
+    generators
 }
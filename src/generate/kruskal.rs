@@ -0,0 +1,130 @@
+use super::Generator;
+use crate::passage::carve;
+use crate::{Cells, Dimensions, Direction, Directions};
+
+use crate::platform::{log, Level};
+use crate::rng::Rng;
+use crate::step::StepResult;
+
+/// A type implementing a randomised [Kruskal's algorithm](https://en.wikipedia.org/wiki/Maze_generation_algorithm#Iterative_randomized_Kruskal's_algorithm_(with_sets))
+/// to generate a maze. Passages already carved are kept, so that a partial maze is completed
+/// into one containing them; a perfect maze unless they already form loops.
+pub(crate) struct Kruskal {
+    rng: Box<dyn Rng>,
+    initialised: bool,
+    // parent of each cell in the disjoint sets of connected cells
+    parents: Vec<usize>,
+    // walls between neighbouring cells in random order, as the cell and its east or south
+    // neighbour; those between connected cells are skipped when popped
+    walls: Vec<(usize, usize)>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Kruskal {
+    /// Create using rng for the order of the walls.
+    pub(crate) fn new(rng: Box<dyn Rng>) -> Self {
+        Self {
+            rng,
+            initialised: false,
+            parents: Vec::new(),
+            walls: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Representative cell of the set of cell, halving the path to it.
+    fn find(&mut self, mut cell: usize) -> usize {
+        while self.parents[cell] != cell {
+            self.parents[cell] = self.parents[self.parents[cell]];
+            cell = self.parents[cell];
+        }
+        cell
+    }
+
+    /// Join the sets of cells a and b. Returns whether they were apart.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[a] = b;
+        a != b
+    }
+
+    /// Reset data for the next generation.
+    fn reset(&mut self) {
+        self.initialised = false;
+        self.parents.clear();
+        self.walls.clear();
+    }
+}
+
+impl Generator for Kruskal {
+    /// Apply a step of the algorithm.
+    fn step(&mut self, dimensions: Dimensions, cells: &mut Cells) -> StepResult<'_> {
+        const WALK: usize = 0;
+
+        self.changed.clear();
+        if !self.initialised {
+            // start of the algorithm; join the cells of the passages already carved and
+            // shuffle the remaining walls
+            log(Level::Info, "create using Kruskal's algorithm");
+            self.parents = (0..cells.len()).collect();
+            let mut loops = 0;
+            for cell in 0..cells.len() {
+                for direction in [Direction::East, Direction::South] {
+                    let Some(neighbour) = direction.neighbour(dimensions, cell) else {
+                        continue;
+                    };
+                    match cells.has_wall(cell, direction) {
+                        true => self.walls.push((cell, neighbour)),
+                        false => {
+                            loops += usize::from(!self.union(cell, neighbour));
+                            for cell in [cell, neighbour] {
+                                if cells.walks[cell].is_none() {
+                                    cells.walks[cell] = Some(WALK);
+                                    self.changed.push(cell);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if loops > 0 {
+                log(
+                    Level::Info,
+                    format_args!("{loops} passages already form loops, so are kept"),
+                );
+            }
+            for i in (1..self.walls.len()).rev() {
+                let j = self.rng.index(i + 1);
+                self.walls.swap(i, j);
+            }
+            self.initialised = true;
+        } else {
+            // loop used to skip walls between connected cells in one step
+            loop {
+                match self.walls.pop() {
+                    None => {
+                        // end of algorithm; reset data
+                        log(Level::Info, "create is complete");
+                        self.reset();
+                        return StepResult::Finished;
+                    }
+                    Some((cell, neighbour)) => {
+                        if self.union(cell, neighbour) {
+                            carve(cells, dimensions, cell, neighbour);
+                            for cell in [cell, neighbour] {
+                                cells.walks[cell] = Some(WALK);
+                            }
+                            self.changed.extend([cell, neighbour]);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::Generator;
+use crate::passage::{differences, swap, wall};
+use crate::{Cells, Dimensions, Direction};
+
+use crate::platform::{log, Level};
+use crate::rng::Rng;
+use crate::step::StepResult;
+
+/// A type changing a maze into a target maze of the same dimensions, a passage at a time. Each
+/// passage of the target missing from the maze is carved while one not in the target on the
+/// way it closes is walled, so that a perfect maze stays perfect as it changes.
+pub(crate) struct Morph {
+    rng: Box<dyn Rng>,
+    target: Cells,
+    initialised: bool,
+    // passages of the target missing from the maze in random order, as the cell and its east
+    // or south neighbour
+    missing: Vec<(usize, usize)>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Morph {
+    /// Create changing into target, using rng for the order of the passages.
+    pub(crate) fn new(rng: Box<dyn Rng>, target: Cells) -> Self {
+        Self {
+            rng,
+            target,
+            initialised: false,
+            missing: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Whether the target has a passage between neighbouring cells a and b.
+    fn in_target(&self, dimensions: Dimensions, (a, b): (usize, usize)) -> bool {
+        Direction::between(dimensions, a, b)
+            .is_some_and(|direction| !self.target.has_wall(a, direction))
+    }
+}
+
+impl Generator for Morph {
+    /// Apply a step of the algorithm.
+    fn step(&mut self, dimensions: Dimensions, cells: &mut Cells) -> StepResult<'_> {
+        const WALK: usize = 0;
+
+        self.changed.clear();
+        if !self.initialised {
+            // start of the algorithm; shuffle the passages of the target to carve
+            log(Level::Info, "morph into the target maze");
+            if self.target.len() != cells.len() {
+                return StepResult::Failed("the target maze has other dimensions".to_string());
+            }
+            self.missing = differences(dimensions, cells, &self.target)
+                .into_iter()
+                .filter(|passage| self.in_target(dimensions, *passage))
+                .collect();
+            for i in (1..self.missing.len()).rev() {
+                let j = self.rng.index(i + 1);
+                self.missing.swap(i, j);
+            }
+            self.initialised = true;
+        } else if let Some((a, b)) = self.missing.pop() {
+            // the way closed by the passage holds at least one passage not in the target, as
+            // the target has no loops
+            let walled = {
+                let (target, rng) = (&self.target, &mut self.rng);
+                swap(cells, dimensions, (a, b), |passages| {
+                    let others = passages
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, (c, d))| {
+                            Direction::between(dimensions, *c, *d)
+                                .is_some_and(|direction| target.has_wall(*c, direction))
+                        })
+                        .map(|(index, _)| index)
+                        .collect::<Vec<_>>();
+                    (!others.is_empty()).then(|| others[rng.index(others.len())])
+                })
+            };
+            self.changed.extend([a, b]);
+            self.changed
+                .extend(walled.into_iter().flat_map(|(c, d)| [c, d]));
+        } else {
+            // end of algorithm; wall passages not in the target that remain, such as the
+            // loops of a maze which was not perfect
+            for (a, b) in differences(dimensions, cells, &self.target) {
+                wall(cells, dimensions, a, b);
+                self.changed.extend([a, b]);
+            }
+            log(Level::Info, "morph is complete");
+            self.initialised = false;
+            return StepResult::Finished;
+        }
+        for cell in &self.changed {
+            cells.walks[*cell].get_or_insert(WALK);
+        }
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+}
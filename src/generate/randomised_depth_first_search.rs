@@ -1,27 +1,92 @@
 use super::Generator;
-use crate::{Cell, Dimensions, Direction, DIRECTIONS};
+use crate::direction::{Direction, Neighbours};
+use crate::passage::carve;
+use crate::{Cells, Dimensions, Directions, DIRECTIONS};
 
-use js_sys::Math::random;
+use crate::platform::{log, Level};
+use crate::rng::Rng;
+use crate::step::StepResult;
 
 /// A type implementing a randomised [depth first search](https://en.wikipedia.org/wiki/Depth-first_search)
 /// algorithm to generate a maze.
-#[derive(Default)]
 pub(crate) struct RandomisedDepthFirstSearch {
+    rng: Box<dyn Rng>,
+    // river factor, from -1 to 1; the chance of preferring to continue straight if positive,
+    // or to turn if negative, otherwise choosing neighbours evenly
+    river: f64,
+    // whether to start from the top left cell instead of a random one
+    corner: bool,
     initialised: bool,
     // stack of cell indexes
     stack: Vec<usize>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl RandomisedDepthFirstSearch {
+    /// Create using rng for the choice of neighbours.
+    pub(crate) fn new(rng: Box<dyn Rng>) -> Self {
+        Self {
+            rng,
+            river: 0.0,
+            corner: false,
+            initialised: false,
+            stack: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Set the river factor, from -1 to 1; positive for longer straight corridors, negative
+    /// for more winding ones, and 0 to choose neighbours evenly.
+    pub(crate) fn with_river(mut self, river: f64) -> Self {
+        self.river = river.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Set whether to start from the top left cell, for a maze growing from a corner.
+    pub(crate) fn with_corner(mut self, corner: bool) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// Index of the neighbour chosen, where straight is that continuing straight on, if
+    /// unvisited. Without a river factor only one number is drawn, so that seeds give the
+    /// same mazes as before it.
+    fn choose(&mut self, neighbours: &[usize], straight: Option<usize>) -> usize {
+        let len = neighbours.len();
+        if self.river == 0.0 {
+            return self.rng.index(len);
+        }
+        match straight.and_then(|straight| neighbours.iter().position(|cell| *cell == straight)) {
+            Some(idx) if self.river > 0.0 && self.rng.random() < self.river => idx,
+            Some(idx) if self.river < 0.0 && len > 1 && self.rng.random() < -self.river => {
+                // any of the others, which all turn
+                let other = self.rng.index(len - 1);
+                other + usize::from(other >= idx)
+            }
+            _ => self.rng.index(len),
+        }
+    }
 }
 
 impl Generator for RandomisedDepthFirstSearch {
     /// Apply a step of the algorithm.
-    fn step(&mut self, dimensions: Dimensions, cells: &mut Vec<Cell>) -> bool {
+    fn step(&mut self, dimensions: Dimensions, cells: &mut Cells) -> StepResult<'_> {
         const WALK: usize = 0;
 
+        self.changed.clear();
         if !self.initialised {
-            // start of the algorithm; select a random from cell
-            web_sys::console::log_1(&"create using randomised depth first search algorithm".into());
-            let from = (random() * cells.len() as f64) as usize;
-            cells[from].walk = Some(WALK);
+            // start of the algorithm; select a random from cell, unless from the corner
+            log(
+                Level::Info,
+                "create using randomised depth first search algorithm",
+            );
+            let from = match self.corner {
+                true => 0,
+                false => self.rng.index(cells.len()),
+            };
+            cells.walks[from] = Some(WALK);
+            self.changed.push(from);
             self.initialised = true;
             self.stack.push(from);
         } else {
@@ -30,10 +95,10 @@ impl Generator for RandomisedDepthFirstSearch {
                 match self.stack.pop() {
                     None => {
                         // end of algorithm; reset data
-                        web_sys::console::log_1(&"create is complete".into());
+                        log(Level::Info, "create is complete");
                         self.initialised = false;
                         self.stack.clear();
-                        return false;
+                        return StepResult::Finished;
                     }
                     Some(cell) => {
                         let neighbour = {
@@ -41,39 +106,29 @@ impl Generator for RandomisedDepthFirstSearch {
                             let neighbours = DIRECTIONS
                                 .iter()
                                 .filter_map(|direction| direction.neighbour(dimensions, cell))
-                                .filter(|neighbour| cells[*neighbour].walk.is_none())
-                                .collect::<Vec<_>>();
+                                .filter(|neighbour| cells.walks[*neighbour].is_none())
+                                .collect::<Neighbours>();
+
+                            // the neighbour continuing the move from the previous cell
+                            let straight = self
+                                .stack
+                                .last()
+                                .and_then(|previous| {
+                                    Direction::between(dimensions, *previous, cell)
+                                })
+                                .and_then(|direction| direction.neighbour(dimensions, cell));
 
                             // pick neighbour (if any) at random
                             match neighbours.len() {
                                 0 => None,
-                                len => Some(neighbours[(random() * len as f64) as usize]),
+                                _ => Some(neighbours[self.choose(&neighbours, straight)]),
                             }
                         };
 
                         if let Some(neighbour) = neighbour {
-                            cells[neighbour].walk = Some(WALK);
-                            match Direction::between(dimensions, cell, neighbour) {
-                                Some(direction) => match direction {
-                                    Direction::First => {
-                                        cells[cell].remove_wall(Direction::First);
-                                        cells[neighbour].remove_wall(Direction::Third);
-                                    }
-                                    Direction::Second => {
-                                        cells[cell].remove_wall(Direction::Second);
-                                        cells[neighbour].remove_wall(Direction::Forth);
-                                    }
-                                    Direction::Third => {
-                                        cells[cell].remove_wall(Direction::Third);
-                                        cells[neighbour].remove_wall(Direction::First);
-                                    }
-                                    Direction::Forth => {
-                                        cells[cell].remove_wall(Direction::Forth);
-                                        cells[neighbour].remove_wall(Direction::Second);
-                                    }
-                                },
-                                None => unreachable!(),
-                            }
+                            cells.walks[neighbour] = Some(WALK);
+                            carve(cells, dimensions, cell, neighbour);
+                            self.changed.extend([cell, neighbour]);
                             self.stack.push(cell);
                             self.stack.push(neighbour);
                             break;
@@ -83,6 +138,14 @@ impl Generator for RandomisedDepthFirstSearch {
             }
         }
 
-        true
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    /// Cells the algorithm is working from; the top of the stack, where the search continues.
+    #[cfg(feature = "wasm")]
+    fn frontier(&self) -> &[usize] {
+        &self.stack[self.stack.len().saturating_sub(1)..]
     }
 }
@@ -0,0 +1,76 @@
+use super::Generator;
+use crate::passage::swap;
+use crate::{Cells, Dimensions, Direction, Directions};
+
+use crate::platform::{log, Level};
+use crate::rng::Rng;
+use crate::step::StepResult;
+
+// Walls drawn at random for a swap before giving up on finding one
+const ATTEMPTS: usize = 64;
+
+/// A type changing a maze by random swaps; a wall is carved, and one of the passages on the
+/// way it closes is walled, so that a perfect maze stays perfect without generating it again.
+pub(crate) struct Shuffle {
+    rng: Box<dyn Rng>,
+    // swaps still to make
+    swaps: usize,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Shuffle {
+    /// Create making swaps, using rng for the walls and passages swapped.
+    pub(crate) fn new(rng: Box<dyn Rng>, swaps: usize) -> Self {
+        Self {
+            rng,
+            swaps,
+            changed: Vec::new(),
+        }
+    }
+
+    /// A random wall between neighbouring cells, as the cell and its east or south neighbour.
+    /// `None` if none is found in the attempts.
+    fn wall(&mut self, dimensions: Dimensions, cells: &Cells) -> Option<(usize, usize)> {
+        (0..ATTEMPTS).find_map(|_| {
+            let cell = self.rng.index(cells.len());
+            let direction = match self.rng.index(2) {
+                0 => Direction::East,
+                _ => Direction::South,
+            };
+            direction
+                .neighbour(dimensions, cell)
+                .filter(|_| cells.has_wall(cell, direction))
+                .map(|neighbour| (cell, neighbour))
+        })
+    }
+}
+
+impl Generator for Shuffle {
+    /// Apply a step of the algorithm.
+    fn step(&mut self, dimensions: Dimensions, cells: &mut Cells) -> StepResult<'_> {
+        self.changed.clear();
+        let wall = match self.swaps {
+            0 => None,
+            _ => self.wall(dimensions, cells),
+        };
+        let Some((a, b)) = wall else {
+            // end of algorithm
+            log(Level::Info, "shuffle is complete");
+            self.swaps = 0;
+            return StepResult::Finished;
+        };
+        let rng = &mut self.rng;
+        let walled = swap(cells, dimensions, (a, b), |passages| {
+            Some(rng.index(passages.len()))
+        });
+        self.changed.extend([a, b]);
+        self.changed
+            .extend(walled.into_iter().flat_map(|(c, d)| [c, d]));
+        self.swaps -= 1;
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+}
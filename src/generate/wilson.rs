@@ -1,48 +1,83 @@
 use super::Generator;
-use crate::{Cell, Dimensions, Direction, DIRECTIONS};
+use crate::direction::Neighbours;
+use crate::passage::carve;
+use crate::{Cells, Dimensions, Directions, DIRECTIONS};
 
-use js_sys::Math::random;
+use crate::platform::{log, Level};
+use crate::rng::Rng;
+use crate::step::StepResult;
 
 /// A type implementing [Wilson's algorithm](https://en.wikipedia.org/wiki/Loop-erased_random_walk)
 /// to generate a maze.
-#[derive(Default)]
 pub(crate) struct Wilson {
+    rng: Box<dyn Rng>,
     // current walk index; if None then start of the algorithm
     walk: Option<usize>,
     // stack of cell indexes for the current walk; if empty then start of new walk
     stack: Vec<usize>,
+    // cell indexes in random order from which new walks start; cells which have since
+    // joined a complete walk are skipped when popped
+    unvisited: Vec<usize>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Wilson {
+    /// Create using rng for the random walks.
+    pub(crate) fn new(rng: Box<dyn Rng>) -> Self {
+        Self {
+            rng,
+            walk: None,
+            stack: Vec::new(),
+            unvisited: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
 }
 
 impl Generator for Wilson {
     /// Apply a step of the algorithm.
-    fn step(&mut self, dimensions: Dimensions, cells: &mut Vec<Cell>) -> bool {
+    fn step(&mut self, dimensions: Dimensions, cells: &mut Cells) -> StepResult<'_> {
+        self.changed.clear();
         match self.walk {
             None => {
                 // start of the algorithm; select a single random cell
                 // which is the destination of the first complete walk
-                web_sys::console::log_1(&"create using Wilson's algorithm".into());
-                let idx = (random() * cells.len() as f64) as usize;
-                cells[idx].walk = Some(0);
+                log(Level::Info, "create using Wilson's algorithm");
+                let idx = self.rng.index(cells.len());
+                cells.walks[idx] = Some(0);
+                self.changed.push(idx);
                 self.walk = Some(1);
+
+                // shuffle the cells
+                self.unvisited = (0..cells.len()).collect();
+                for i in (1..self.unvisited.len()).rev() {
+                    let j = self.rng.index(i + 1);
+                    self.unvisited.swap(i, j);
+                }
             }
             Some(walk) => {
                 match self.stack.last() {
                     None => {
-                        match cells
-                            .iter()
-                            .enumerate()
-                            .find(|(_, cell)| cell.walk.is_none())
+                        while self
+                            .unvisited
+                            .last()
+                            .is_some_and(|idx| cells.walks[*idx].is_some())
                         {
+                            self.unvisited.pop();
+                        }
+                        match self.unvisited.pop() {
                             None => {
                                 // end of algorithm; reset data
-                                web_sys::console::log_1(&"create is complete".into());
+                                log(Level::Info, "create is complete");
                                 self.walk = None;
                                 self.stack.clear();
-                                return false;
+                                return StepResult::Finished;
                             }
-                            Some((idx, _)) => {
+                            Some(idx) => {
                                 // start of new walk
-                                cells[idx].walk = Some(walk);
+                                cells.walks[idx] = Some(walk);
+                                self.changed.push(idx);
                                 self.stack.push(idx);
                             }
                         }
@@ -55,53 +90,41 @@ impl Generator for Wilson {
                             let neighbours = DIRECTIONS
                                 .iter()
                                 .filter_map(|direction| direction.neighbour(dimensions, *cell))
-                                .collect::<Vec<_>>();
+                                .collect::<Neighbours>();
 
                             // pick neighbour at random
-                            neighbours[(random() * neighbours.len() as f64) as usize]
+                            neighbours[self.rng.index(neighbours.len())]
                         };
 
-                        match cells[neighbour].walk {
+                        match cells.walks[neighbour] {
                             None => {
                                 // add cell to current walk
-                                cells[neighbour].walk = Some(walk);
+                                cells.walks[neighbour] = Some(walk);
+                                self.changed.push(neighbour);
                                 self.stack.push(neighbour);
                             }
                             Some(neighbour_walk) => {
                                 if walk == neighbour_walk {
                                     // encountered the current walk; erase the loop
+                                    log(
+                                        Level::Trace,
+                                        format_args!(
+                                            "walk {walk} erases a loop at cell {neighbour}"
+                                        ),
+                                    );
                                     while *self.stack.last().unwrap() != neighbour {
-                                        cells[self.stack.pop().unwrap()].walk = None;
+                                        let last = self.stack.pop().unwrap();
+                                        cells.walks[last] = None;
+                                        self.changed.push(last);
                                     }
                                 } else {
                                     // encountered a previous walk; complete the current walk
-                                    web_sys::console::log_1(
-                                        &format!("walk {walk} is complete").into(),
-                                    );
+                                    log(Level::Debug, format_args!("walk {walk} is complete"));
                                     self.walk = Some(walk + 1);
+                                    self.changed.push(neighbour);
                                     while let Some(last) = self.stack.pop() {
-                                        match Direction::between(dimensions, last, neighbour) {
-                                            Some(direction) => match direction {
-                                                Direction::First => {
-                                                    cells[last].remove_wall(Direction::First);
-                                                    cells[neighbour].remove_wall(Direction::Third);
-                                                }
-                                                Direction::Second => {
-                                                    cells[last].remove_wall(Direction::Second);
-                                                    cells[neighbour].remove_wall(Direction::Forth);
-                                                }
-                                                Direction::Third => {
-                                                    cells[last].remove_wall(Direction::Third);
-                                                    cells[neighbour].remove_wall(Direction::First);
-                                                }
-                                                Direction::Forth => {
-                                                    cells[last].remove_wall(Direction::Forth);
-                                                    cells[neighbour].remove_wall(Direction::Second);
-                                                }
-                                            },
-                                            None => unreachable!(),
-                                        }
-
+                                        carve(cells, dimensions, last, neighbour);
+                                        self.changed.push(last);
                                         neighbour = last;
                                     }
                                 }
@@ -112,6 +135,14 @@ impl Generator for Wilson {
             }
         }
 
-        true
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    /// Cells the algorithm is working from; those of the current walk.
+    #[cfg(feature = "wasm")]
+    fn frontier(&self) -> &[usize] {
+        &self.stack
     }
 }
@@ -0,0 +1,110 @@
+use crate::app::window;
+use crate::Dimensions;
+
+use std::collections::BTreeMap;
+
+// Key of the leaderboard in local storage
+const STORAGE_KEY: &str = "wasm-maze-leaderboard";
+
+// Number of best times kept for each maze
+const BEST_TIMES: usize = 5;
+
+/// A type for the best times of reaching the goal in play, in seconds, for each maze
+/// generated from a seed, kept in local storage.
+#[derive(Default)]
+pub(crate) struct Leaderboard {
+    times: BTreeMap<String, Vec<f64>>,
+}
+
+impl Leaderboard {
+    /// Load from local storage, or empty if unavailable.
+    pub(crate) fn load() -> Self {
+        let text = window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .unwrap_or_default();
+        // times do not contain '=' so split at the last in each line
+        Self {
+            times: text
+                .lines()
+                .filter_map(|line| line.rsplit_once('='))
+                .map(|(maze, times)| {
+                    let times = times
+                        .split(',')
+                        .filter_map(|time| time.parse().ok())
+                        .collect();
+                    (maze.to_string(), times)
+                })
+                .collect(),
+        }
+    }
+
+    /// Save to local storage. Returns whether saved.
+    fn save(&self) -> bool {
+        let text = self
+            .times
+            .iter()
+            .map(|(maze, times)| {
+                let times = times.iter().map(f64::to_string).collect::<Vec<_>>();
+                format!("{maze}={}", times.join(","))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .is_some_and(|storage| storage.set_item(STORAGE_KEY, &text).is_ok())
+    }
+
+    /// Best times for maze, fastest first.
+    pub(crate) fn best(&self, maze: &str) -> &[f64] {
+        self.times.get(maze).map_or(&[], Vec::as_slice)
+    }
+
+    /// Record time for maze, keeping the best. Returns the position of the time among the
+    /// best, 0 being a personal best, or `None` if not among them.
+    pub(crate) fn record(&mut self, maze: &str, seconds: f64) -> Option<usize> {
+        let times = self.times.entry(maze.to_string()).or_default();
+        let position = times.partition_point(|time| *time <= seconds);
+        if position >= BEST_TIMES {
+            return None;
+        }
+        times.insert(position, seconds);
+        times.truncate(BEST_TIMES);
+        self.save();
+        Some(position)
+    }
+}
+
+/// Key of the maze of dimensions generated by the named generator from seed; on one line
+/// without '='.
+pub(crate) fn maze_key(dimensions: Dimensions, generator: &str, seed: u64) -> String {
+    format!(
+        "{} by {}, {} seed {seed}",
+        dimensions.0,
+        dimensions.1,
+        generator.replace(['=', '\n'], " ")
+    )
+}
+
+/// Best times for maze as text, with the time at position marked.
+pub(crate) fn describe(times: &[f64], position: Option<usize>) -> String {
+    match times.is_empty() {
+        true => "No best times yet for this maze".to_string(),
+        false => format!(
+            "Best times: {}",
+            times
+                .iter()
+                .enumerate()
+                .map(|(idx, time)| match Some(idx) == position {
+                    true => format!("{}. {time:.1} s (new)", idx + 1),
+                    false => format!("{}. {time:.1} s", idx + 1),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
@@ -1,48 +1,82 @@
 // An implementation of some maze generating and solving algorithms in WebAssembly.
-
+//
+// The data structures and algorithms build natively without the `wasm` feature, for tests
+// and native tools; items only the app uses are gated on it.
+
+#[cfg(feature = "wasm")]
+mod analysis;
+#[cfg(feature = "wasm")]
+mod app;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bench;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+#[cfg(feature = "wasm")]
+mod compare;
+#[cfg(feature = "wasm")]
+mod diagnostics;
 mod direction;
+#[cfg(feature = "app")]
+mod element;
+#[cfg(feature = "wasm")]
+mod embed;
+#[cfg(feature = "wasm")]
+mod error;
+mod format;
 mod generate;
 mod geometry;
+#[cfg(feature = "wasm")]
+mod leaderboard;
+#[cfg(feature = "wasm")]
+mod library;
+#[cfg(feature = "wasm")]
+mod maze;
+mod movement;
+mod parallel;
+mod parameter;
+mod passage;
+mod path;
+mod platform;
+#[cfg(feature = "wasm")]
+mod play;
+#[cfg(feature = "wasm")]
+mod qr;
+#[cfg(feature = "wasm")]
+mod recording;
+mod registry;
+mod rng;
+#[cfg(feature = "wasm")]
+mod settings;
 mod solve;
+#[cfg(feature = "wasm")]
+mod sound;
+mod step;
+#[cfg(feature = "wasm")]
+mod theme;
+#[cfg(feature = "wasm")]
+mod ui;
+#[cfg(feature = "wasm")]
+mod video;
 
-use direction::{Direction, DIRECTIONS};
-use geometry::row_and_col;
-
-use js_sys::Math::random;
-use wasm_bindgen::prelude::*;
-use web_sys::{
-    CanvasRenderingContext2d, Event, HtmlButtonElement, HtmlCanvasElement, HtmlInputElement,
-    HtmlOptionElement, HtmlSelectElement,
-};
-
-use std::cell::RefCell;
-use std::collections::BTreeMap;
-use std::rc::Rc;
-use std::sync::{Arc, Mutex};
+use direction::{Direction, Directions, DIRECTIONS};
 
-// Default number of cells
-const DEFAULT_WIDTH: u32 = 20;
-const DEFAULT_HEIGHT: u32 = 20;
+use std::collections::HashMap;
 
-// Number of pixels in each cell dimension
-const CELL_PIXELS: u32 = 20;
+// Minimum number of cells in each dimension
+const MINIMUM_DIMENSION: usize = 2;
 
-// Fill and stroke styles
-const BACKGROUND_STYLE: &str = "rgb(255,255,255)";
-const CELL_BORDER_STYLE: &str = "rgb(0,0,0)";
-const FROM_TO_STYLE: &str = "rgb(255,0,0)";
-const SEARCH_STYLE: &str = "rgba(255,127,0,0.5)";
-const RESULT_STYLE: &str = "rgb(255,0,0)";
+type Dimensions = (usize, usize);
 
-// Stroke widths
-const CELL_BORDER_WIDTH: f64 = 2.0;
-const SEARCH_LINE_WIDTH: f64 = 2.0;
-const RESULT_LINE_WIDTH: f64 = 4.0;
+// Number of cells whose walls are stored in each byte of `Cells`
+const CELLS_PER_WALL_BYTE: usize = 4;
 
-type Dimensions = (usize, usize);
+// Bits of all the walls of a cell
+#[cfg(any(feature = "wasm", test))]
+const ALL_WALLS: u8 =
+    Direction::North as u8 | Direction::East as u8 | Direction::South as u8 | Direction::West as u8;
 
-/// Solution details for `Cell`.
-#[derive(Clone, Default)]
+/// Solution details of a cell in `Cells`.
+#[derive(Clone, Copy, Default, PartialEq)]
 struct CellSolution {
     from: bool,
     to: bool,
@@ -50,464 +84,160 @@ struct CellSolution {
     result: bool,
 }
 
-/// A type for a cell in a maze.
+/// A type for the cells of a maze.
+///
+/// The walls, unchanged once generated, are held apart from the state of the algorithms
+/// overlaid on them so that a solve is cleared without touching the walls. Only the east
+/// and south walls of each cell are stored, packed four cells to a byte; the north and west
+/// walls are those of the neighbours, and the walls on the boundary are always present.
 #[derive(Clone)]
-struct Cell {
-    // bit per wall; 0 is removed, 1 is present
-    walls: u8,
-    // walk index from generator
-    walk: Option<usize>,
-    // solution details
-    solution: CellSolution,
+struct Cells {
+    dimensions: Dimensions,
+    // bit per stored wall, east then south, of each cell from the least significant bit;
+    // 0 is removed, 1 is present
+    walls: Vec<u8>,
+    // walk index of each cell from generator
+    walks: Vec<Option<usize>>,
+    // solution details of the cells changed by a solver; others have the default
+    solutions: HashMap<usize, CellSolution>,
 }
 
-impl Default for Cell {
-    fn default() -> Self {
+impl Cells {
+    /// Create the cells of dimensions with all walls present.
+    fn new(dimensions: Dimensions) -> Self {
+        let count = dimensions.0 * dimensions.1;
         Self {
-            walls: DIRECTIONS
-                .iter()
-                .fold(0, |accumulator, direction| accumulator + *direction as u8),
-            walk: None,
-            solution: CellSolution::default(),
+            dimensions,
+            walls: vec![u8::MAX; count.div_ceil(CELLS_PER_WALL_BYTE)],
+            walks: vec![None; count],
+            solutions: HashMap::new(),
         }
     }
-}
 
-impl Cell {
-    /// Remove wall.
-    fn remove_wall(&mut self, direction: Direction) {
-        self.walls &= !(direction as u8);
+    /// Create the cells of dimensions with the walls of each cell; bit per wall.
+    fn from_walls(dimensions: Dimensions, walls: &[u8]) -> Self {
+        let mut cells = Self::new(dimensions);
+        for (cell, walls) in walls.iter().enumerate() {
+            cells.set_walls(cell, *walls);
+        }
+        cells
     }
 
-    /// Weather wall is present.
-    fn has_wall(&self, direction: Direction) -> bool {
-        self.walls & direction as u8 > 0
+    /// Number of cells.
+    fn len(&self) -> usize {
+        self.walks.len()
     }
 
-    /// Draw into canvas.
-    fn draw(&self, dimensions: Dimensions, idx: usize, context: &CanvasRenderingContext2d) {
-        // Drawing references:
-        // - https://developer.mozilla.org/en-US/docs/Web/API/Canvas_API/Tutorial/Drawing_shapes
-        // - https://developer.mozilla.org/en-US/docs/Web/API/Canvas_API/Tutorial/Applying_styles_and_colors
-
-        let (row, col) = row_and_col(dimensions, idx);
-        let (x, y) = (col * CELL_PIXELS as usize, row * CELL_PIXELS as usize);
-        match self.walk {
-            Some(_) => {
-                context.set_line_width(CELL_BORDER_WIDTH);
-                context.set_stroke_style(&JsValue::from_str(CELL_BORDER_STYLE));
-                context.begin_path();
-                if self.has_wall(Direction::First) {
-                    context.move_to(x as f64, y as f64);
-                    context.line_to((x + CELL_PIXELS as usize) as f64, y as f64);
-                }
-                if self.has_wall(Direction::Second) {
-                    context.move_to((x + CELL_PIXELS as usize) as f64, y as f64);
-                    context.line_to(
-                        (x + CELL_PIXELS as usize) as f64,
-                        (y + CELL_PIXELS as usize) as f64,
-                    );
-                }
-                if self.has_wall(Direction::Third) {
-                    context.move_to(
-                        (x + CELL_PIXELS as usize) as f64,
-                        (y + CELL_PIXELS as usize) as f64,
-                    );
-                    context.line_to(x as f64, (y + CELL_PIXELS as usize) as f64);
-                }
-                if self.has_wall(Direction::Forth) {
-                    context.move_to(x as f64, (y + CELL_PIXELS as usize) as f64);
-                    context.line_to(x as f64, y as f64);
-                }
-                context.stroke();
+    /// Byte and mask of the bit storing the wall of cell in direction. `None` if the wall
+    /// is on the boundary.
+    fn wall_bit(&self, cell: usize, direction: Direction) -> Option<(usize, u8)> {
+        let neighbour = direction.neighbour(self.dimensions, cell)?;
+        let (cell, bit) = match direction {
+            Direction::North => (neighbour, 1),
+            Direction::East => (cell, 0),
+            Direction::South => (cell, 1),
+            Direction::West => (neighbour, 0),
+        };
+        Some((
+            cell / CELLS_PER_WALL_BYTE,
+            1 << (cell % CELLS_PER_WALL_BYTE * 2 + bit),
+        ))
+    }
 
-                if self.solution.from {
-                    context.set_fill_style(&JsValue::from_str(FROM_TO_STYLE));
-                    context.begin_path();
-                    let _ = context.arc(
-                        x as f64 + CELL_PIXELS as f64 / 2.0,
-                        y as f64 + CELL_PIXELS as f64 / 2.0,
-                        CELL_PIXELS as f64 * 0.4,
-                        0.0,
-                        std::f64::consts::TAU,
-                    );
-                    context.fill();
-                }
+    /// Walls of cell; bit per wall.
+    fn walls(&self, cell: usize) -> u8 {
+        DIRECTIONS
+            .iter()
+            .filter(|direction| self.has_wall(cell, **direction))
+            .fold(0, |walls, direction| walls | *direction as u8)
+    }
 
-                if self.solution.to {
-                    context.set_line_width(CELL_PIXELS as f64 * 0.1);
-                    context.set_stroke_style(&JsValue::from_str(FROM_TO_STYLE));
-                    context.begin_path();
-                    let _ = context.arc(
-                        x as f64 + CELL_PIXELS as f64 / 2.0,
-                        y as f64 + CELL_PIXELS as f64 / 2.0,
-                        CELL_PIXELS as f64 * 0.3,
-                        0.0,
-                        std::f64::consts::TAU,
-                    );
-                    context.stroke();
-                }
+    /// Stored walls, packed as described for the type; equal exactly when the walls of cells
+    /// of the same dimensions are.
+    #[cfg(feature = "wasm")]
+    fn packed_walls(&self) -> &[u8] {
+        &self.walls
+    }
 
-                if let Some(previous) = self.solution.previous {
-                    let (prev_row, prev_col) = row_and_col(dimensions, previous);
-                    let (prev_x, prev_y) = (
-                        prev_col * CELL_PIXELS as usize,
-                        prev_row * CELL_PIXELS as usize,
-                    );
+    /// Walls of each cell; bit per wall.
+    fn all_walls(&self) -> Vec<u8> {
+        (0..self.len()).map(|cell| self.walls(cell)).collect()
+    }
 
-                    context.set_line_width(match self.solution.result {
-                        true => RESULT_LINE_WIDTH,
-                        false => SEARCH_LINE_WIDTH,
-                    });
-                    context.set_stroke_style(&JsValue::from_str(match self.solution.result {
-                        true => RESULT_STYLE,
-                        false => SEARCH_STYLE,
-                    }));
-                    context.begin_path();
-                    context.move_to(
-                        prev_x as f64 + CELL_PIXELS as f64 / 2.0,
-                        prev_y as f64 + CELL_PIXELS as f64 / 2.0,
-                    );
-                    context.line_to(
-                        x as f64 + CELL_PIXELS as f64 / 2.0,
-                        y as f64 + CELL_PIXELS as f64 / 2.0,
-                    );
-                    context.stroke();
+    /// Set the walls of cell; bit per wall. Walls on the boundary are always present.
+    fn set_walls(&mut self, cell: usize, walls: u8) {
+        for direction in DIRECTIONS {
+            if let Some((byte, mask)) = self.wall_bit(cell, *direction) {
+                match walls & *direction as u8 != 0 {
+                    true => self.walls[byte] |= mask,
+                    false => self.walls[byte] &= !mask,
                 }
             }
-            None => {
-                context.set_fill_style(&JsValue::from_str(CELL_BORDER_STYLE));
-                context.fill_rect(x as f64, y as f64, CELL_PIXELS as f64, CELL_PIXELS as f64);
-            }
         }
     }
-}
-
-/// A type indicating the phase of the application.
-#[derive(Clone)]
-enum Phase {
-    Generate,
-    Solve,
-    Complete,
-}
-
-fn window() -> web_sys::Window {
-    web_sys::window().expect("should have window")
-}
-
-fn request_animation_frame(f: &Closure<dyn FnMut()>) {
-    window()
-        .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("should register request animation frame callback");
-}
-
-/// Entry point of the application.
-#[wasm_bindgen(start)]
-pub fn main() -> Result<(), JsValue> {
-    let document = window().document().expect("should have document");
-    let body = document.body().ok_or("should have document body")?;
-
-    let canvas = document
-        .create_element("canvas")?
-        .dyn_into::<HtmlCanvasElement>()?;
-    canvas.set_width(DEFAULT_WIDTH * CELL_PIXELS);
-    canvas.set_height(DEFAULT_HEIGHT * CELL_PIXELS);
-    body.append_child(&canvas)?;
-
-    let context = canvas
-        .get_context("2d")?
-        .expect("should have 2d context")
-        .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
-    context.set_line_cap("round");
-
-    let div = document.create_element("div")?;
-    div.set_text_content(Some("Generator"));
-    body.append_child(&div)?;
-
-    let div = document.create_element("div")?;
-    body.append_child(&div)?;
-
-    let input_width = document
-        .create_element("input")?
-        .dyn_into::<HtmlInputElement>()?;
-    input_width.set_type("number");
-    input_width.set_min("2");
-    input_width.set_value(DEFAULT_WIDTH.to_string().as_str());
-    div.append_child(&input_width)?;
-
-    let label = document.create_element("label")?;
-    label.set_text_content(Some("width"));
-    div.append_child(&label)?;
-
-    let div = document.create_element("div")?;
-    body.append_child(&div)?;
-
-    let input_height = document
-        .create_element("input")?
-        .dyn_into::<HtmlInputElement>()?;
-    input_height.set_type("number");
-    input_height.set_min("2");
-    input_height.set_value(DEFAULT_HEIGHT.to_string().as_str());
-    div.append_child(&input_height)?;
 
-    let label = document.create_element("label")?;
-    label.set_text_content(Some("height"));
-    div.append_child(&label)?;
-
-    let div = document.create_element("div")?;
-    body.append_child(&div)?;
-
-    let select_generator = document
-        .create_element("select")?
-        .dyn_into::<HtmlSelectElement>()?;
-    div.append_child(&select_generator)?;
-
-    let div = document.create_element("div")?;
-    body.append_child(&div)?;
-
-    let button_generator = document
-        .create_element("button")?
-        .dyn_into::<HtmlButtonElement>()?;
-    button_generator.set_text_content(Some("Generate"));
-    div.append_child(&button_generator)?;
-
-    let div = document.create_element("div")?;
-    div.set_text_content(Some("Solver"));
-    body.append_child(&div)?;
-
-    let div = document.create_element("div")?;
-    body.append_child(&div)?;
-
-    let select_solver = document
-        .create_element("select")?
-        .dyn_into::<HtmlSelectElement>()?;
-    div.append_child(&select_solver)?;
-
-    let div = document.create_element("div")?;
-    body.append_child(&div)?;
-
-    let input_from_to = document
-        .create_element("input")?
-        .dyn_into::<HtmlInputElement>()?;
-    input_from_to.set_type("checkbox");
-    div.append_child(&input_from_to)?;
-
-    let label = document.create_element("label")?;
-    label.set_text_content(Some("with new locations"));
-    div.append_child(&label)?;
-
-    let div = document.create_element("div")?;
-    body.append_child(&div)?;
-
-    let button_solver = document
-        .create_element("button")?
-        .dyn_into::<HtmlButtonElement>()?;
-    button_solver.set_text_content(Some("Solve"));
-    button_solver.set_disabled(true);
-    div.append_child(&button_solver)?;
-
-    // setup generators
-    let mut generators: BTreeMap<String, fn() -> Box<dyn generate::Generator>> = BTreeMap::new();
-    generators.insert("Wilson's algorithm".to_string(), || {
-        Box::<generate::Wilson>::default()
-    });
-    generators.insert(
-        "Randomised depth first search algorithm".to_string(),
-        || Box::<generate::RandomisedDepthFirstSearch>::default(),
-    );
-    for name in generators.keys() {
-        let option = document
-            .create_element("option")?
-            .dyn_into::<HtmlOptionElement>()?;
-        option.set_value(name);
-        option.set_text_content(Some(name));
-        select_generator.append_child(&option)?;
+    /// Remove wall of cell, which is also the wall of the neighbour in direction.
+    fn remove_wall(&mut self, cell: usize, direction: Direction) {
+        if let Some((byte, mask)) = self.wall_bit(cell, direction) {
+            self.walls[byte] &= !mask;
+        }
     }
-    let generator = generators.get(&select_generator.value()).unwrap()();
 
-    // setup solvers
-    let mut solvers: BTreeMap<String, fn() -> Box<dyn solve::Solver>> = BTreeMap::new();
-    solvers.insert(
-        "A* algorithm (using Taxicab distance heuristic)".to_string(),
-        || Box::<solve::AStarSearch<solve::TaxicabDistance>>::default(),
-    );
-    solvers.insert(
-        "Dijkstra's algorithm (A* algorithm without heuristic)".to_string(),
-        || Box::<solve::AStarSearch<solve::Zero>>::default(),
-    );
-    solvers.insert(
-        "Randomised depth first search algorithm".to_string(),
-        || Box::<solve::RandomisedDepthFirstSearch>::default(),
-    );
-    solvers.insert("Wall follower (left turn)".to_string(), || {
-        Box::<solve::WallFollowerSearch<solve::Left>>::default()
-    });
-    solvers.insert("Wall follower (right turn)".to_string(), || {
-        Box::<solve::WallFollowerSearch<solve::Right>>::default()
-    });
-    for name in solvers.keys() {
-        let option = document
-            .create_element("option")?
-            .dyn_into::<HtmlOptionElement>()?;
-        option.set_value(name);
-        option.set_text_content(Some(name));
-        select_solver.append_child(&option)?;
+    /// Add wall of cell, which is also the wall of the neighbour in direction.
+    #[cfg(feature = "wasm")]
+    fn add_wall(&mut self, cell: usize, direction: Direction) {
+        if let Some((byte, mask)) = self.wall_bit(cell, direction) {
+            self.walls[byte] |= mask;
+        }
     }
-    let solver = solvers.get(&select_solver.value()).unwrap()();
-
-    let context = Box::new(RefCell::new(context));
-    let select_solver = Arc::new(RefCell::new(select_solver));
-    let button_solver = Arc::new(RefCell::new(button_solver));
-    let generator = Arc::new(RefCell::new(generator));
-    let solvers = Arc::new(RefCell::new(solvers));
-    let solver = Arc::new(RefCell::new(solver));
-
-    // program phase; also used for synchronisation
-    let phase = Arc::new(Mutex::new(Phase::Generate));
-
-    // maze dimensions
-    let dimensions = Arc::new(RefCell::new((
-        DEFAULT_WIDTH as usize,
-        DEFAULT_HEIGHT as usize,
-    )));
 
-    // maze cells
-    let cells = {
-        let dimensions = dimensions.borrow();
-        Arc::new(RefCell::new(vec![
-            Cell::default();
-            dimensions.0 * dimensions.1
-        ]))
-    };
-
-    // generate button behaviour
-    {
-        let context = context.clone();
-        let button_solver = button_solver.clone();
-        let generator = generator.clone();
-        let phase = phase.clone();
-        let dimensions = dimensions.clone();
-        let cells = cells.clone();
-        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
-            let mut phase = phase.lock().unwrap();
-            button_solver.borrow().set_disabled(true);
-            let mut dimensions = dimensions.borrow_mut();
-            *dimensions = (
-                input_width.value().parse().unwrap_or(dimensions.0).max(2),
-                input_height.value().parse().unwrap_or(dimensions.1).max(2),
-            );
-            input_width.set_value(dimensions.0.to_string().as_str());
-            input_height.set_value(dimensions.1.to_string().as_str());
-            {
-                let context = context.borrow();
-                let canvas = context.canvas().unwrap();
-                canvas.set_width(dimensions.0 as u32 * CELL_PIXELS);
-                canvas.set_height(dimensions.1 as u32 * CELL_PIXELS);
-                context.set_line_cap("round");
-            }
-            *cells.borrow_mut() = vec![Cell::default(); dimensions.0 * dimensions.1];
-            *generator.borrow_mut() = generators.get(&select_generator.value()).unwrap()();
-            *phase = Phase::Generate;
-        });
-        button_generator
-            .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
-        closure.forget();
+    /// Weather wall of cell is present.
+    fn has_wall(&self, cell: usize, direction: Direction) -> bool {
+        self.wall_bit(cell, direction)
+            .is_none_or(|(byte, mask)| self.walls[byte] & mask != 0)
     }
 
-    // from and to cell indexes
-    let (from, to) = (Arc::new(RefCell::new(0)), Arc::new(RefCell::new(0)));
+    /// Solution details of cell.
+    fn solution(&self, cell: usize) -> CellSolution {
+        self.solutions.get(&cell).copied().unwrap_or_default()
+    }
 
-    // solve button behaviour
-    {
-        let select_solver = select_solver.clone();
-        let solvers = solvers.clone();
-        let solver = solver.clone();
-        let phase = phase.clone();
-        let cells = cells.clone();
-        let (from, to) = (from.clone(), to.clone());
-        let closure = Closure::<dyn FnMut(_)>::new(move |_: Event| {
-            let mut phase = phase.lock().unwrap();
-            let mut cells = cells.borrow_mut();
-            for cell in &mut *cells {
-                cell.solution = CellSolution::default();
-            }
-            let (mut from, mut to) = (from.borrow_mut(), to.borrow_mut());
-            if input_from_to.checked() {
-                (*from, *to) = (
-                    (random() * cells.len() as f64) as usize,
-                    (random() * cells.len() as f64) as usize,
-                );
-                while *from == *to {
-                    *to = (random() * cells.len() as f64) as usize;
-                }
-            }
-            (cells[*from].solution.from, cells[*to].solution.to) = (true, true);
-            *solver.borrow_mut() = solvers
-                .borrow()
-                .get(&select_solver.borrow().value())
-                .unwrap()();
-            *phase = Phase::Solve;
-        });
-        button_solver
-            .borrow()
-            .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
-        closure.forget();
+    /// Mutable solution details of cell.
+    fn solution_mut(&mut self, cell: usize) -> &mut CellSolution {
+        self.solutions.entry(cell).or_default()
     }
 
-    // visualisation
-    let f = Rc::new(RefCell::new(None));
-    let g = f.clone();
-    *g.borrow_mut() = Some(Closure::new(move || {
-        let mut phase = phase.lock().unwrap();
-        if match *phase {
-            Phase::Generate => {
-                let mut cells = cells.borrow_mut();
-                if !generator
-                    .borrow_mut()
-                    .step(*dimensions.borrow(), &mut cells)
-                {
-                    let (mut from, mut to) = (from.borrow_mut(), to.borrow_mut());
-                    (*from, *to) = (
-                        (random() * cells.len() as f64) as usize,
-                        (random() * cells.len() as f64) as usize,
-                    );
-                    while *from == *to {
-                        *to = (random() * cells.len() as f64) as usize;
-                    }
-                    (cells[*from].solution.from, cells[*to].solution.to) = (true, true);
-                    *solver.borrow_mut() = solvers
-                        .borrow()
-                        .get(&select_solver.borrow().value())
-                        .unwrap()();
-                    button_solver.borrow().set_disabled(false);
-                    *phase = Phase::Solve;
-                }
-                true
-            }
-            Phase::Solve => {
-                let (mut cells, from, to) = (cells.borrow_mut(), from.borrow(), to.borrow());
-                if !solver
-                    .borrow_mut()
-                    .step(*dimensions.borrow(), &mut cells, *from, *to)
-                {
-                    *phase = Phase::Complete;
-                }
-                true
-            }
-            Phase::Complete => false,
-        } {
-            let context = context.borrow();
-            let canvas = context.canvas().unwrap();
-            context.set_fill_style(&JsValue::from_str(BACKGROUND_STYLE));
-            context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
-            for (idx, cell) in cells.borrow().iter().enumerate() {
-                cell.draw(*dimensions.borrow(), idx, &context);
-            }
-        }
+    /// Set the solution details of cell.
+    #[cfg(feature = "wasm")]
+    fn set_solution(&mut self, cell: usize, solution: CellSolution) {
+        match solution == CellSolution::default() {
+            true => self.solutions.remove(&cell),
+            false => self.solutions.insert(cell, solution),
+        };
+    }
 
-        request_animation_frame(f.borrow().as_ref().unwrap());
-    }));
-    request_animation_frame(g.borrow().as_ref().unwrap());
+    /// Remove the solution details of all cells, then flag the from and to cells.
+    fn reset_solution(&mut self, from: usize, to: usize) {
+        self.solutions.clear();
+        (self.solution_mut(from).from, self.solution_mut(to).to) = (true, true);
+    }
+}
 
-    Ok(())
+/// Name of the algorithm starting with value, compared ignoring case, spaces and
+/// punctuation, with `*` as "star". Value if none match.
+fn algorithm_name(mut names: impl Iterator<Item = String>, value: &str) -> String {
+    let simplify = |text: &str| {
+        text.replace('*', "star")
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_ascii_lowercase()
+    };
+    let start = simplify(value);
+    if start.is_empty() {
+        return value.to_string();
+    }
+    names
+        .find(|name| simplify(name).starts_with(&start))
+        .unwrap_or_else(|| value.to_string())
 }
@@ -0,0 +1,73 @@
+use crate::app::window;
+
+use std::collections::BTreeMap;
+
+// Key of the library in local storage
+const STORAGE_KEY: &str = "wasm-maze-library";
+
+/// A type for mazes saved by name, kept in local storage as maze codes.
+#[derive(Default)]
+pub(crate) struct Library {
+    mazes: BTreeMap<String, String>,
+}
+
+impl Library {
+    /// Load from local storage, or empty if unavailable.
+    pub(crate) fn load() -> Self {
+        let text = window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .unwrap_or_default();
+        // maze codes do not contain '=' so split at the last in each line
+        Self {
+            mazes: text
+                .lines()
+                .filter_map(|line| line.rsplit_once('='))
+                .map(|(name, code)| (name.to_string(), code.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Save to local storage. Returns whether saved.
+    fn save(&self) -> bool {
+        let text = self
+            .mazes
+            .iter()
+            .map(|(name, code)| format!("{name}={code}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .is_some_and(|storage| storage.set_item(STORAGE_KEY, &text).is_ok())
+    }
+
+    /// Names of the saved mazes, in order.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &String> {
+        self.mazes.keys()
+    }
+
+    /// Code of the maze saved with name.
+    pub(crate) fn get(&self, name: &str) -> Option<&String> {
+        self.mazes.get(name)
+    }
+
+    /// Save maze code with name, replacing any of the same name. Returns the name used,
+    /// on a single line, or `None` if not saved.
+    pub(crate) fn insert(&mut self, name: &str, code: String) -> Option<String> {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+            return None;
+        }
+        self.mazes.insert(name.clone(), code);
+        self.save().then_some(name)
+    }
+
+    /// Delete maze with name. Returns whether deleted.
+    pub(crate) fn remove(&mut self, name: &str) -> bool {
+        self.mazes.remove(name).is_some() && self.save()
+    }
+}
@@ -0,0 +1,324 @@
+use crate::generate::generators;
+use crate::parameter::{Parameter, Values};
+use crate::path::solution;
+use crate::platform::{self, set_level, Level};
+use crate::recording::{self, Recording};
+use crate::registry::{resolve, Registered};
+use crate::rng::{Platform, Xorshift};
+use crate::solve::solvers;
+use crate::{Cells, Dimensions, Direction, Directions, MINIMUM_DIMENSION};
+
+use js_sys::{Array, Function, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use std::collections::BTreeMap;
+
+/// A maze for use from JavaScript without the user interface, generated and solved
+/// by the algorithms available by name.
+#[wasm_bindgen]
+pub struct Maze {
+    dimensions: Dimensions,
+    cells: Cells,
+    // cells of the solution, including from and to; empty if not solved
+    solution: Vec<usize>,
+    // called with an event for each step; if None then steps are not reported
+    listener: Option<Function>,
+}
+
+#[wasm_bindgen]
+impl Maze {
+    /// Create a `Maze` of width and height cells with all walls present.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Result<Maze, JsValue> {
+        if width < MINIMUM_DIMENSION || height < MINIMUM_DIMENSION {
+            return Err(format!("dimensions must be at least {MINIMUM_DIMENSION}").into());
+        }
+        width
+            .checked_mul(height)
+            .ok_or("dimensions are too large")?;
+        Ok(Self {
+            dimensions: (width, height),
+            cells: Cells::new((width, height)),
+            solution: Vec::new(),
+            listener: None,
+        })
+    }
+
+    /// Number of cells across.
+    pub fn width(&self) -> usize {
+        self.dimensions.0
+    }
+
+    /// Number of cells down.
+    pub fn height(&self) -> usize {
+        self.dimensions.1
+    }
+
+    /// Names of the available generating algorithms.
+    pub fn generators() -> Vec<String> {
+        names(&generators())
+    }
+
+    /// Names of the available solving algorithms.
+    pub fn solvers() -> Vec<String> {
+        names(&solvers())
+    }
+
+    /// The available generating algorithms, each with its `id`, which does not change with
+    /// its display `name`, and `category`.
+    pub fn generator_entries() -> Array {
+        entries(&generators())
+    }
+
+    /// The available solving algorithms, each with its `id`, display `name` and `category`.
+    pub fn solver_entries() -> Array {
+        entries(&solvers())
+    }
+
+    /// Set the function called with an event for each step of generating and solving,
+    /// or remove it if not given.
+    ///
+    /// Each event has the `phase` ("generate" or "solve"), the `step` number and the
+    /// changed `cells`. When generating, each changed cell has its `walls` and `walk`
+    /// index, `wallsRemoved` lists the pairs of cells joined and `frontier` is the changed
+    /// cell latest in the walk, if any. When solving, each
+    /// changed cell has its `previous` cell and whether it is on the `result` path.
+    pub fn set_listener(&mut self, listener: Option<Function>) {
+        self.listener = listener;
+    }
+
+    /// Generate the maze with the algorithm of name or id, replacing any previous maze. The
+    /// same seed, a whole number, generates the same maze; if not given then the maze is random.
+    /// Parameters of the algorithm may be given by key in the parameters object; others have
+    /// their defaults.
+    pub fn generate(
+        &mut self,
+        algorithm: &str,
+        seed: Option<f64>,
+        parameters: Option<Object>,
+    ) -> Result<(), JsValue> {
+        let generators = generators();
+        let generator = generators
+            .get(&resolve(&generators, algorithm))
+            .ok_or_else(|| format!("unknown generator \"{algorithm}\""))?;
+        let mut generator = generator.create(
+            Box::new(Xorshift::new(match seed {
+                Some(seed) if seed < 0.0 || seed.fract() != 0.0 || seed > u64::MAX as f64 => {
+                    return Err(format!("seed {seed} is not a whole number").into());
+                }
+                Some(seed) => seed as u64,
+                None => platform::random_seed(),
+            })),
+            &values(generator.parameters(), parameters.as_ref())?,
+        );
+        self.cells = Cells::new(self.dimensions);
+        self.solution.clear();
+        match &self.listener {
+            None => {
+                while generator
+                    .step(self.dimensions, &mut self.cells)
+                    .is_continue()
+                {}
+            }
+            Some(listener) => {
+                let mut recording = Recording::<recording::Maze>::default();
+                let mut stepping = true;
+                while stepping {
+                    stepping = recording.record(&mut self.cells, |cells| {
+                        generator.step(self.dimensions, cells).is_continue()
+                    });
+                    let cells = Array::new();
+                    let walls_removed = Array::new();
+                    let mut frontier = None;
+                    for (cell, (before, _), (walls, walk)) in recording.last_changes() {
+                        cells.push(&object(&[
+                            ("cell", cell.into()),
+                            ("walls", (*walls).into()),
+                            ("walk", walk.map_or(JsValue::NULL, JsValue::from)),
+                        ]));
+                        if walk.is_some() && frontier.is_none_or(|(_, latest)| *walk > latest) {
+                            frontier = Some((cell, *walk));
+                        }
+                        for direction in [Direction::East, Direction::South] {
+                            if before & !walls & direction as u8 != 0 {
+                                if let Some(neighbour) = direction.neighbour(self.dimensions, cell)
+                                {
+                                    walls_removed
+                                        .push(&Array::of2(&cell.into(), &neighbour.into()));
+                                }
+                            }
+                        }
+                    }
+                    listener.call1(
+                        &JsValue::NULL,
+                        &object(&[
+                            ("phase", "generate".into()),
+                            ("step", recording.len().into()),
+                            ("cells", cells.into()),
+                            ("wallsRemoved", walls_removed.into()),
+                            (
+                                "frontier",
+                                frontier.map_or(JsValue::NULL, |(cell, _)| cell.into()),
+                            ),
+                        ]),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Solve the maze between cells with the algorithm of name or id. Returns whether solved.
+    /// Parameters of the algorithm may be given by key in the parameters object, such as
+    /// `{ order: "ESWN", "max-steps": 1000 }`; others have their defaults.
+    pub fn solve(
+        &mut self,
+        algorithm: &str,
+        from: usize,
+        to: usize,
+        parameters: Option<Object>,
+    ) -> Result<bool, JsValue> {
+        let solvers = solvers();
+        let solver = solvers
+            .get(&resolve(&solvers, algorithm))
+            .ok_or_else(|| format!("unknown solver \"{algorithm}\""))?;
+        let mut solver = solver.create(
+            Box::new(Platform),
+            &values(solver.parameters(), parameters.as_ref())?,
+        );
+        if from >= self.cells.len() || to >= self.cells.len() || from == to {
+            return Err("from and to must be different cells of the maze".into());
+        }
+        self.cells.reset_solution(from, to);
+        match &self.listener {
+            None => {
+                while solver
+                    .step(self.dimensions, &mut self.cells, from, to)
+                    .is_continue()
+                {}
+            }
+            Some(listener) => {
+                let mut recording = Recording::<recording::Solution>::default();
+                let mut stepping = true;
+                while stepping {
+                    stepping = recording.record(&mut self.cells, |cells| {
+                        solver.step(self.dimensions, cells, from, to).is_continue()
+                    });
+                    let cells = Array::new();
+                    for (cell, _, solution) in recording.last_changes() {
+                        cells.push(&object(&[
+                            ("cell", cell.into()),
+                            (
+                                "previous",
+                                solution.previous.map_or(JsValue::NULL, JsValue::from),
+                            ),
+                            ("result", solution.result.into()),
+                        ]));
+                    }
+                    listener.call1(
+                        &JsValue::NULL,
+                        &object(&[
+                            ("phase", "solve".into()),
+                            ("step", recording.len().into()),
+                            ("cells", cells.into()),
+                        ]),
+                    )?;
+                }
+            }
+        }
+        self.solution = solution(&self.cells, from, to).unwrap_or_default();
+        Ok(!self.solution.is_empty())
+    }
+
+    /// Walls of each cell by row, as a bit per wall; north, east, south then west from
+    /// the least significant bit.
+    pub fn walls(&self) -> Vec<u8> {
+        self.cells.all_walls()
+    }
+
+    /// Walls of the cells as a view into the memory of the module, without copying, for
+    /// large mazes. Each byte holds 4 cells by row from the least significant bits, as 2 bits
+    /// per cell; the east wall then the south wall, 1 if present. The north and west walls
+    /// are those of the neighbours, and walls on the boundary are always present.
+    ///
+    /// The view is invalid once the maze is generated again, since the walls are replaced,
+    /// and once any call grows the memory of the module, since the view is then detached
+    /// and empty. Take a new view after each such call rather than keeping one.
+    pub fn walls_view(&self) -> Uint8Array {
+        // SAFETY: the view is of walls which are not changed while the view is valid, as no
+        // Rust code runs until JavaScript makes another call; the invalidation on later calls
+        // is documented above
+        unsafe { Uint8Array::view(&self.cells.walls) }
+    }
+
+    /// Cells of the solution from the last solve, including from and to.
+    pub fn solution(&self) -> Vec<u32> {
+        self.solution.iter().map(|cell| *cell as u32).collect()
+    }
+}
+
+/// Set the most detailed level of the messages logged to the console by the algorithms;
+/// "off", "info", "debug" or "trace".
+#[wasm_bindgen]
+pub fn set_log_level(level: &str) -> Result<(), JsValue> {
+    set_level(Level::parse(level).ok_or_else(|| format!("unknown log level \"{level}\""))?);
+    Ok(())
+}
+
+/// Values of parameters from the properties of given by key, as text, numbers or booleans;
+/// other properties are ignored.
+fn values(parameters: &[Parameter], given: Option<&Object>) -> Result<Values, JsValue> {
+    let mut values = Values::default();
+    let Some(given) = given else {
+        return Ok(values);
+    };
+    for parameter in parameters {
+        let value = Reflect::get(given, &JsValue::from_str(parameter.key))?;
+        if value.is_undefined() {
+            continue;
+        }
+        let text = value
+            .as_string()
+            .or_else(|| value.as_bool().map(|flag| flag.to_string()))
+            .or_else(|| value.as_f64().map(|number| number.to_string()))
+            .unwrap_or_default();
+        values.set(
+            parameter.key,
+            parameter.parse(&text).ok_or_else(|| {
+                format!("invalid value \"{text}\" for parameter {}", parameter.key)
+            })?,
+        );
+    }
+    Ok(values)
+}
+
+/// Display names of the algorithms of registry.
+fn names<T: Registered>(registry: &BTreeMap<String, T>) -> Vec<String> {
+    registry
+        .values()
+        .map(|entry| entry.name().to_string())
+        .collect()
+}
+
+/// Entries of the algorithms of registry, each with its id, display name and category.
+fn entries<T: Registered>(registry: &BTreeMap<String, T>) -> Array {
+    registry
+        .iter()
+        .map(|(id, entry)| {
+            JsValue::from(object(&[
+                ("id", id.into()),
+                ("name", entry.name().into()),
+                ("category", entry.category().id().into()),
+            ]))
+        })
+        .collect()
+}
+
+/// Create an object with properties.
+pub(crate) fn object(properties: &[(&str, JsValue)]) -> Object {
+    let object = Object::new();
+    for (key, value) in properties {
+        let _ = Reflect::set(&object, &JsValue::from_str(key), value);
+    }
+    object
+}
@@ -0,0 +1,63 @@
+// Ways of moving through a maze, shared by the human player and the solvers.
+
+#[cfg(any(feature = "wasm", test))]
+use crate::DIRECTIONS;
+use crate::{Cells, Dimensions, Direction, Directions};
+
+/// Ways of moving from a cell in a direction.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub(crate) enum Movement {
+    // a cell at a time
+    #[default]
+    Step,
+    // on to the cell before the next wall, as on an ice floor
+    Slide,
+}
+
+impl Movement {
+    /// Cell reached moving from cell in direction; a slide also stops at the stop cell, so
+    /// that the goal may be reached. `None` if a wall blocks the way.
+    pub(crate) fn destination(
+        &self,
+        dimensions: Dimensions,
+        cells: &Cells,
+        cell: usize,
+        direction: Direction,
+        stop: usize,
+    ) -> Option<usize> {
+        let mut destination = next(dimensions, cells, cell, direction)?;
+        if *self == Self::Slide {
+            while destination != stop {
+                match next(dimensions, cells, destination, direction) {
+                    Some(next) => destination = next,
+                    None => break,
+                }
+            }
+        }
+        Some(destination)
+    }
+
+    /// Cells reached moving from cell in each direction not blocked by a wall, stopping at
+    /// the stop cell.
+    #[cfg(any(feature = "wasm", test))]
+    pub(crate) fn destinations<'a>(
+        &self,
+        dimensions: Dimensions,
+        cells: &'a Cells,
+        cell: usize,
+        stop: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let movement = *self;
+        DIRECTIONS.iter().filter_map(move |direction| {
+            movement.destination(dimensions, cells, cell, *direction, stop)
+        })
+    }
+}
+
+/// Neighbour of cell in direction, unless a wall is between them.
+fn next(dimensions: Dimensions, cells: &Cells, cell: usize, direction: Direction) -> Option<usize> {
+    match cells.has_wall(cell, direction) {
+        true => None,
+        false => direction.neighbour(dimensions, cell),
+    }
+}
@@ -0,0 +1,47 @@
+// Work spread over the threads of the machine with the `parallel` feature when built
+// natively. Otherwise, as in WebAssembly where threads need cross-origin isolation and a
+// rebuilt standard library, the work is done on the one thread.
+
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+use crate::platform;
+
+/// Results of f applied to contiguous parts of items, in order; a part for each thread
+/// available, or all of items as one part without threads. The threads log at the level of
+/// the calling thread.
+pub(crate) fn parts<T: Sync, R: Send>(items: &[T], f: impl Fn(&[T]) -> R + Sync) -> Vec<R> {
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        let threads = std::thread::available_parallelism().map_or(1, usize::from);
+        if threads > 1 && items.len() > 1 {
+            let (f, level) = (&f, platform::level());
+            return std::thread::scope(|scope| {
+                let handles = items
+                    .chunks(items.len().div_ceil(threads))
+                    .map(|part| {
+                        scope.spawn(move || {
+                            platform::set_level(level);
+                            f(part)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            });
+        }
+    }
+    vec![f(items)]
+}
+
+/// Results of f applied to each of items, in order, spread over threads as `parts`.
+pub(crate) fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    parts(items, |part| part.iter().map(&f).collect::<Vec<_>>())
+        .into_iter()
+        .flatten()
+        .collect()
+}
@@ -0,0 +1,128 @@
+// Options which an algorithm declares for itself, so that they are chosen only while it is.
+
+use crate::direction::Direction;
+
+use std::collections::BTreeMap;
+
+/// Control choosing the value of a parameter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Control {
+    /// A number from minimum to maximum, in steps.
+    Number {
+        minimum: f64,
+        maximum: f64,
+        step: f64,
+    },
+    /// A checkbox; the value is 1 if checked, otherwise 0.
+    Checkbox,
+    /// An order of the directions, entered as their compass letters; the value is the index
+    /// of the order as `Direction::order_index`.
+    Order,
+}
+
+/// A parameter declared by an algorithm.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Parameter {
+    // key of the value, unique to the algorithm
+    pub(crate) key: &'static str,
+    // label of the control
+    pub(crate) label: &'static str,
+    pub(crate) control: Control,
+    pub(crate) default: f64,
+}
+
+impl Parameter {
+    /// Value within the range of the control.
+    fn clamp(&self, value: f64) -> f64 {
+        match self.control {
+            Control::Number {
+                minimum, maximum, ..
+            } => value.clamp(minimum, maximum),
+            Control::Checkbox => f64::from(u8::from(value != 0.0)),
+            Control::Order => value.round().clamp(0.0, (Direction::ORDERS - 1) as f64),
+        }
+    }
+
+    /// Value from text; a number, `1`, `0`, `true` or `false` for a checkbox, or compass
+    /// letters for an order. `None` if not valid for the control.
+    pub(crate) fn parse(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        match self.control {
+            Control::Number { .. } => text.parse().ok().filter(|value: &f64| value.is_finite()),
+            Control::Checkbox => match text {
+                "true" | "1" => Some(1.0),
+                "false" | "0" => Some(0.0),
+                _ => None,
+            },
+            Control::Order => {
+                Direction::parse_order(text).map(|order| Direction::order_index(order) as f64)
+            }
+        }
+    }
+
+    /// Text of value, as parsed by `parse`.
+    #[cfg(feature = "wasm")]
+    pub(crate) fn format(&self, value: f64) -> String {
+        match self.control {
+            Control::Number { .. } => value.to_string(),
+            Control::Checkbox => u8::from(value != 0.0).to_string(),
+            Control::Order => Direction::order_at(value as usize)
+                .iter()
+                .map(Direction::letter)
+                .collect(),
+        }
+    }
+}
+
+/// Values of parameters by key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Values(BTreeMap<&'static str, f64>);
+
+impl Values {
+    /// Values of parameters; those chosen, within the range of their controls, otherwise the
+    /// defaults. Chosen values of other keys are ignored.
+    pub(crate) fn of(parameters: &[Parameter], chosen: &Values) -> Self {
+        Self(
+            parameters
+                .iter()
+                .map(|parameter| {
+                    let value = chosen.0.get(parameter.key).copied();
+                    (
+                        parameter.key,
+                        value.map_or(parameter.default, |value| parameter.clamp(value)),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Choose value for key.
+    pub(crate) fn set(&mut self, key: &'static str, value: f64) {
+        self.0.insert(key, value);
+    }
+
+    /// Value of key; 0 if not a parameter.
+    pub(crate) fn number(&self, key: &str) -> f64 {
+        self.0.get(key).copied().unwrap_or_default()
+    }
+
+    /// Whether the value of key is checked.
+    pub(crate) fn flag(&self, key: &str) -> bool {
+        self.number(key) != 0.0
+    }
+
+    /// Description of the values which are not the defaults of parameters, as each key and
+    /// value. `None` if all are the defaults.
+    #[cfg(feature = "wasm")]
+    pub(crate) fn describe(&self, parameters: &[Parameter]) -> Option<String> {
+        let changed = parameters
+            .iter()
+            .filter(|parameter| self.number(parameter.key) != parameter.default)
+            .map(|parameter| {
+                let value = parameter.format(self.number(parameter.key));
+                format!("{} {value}", parameter.key)
+            })
+            .collect::<Vec<_>>();
+        (!changed.is_empty()).then(|| changed.join(", "))
+    }
+}
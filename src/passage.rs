@@ -0,0 +1,71 @@
+// Operations on the passages between the cells of a maze, shared by the algorithms.
+
+#[cfg(feature = "wasm")]
+use crate::path::shortest_path;
+use crate::{Cells, Dimensions, Direction, Directions};
+
+/// Carve a passage between neighbouring cells a and b, removing the wall on both sides.
+/// Cells which are not neighbours are unchanged.
+pub(crate) fn carve(cells: &mut Cells, dimensions: Dimensions, a: usize, b: usize) {
+    if let Some(direction) = Direction::between(dimensions, a, b) {
+        cells.remove_wall(a, direction);
+        cells.remove_wall(b, direction.opposite());
+    }
+}
+
+/// Build a wall between neighbouring cells a and b, on both sides. Cells which are not
+/// neighbours are unchanged.
+#[cfg(feature = "wasm")]
+pub(crate) fn wall(cells: &mut Cells, dimensions: Dimensions, a: usize, b: usize) {
+    if let Some(direction) = Direction::between(dimensions, a, b) {
+        cells.add_wall(a, direction);
+        cells.add_wall(b, direction.opposite());
+    }
+}
+
+/// Passages or walls between neighbouring cells which differ between the mazes, as each cell
+/// and its east or south neighbour.
+#[cfg(feature = "wasm")]
+pub(crate) fn differences(
+    dimensions: Dimensions,
+    cells: &Cells,
+    other: &Cells,
+) -> Vec<(usize, usize)> {
+    (0..cells.len())
+        .flat_map(|cell| [Direction::East, Direction::South].map(|direction| (cell, direction)))
+        .filter(|(cell, direction)| {
+            cells.has_wall(*cell, *direction) != other.has_wall(*cell, *direction)
+        })
+        .filter_map(|(cell, direction)| {
+            direction
+                .neighbour(dimensions, cell)
+                .map(|neighbour| (cell, neighbour))
+        })
+        .collect()
+}
+
+/// Carve the wall between neighbouring cells a and b, then wall one of the passages on the
+/// way that joined them, chosen by `choose` from those in order; so that a perfect maze stays
+/// perfect. Returns the walled passage, or `None` if a and b were not joined or not separated
+/// by a wall, when the maze is only carved.
+#[cfg(feature = "wasm")]
+pub(crate) fn swap(
+    cells: &mut Cells,
+    dimensions: Dimensions,
+    (a, b): (usize, usize),
+    choose: impl FnOnce(&[(usize, usize)]) -> Option<usize>,
+) -> Option<(usize, usize)> {
+    let direction = Direction::between(dimensions, a, b)?;
+    if !cells.has_wall(a, direction) {
+        return None;
+    }
+    let way = shortest_path(dimensions, cells, a, b);
+    carve(cells, dimensions, a, b);
+    let passages = way?
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect::<Vec<_>>();
+    let (c, d) = passages[choose(&passages)?];
+    wall(cells, dimensions, c, d);
+    Some((c, d))
+}
@@ -0,0 +1,197 @@
+#[cfg(feature = "wasm")]
+use crate::geometry::row_and_col;
+#[cfg(any(feature = "wasm", test))]
+use crate::movement::Movement;
+use crate::Cells;
+#[cfg(any(feature = "wasm", test))]
+use crate::Dimensions;
+#[cfg(feature = "wasm")]
+use crate::Direction;
+
+#[cfg(any(feature = "wasm", test))]
+use std::collections::VecDeque;
+
+/// Shortest path between cells using a [breadth first search](https://en.wikipedia.org/wiki/Breadth-first_search)
+/// through removed walls. Includes both cells. `None` if cells are not connected.
+#[cfg(any(feature = "wasm", test))]
+pub(crate) fn shortest_path(
+    dimensions: Dimensions,
+    cells: &Cells,
+    from: usize,
+    to: usize,
+) -> Option<Vec<usize>> {
+    shortest_moves(dimensions, cells, from, to, Movement::Step)
+}
+
+/// Cells reached by the fewest moves between cells with `Movement`, using a breadth first
+/// search. Includes both cells. `None` if to cannot be reached.
+#[cfg(any(feature = "wasm", test))]
+pub(crate) fn shortest_moves(
+    dimensions: Dimensions,
+    cells: &Cells,
+    from: usize,
+    to: usize,
+    movement: Movement,
+) -> Option<Vec<usize>> {
+    let mut previous = vec![None; cells.len()];
+    let mut queue = VecDeque::from([from]);
+    while let Some(cell) = queue.pop_front() {
+        if cell == to {
+            let mut path = vec![to];
+            let mut cell = to;
+            while let Some(prev) = previous[cell] {
+                path.push(prev);
+                cell = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for destination in movement.destinations(dimensions, cells, cell, to) {
+            if destination != from && previous[destination].is_none() {
+                previous[destination] = Some(cell);
+                queue.push_back(destination);
+            }
+        }
+    }
+
+    None
+}
+
+/// Number of moves from cell from to each cell through removed walls, using a breadth first
+/// search. `None` for cells which are not connected.
+#[cfg(any(feature = "wasm", test))]
+pub(crate) fn distances(dimensions: Dimensions, cells: &Cells, from: usize) -> Vec<Option<usize>> {
+    let mut distances = vec![None; cells.len()];
+    distances[from] = Some(0);
+    let mut queue = VecDeque::from([from]);
+    while let Some(cell) = queue.pop_front() {
+        let distance = distances[cell].map(|distance| distance + 1);
+        for neighbour in Movement::Step.destinations(dimensions, cells, cell, cell) {
+            if distances[neighbour].is_none() {
+                distances[neighbour] = distance;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    distances
+}
+
+/// A type for the shortest paths from a cell to every other, as the tree of the breadth
+/// first search from it.
+#[cfg(feature = "wasm")]
+#[derive(Clone)]
+pub(crate) struct PathTree {
+    from: usize,
+    // previous cell on the shortest path to each cell; None for from and cells which are
+    // not connected
+    previous: Vec<Option<usize>>,
+    // number of moves from from to each cell; None for cells which are not connected
+    distances: Vec<Option<usize>>,
+}
+
+#[cfg(feature = "wasm")]
+impl PathTree {
+    /// Create by searching the cells breadth first from cell from.
+    pub(crate) fn new(dimensions: Dimensions, cells: &Cells, from: usize) -> Self {
+        let mut previous = vec![None; cells.len()];
+        let mut distances = vec![None; cells.len()];
+        distances[from] = Some(0);
+        let mut queue = VecDeque::from([from]);
+        while let Some(cell) = queue.pop_front() {
+            let distance = distances[cell].map(|distance| distance + 1);
+            for neighbour in Movement::Step.destinations(dimensions, cells, cell, cell) {
+                if distances[neighbour].is_none() {
+                    distances[neighbour] = distance;
+                    previous[neighbour] = Some(cell);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        Self {
+            from,
+            previous,
+            distances,
+        }
+    }
+
+    /// Cell the paths are from.
+    pub(crate) fn from(&self) -> usize {
+        self.from
+    }
+
+    /// Number of moves from the from cell to cell. `None` if not connected.
+    pub(crate) fn distance(&self, cell: usize) -> Option<usize> {
+        self.distances.get(cell).copied().flatten()
+    }
+
+    /// Cells of the shortest path from the from cell to cell, including both. `None` if not
+    /// connected.
+    pub(crate) fn path(&self, cell: usize) -> Option<Vec<usize>> {
+        self.distance(cell)?;
+        let mut path = vec![cell];
+        let mut cell = cell;
+        while let Some(previous) = self.previous[cell] {
+            path.push(previous);
+            cell = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Number of cells between from and cell following the previous cells of the
+/// solution details. `None` if cell has not been reached by a solver.
+#[cfg(feature = "wasm")]
+pub(crate) fn solve_distance(cells: &Cells, from: usize, cell: usize) -> Option<usize> {
+    let mut distance = 0;
+    let mut cell = cell;
+    while cell != from {
+        cell = cells.solution(cell).previous?;
+        distance += 1;
+    }
+    Some(distance)
+}
+
+/// Cells of the solution flagged by a solver, including from and to. `None` if not solved.
+pub(crate) fn solution(cells: &Cells, from: usize, to: usize) -> Option<Vec<usize>> {
+    if !cells.solution(to).result {
+        return None;
+    }
+    let mut path = vec![to];
+    let mut cell = to;
+    while cell != from {
+        cell = cells.solution(cell).previous?;
+        path.push(cell);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Textual description of the moves along a path of neighbouring cells,
+/// e.g. "3 moves from row 1, column 1: 2 east, 1 south".
+#[cfg(feature = "wasm")]
+pub(crate) fn describe_moves(dimensions: Dimensions, path: &[usize]) -> String {
+    let mut moves: Vec<(Direction, usize)> = Vec::new();
+    for pair in path.windows(2) {
+        let direction =
+            Direction::between(dimensions, pair[0], pair[1]).expect("should be neighbours");
+        match moves.last_mut() {
+            Some((last, count)) if *last == direction => *count += 1,
+            _ => moves.push((direction, 1)),
+        }
+    }
+
+    let (row, col) = row_and_col(dimensions, path[0]);
+    format!(
+        "{} moves from row {}, column {}: {}",
+        path.len() - 1,
+        row + 1,
+        col + 1,
+        moves
+            .iter()
+            .map(|(direction, count)| format!("{count} {}", direction.name()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
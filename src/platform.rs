@@ -0,0 +1,114 @@
+// Services of the platform used by the algorithms; the browser when built for WebAssembly
+// with the `wasm` feature, otherwise the standard library.
+
+use crate::rng::{Rng, Xorshift};
+
+use std::cell::RefCell;
+
+thread_local! {
+    // generator shared by the platform, seeded differently on each run
+    static GENERATOR: RefCell<Xorshift> = RefCell::new(Xorshift::new(platform_seed()));
+}
+
+/// A seed differing on each run.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+fn platform_seed() -> u64 {
+    // the 53 bits of a random number, with the time for the rest
+    (js_sys::Math::random() * (1u64 << 53) as f64) as u64 ^ (js_sys::Date::now() as u64) << 53
+}
+
+/// A seed differing on each run.
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+fn platform_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+/// A seed chosen at random, independent of any earlier seed, for a maze which may be
+/// repeated later.
+pub(crate) fn random_seed() -> u64 {
+    // 53 bits, as whole numbers up to this are exact in JavaScript
+    (Xorshift::new(platform_seed()).random() * (1u64 << 53) as f64) as u64
+}
+
+/// Random number in the range 0 (inclusive) to 1 (exclusive).
+pub(crate) fn random() -> f64 {
+    GENERATOR.with_borrow_mut(Rng::random)
+}
+
+/// Levels of detail of log messages, from none to every step of the algorithms.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+pub(crate) enum Level {
+    Off,
+    Info,
+    Debug,
+    Trace,
+}
+
+// Levels in order of detail
+pub(crate) const LEVELS: [Level; 4] = [Level::Off, Level::Info, Level::Debug, Level::Trace];
+
+impl Level {
+    /// Name of this `Level`.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+
+    /// `Level` with name, ignoring case. `None` if unknown.
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        LEVELS
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(name))
+    }
+}
+
+// Level of the messages logged unless set; the console in the browser, nothing natively
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub(crate) const DEFAULT_LEVEL: Level = Level::Info;
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) const DEFAULT_LEVEL: Level = Level::Off;
+
+thread_local! {
+    // most detailed level of the messages logged
+    static LEVEL: std::cell::Cell<Level> = const { std::cell::Cell::new(DEFAULT_LEVEL) };
+}
+
+/// Set the most detailed level of the messages logged.
+pub(crate) fn set_level(level: Level) {
+    LEVEL.set(level);
+}
+
+/// Most detailed level of the messages logged, given to the threads of `parallel`.
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+pub(crate) fn level() -> Level {
+    LEVEL.get()
+}
+
+/// Log message at level, if enabled. The message is only formatted when logged, so
+/// `format_args!` costs little for detailed levels.
+pub(crate) fn log(level: Level, message: impl std::fmt::Display) {
+    if level != Level::Off && level <= LEVEL.get() {
+        write(level, &message.to_string());
+    }
+}
+
+/// Write message to the browser console.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+fn write(level: Level, message: &str) {
+    match level {
+        Level::Trace | Level::Debug => web_sys::console::debug_1(&message.into()),
+        _ => web_sys::console::log_1(&message.into()),
+    }
+}
+
+/// Write message to standard error, leaving standard output to the output of native tools.
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+fn write(level: Level, message: &str) {
+    eprintln!("[{}] {message}", level.name());
+}
@@ -0,0 +1,269 @@
+use crate::app::{CELL_PIXELS, ENEMY_STYLE, HINT_STYLE, ITEM_STYLE, PLAYER_LINE_WIDTH};
+use crate::geometry::row_and_col;
+use crate::movement::Movement;
+use crate::path::shortest_moves;
+use crate::rng::Rng;
+use crate::solve::visit_order;
+use crate::{Cells, Dimensions, Direction, Directions, DIRECTIONS};
+
+use js_sys::Date;
+use web_sys::CanvasRenderingContext2d;
+
+// Number of cells of the path shown by a hint
+const HINT_CELLS: usize = 5;
+
+// Duration a hint is shown for
+const HINT_MILLISECONDS: f64 = 1000.0;
+
+// Duration between the moves of the enemies
+const ENEMY_MILLISECONDS: f64 = 400.0;
+
+/// Ways an enemy chooses where to patrol.
+#[derive(Clone, Copy)]
+pub(crate) enum Patrol {
+    // keep a hand on the wall to the right, facing direction
+    WallFollower(Direction),
+    // choose an open way at random, turning back only at dead ends
+    RandomWalk(Direction),
+}
+
+/// A type for an enemy patrolling the corridors of a maze, which sends the player back to
+/// the start when they meet.
+pub(crate) struct Enemy {
+    // current cell index
+    cell: usize,
+    patrol: Patrol,
+}
+
+impl Enemy {
+    /// Create an `Enemy` at cell, patrolling by following the wall or, if random, walking at
+    /// random.
+    pub(crate) fn new(cell: usize, random: bool) -> Self {
+        Self {
+            cell,
+            patrol: match random {
+                true => Patrol::RandomWalk(Direction::North),
+                false => Patrol::WallFollower(Direction::North),
+            },
+        }
+    }
+
+    /// Move to a neighbouring cell chosen by the `Patrol`, if not enclosed by walls.
+    fn step(&mut self, dimensions: Dimensions, cells: &Cells, rng: &mut dyn Rng) {
+        let open = |direction: &Direction| {
+            !cells.has_wall(self.cell, *direction)
+                && direction.neighbour(dimensions, self.cell).is_some()
+        };
+        let direction = match self.patrol {
+            // turn right if possible, otherwise ahead, left or back, as the wall follower
+            Patrol::WallFollower(facing) => {
+                std::iter::successors(Some(facing.next()), |direction| Some(direction.prev()))
+                    .take(DIRECTIONS.len())
+                    .find(open)
+            }
+            Patrol::RandomWalk(facing) => {
+                let ways = DIRECTIONS
+                    .iter()
+                    .copied()
+                    .filter(|direction| open(direction) && *direction != facing.opposite())
+                    .collect::<Vec<_>>();
+                match ways.len() {
+                    0 => Some(facing.opposite()).filter(open),
+                    len => Some(ways[rng.index(len)]),
+                }
+            }
+        };
+        let Some(direction) = direction else {
+            return;
+        };
+        if let Some(neighbour) = direction.neighbour(dimensions, self.cell) {
+            self.cell = neighbour;
+            self.patrol = match self.patrol {
+                Patrol::WallFollower(_) => Patrol::WallFollower(direction),
+                Patrol::RandomWalk(_) => Patrol::RandomWalk(direction),
+            };
+        }
+    }
+}
+
+/// A type for a human player navigating a maze.
+pub(crate) struct Player {
+    // cell index started from, and returned to when caught
+    start: usize,
+    // current cell index
+    pub(crate) cell: usize,
+    // cell indexes visited in order, including the current cell
+    trail: Vec<usize>,
+    // cell indexes of the hint and time at which it expires
+    hint: (Vec<usize>, f64),
+    // way of moving for each key press
+    movement: Movement,
+    // cell indexes of the items not yet collected, which must be before the goal
+    items: Vec<usize>,
+    // enemies patrolling the maze and the time of their next move
+    enemies: (Vec<Enemy>, f64),
+}
+
+impl Player {
+    /// Create a `Player` at cell, moving with `Movement`, collecting items and avoiding
+    /// enemies.
+    pub(crate) fn new(
+        cell: usize,
+        movement: Movement,
+        items: Vec<usize>,
+        enemies: Vec<Enemy>,
+    ) -> Self {
+        Self {
+            start: cell,
+            cell,
+            trail: vec![cell],
+            hint: (Vec::new(), 0.0),
+            movement,
+            items,
+            enemies: (enemies, Date::now() + ENEMY_MILLISECONDS),
+        }
+    }
+
+    /// Move the enemies if it is time, then send the player back to the start if any enemy
+    /// has caught them. Returns whether caught.
+    pub(crate) fn patrol(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &Cells,
+        rng: &mut dyn Rng,
+    ) -> bool {
+        let (enemies, next) = &mut self.enemies;
+        if Date::now() >= *next {
+            *next = Date::now() + ENEMY_MILLISECONDS;
+            for enemy in enemies.iter_mut() {
+                enemy.step(dimensions, cells, rng);
+            }
+        }
+        let caught = enemies.iter().any(|enemy| enemy.cell == self.cell);
+        if caught {
+            self.cell = self.start;
+            self.trail = vec![self.start];
+        }
+        caught
+    }
+
+    /// Whether every item has been collected.
+    pub(crate) fn collected(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Show the cells reached by the next few moves of the shortest path to cell, by way of
+    /// the next item to collect.
+    pub(crate) fn hint(&mut self, dimensions: Dimensions, cells: &Cells, to: usize) {
+        let next = visit_order(dimensions, cells, self.cell, to, &self.items)
+            .and_then(|order| order.first().copied())
+            .unwrap_or(to);
+        if let Some(path) = shortest_moves(dimensions, cells, self.cell, next, self.movement) {
+            self.hint = (
+                path.into_iter().skip(1).take(HINT_CELLS).collect(),
+                Date::now() + HINT_MILLISECONDS,
+            );
+        }
+    }
+
+    /// Move in `Direction` if not blocked by a wall; sliding stops at the goal, cell to.
+    /// Returns whether moved.
+    pub(crate) fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &Cells,
+        direction: Direction,
+        to: usize,
+    ) -> bool {
+        match self
+            .movement
+            .destination(dimensions, cells, self.cell, direction, to)
+        {
+            Some(destination) => {
+                // moving back along the trail retracts it
+                if self.trail.len() > 1 && self.trail[self.trail.len() - 2] == destination {
+                    self.trail.pop();
+                } else {
+                    self.trail.push(destination);
+                }
+                self.cell = destination;
+                self.items.retain(|item| *item != destination);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draw into canvas with style.
+    pub(crate) fn draw(
+        &self,
+        dimensions: Dimensions,
+        context: &CanvasRenderingContext2d,
+        style: &str,
+    ) {
+        let centre = |idx: usize| {
+            let (row, col) = row_and_col(dimensions, idx);
+            (
+                (col * CELL_PIXELS as usize) as f64 + CELL_PIXELS as f64 / 2.0,
+                (row * CELL_PIXELS as usize) as f64 + CELL_PIXELS as f64 / 2.0,
+            )
+        };
+
+        context.set_line_width(PLAYER_LINE_WIDTH);
+        context.set_stroke_style_str(style);
+        context.begin_path();
+        for (idx, cell) in self.trail.iter().enumerate() {
+            let (x, y) = centre(*cell);
+            match idx {
+                0 => context.move_to(x, y),
+                _ => context.line_to(x, y),
+            }
+        }
+        context.stroke();
+
+        context.set_fill_style_str(ITEM_STYLE);
+        for item in &self.items {
+            let (x, y) = centre(*item);
+            let half = CELL_PIXELS as f64 * 0.2;
+            context.fill_rect(x - half, y - half, half * 2.0, half * 2.0);
+        }
+
+        if Date::now() < self.hint.1 {
+            context.set_fill_style_str(HINT_STYLE);
+            for cell in &self.hint.0 {
+                let (x, y) = centre(*cell);
+                context.begin_path();
+                let _ = context.arc(x, y, CELL_PIXELS as f64 * 0.15, 0.0, std::f64::consts::TAU);
+                context.fill();
+            }
+        }
+
+        context.set_fill_style_str(ENEMY_STYLE);
+        for enemy in &self.enemies.0 {
+            let (x, y) = centre(enemy.cell);
+            context.begin_path();
+            let _ = context.arc(x, y, CELL_PIXELS as f64 * 0.3, 0.0, std::f64::consts::TAU);
+            context.fill();
+        }
+
+        let (x, y) = centre(self.cell);
+        context.set_fill_style_str(style);
+        context.begin_path();
+        let _ = context.arc(x, y, CELL_PIXELS as f64 * 0.25, 0.0, std::f64::consts::TAU);
+        context.fill();
+    }
+}
+
+/// Cell mirroring cell across the maze; left to right, otherwise top to bottom or through the
+/// centre, whichever is first neither cell nor excluded. `None` if there is none.
+pub(crate) fn mirrored(dimensions: Dimensions, cell: usize, excluded: &[usize]) -> Option<usize> {
+    let (row, col) = row_and_col(dimensions, cell);
+    let (width, height) = dimensions;
+    [
+        row * width + width - 1 - col,
+        (height - 1 - row) * width + col,
+        (height - 1 - row) * width + width - 1 - col,
+    ]
+    .into_iter()
+    .find(|mirror| *mirror != cell && !excluded.contains(mirror))
+}
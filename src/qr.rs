@@ -0,0 +1,495 @@
+// Encoding of text as a [QR code](https://en.wikipedia.org/wiki/QR_code), in byte mode with
+// the low error correction level so that long permalinks fit.
+
+// Number of error correction codewords in each block, for versions 1 to 40
+const ECC_CODEWORDS_PER_BLOCK: [usize; 40] = [
+    7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30,
+    26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+];
+
+// Number of error correction blocks, for versions 1 to 40
+const ERROR_CORRECTION_BLOCKS: [usize; 40] = [
+    1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14,
+    15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+];
+
+// Bits of the low error correction level in the format information
+const ECC_LOW_FORMAT_BITS: u32 = 1;
+
+/// A type for a QR code; a square of dark and light modules.
+pub(crate) struct QrCode {
+    // number of modules in each dimension
+    size: usize,
+    // whether each module is dark, by row
+    modules: Vec<bool>,
+    // whether each module is part of a function pattern, so is not data and not masked
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode bytes in the smallest version which fits them. `Err` if they are too long.
+    pub(crate) fn encode(bytes: &[u8]) -> Result<Self, String> {
+        let version = (1..=40)
+            .find(|version| {
+                let count_bits = if *version <= 9 { 8 } else { 16 };
+                bytes.len() < 1 << count_bits
+                    && 4 + count_bits + bytes.len() * 8 <= data_codewords(*version) * 8
+            })
+            .ok_or_else(|| format!("{} bytes are too long for a QR code", bytes.len()))?;
+
+        // byte mode, count and data, then a terminator and padding to the capacity
+        let capacity = data_codewords(version) * 8;
+        let mut bits = Vec::new();
+        let mut push = |value: u32, count: usize| {
+            bits.extend((0..count).rev().map(|bit| (value >> bit) & 1 != 0));
+        };
+        push(0b0100, 4);
+        push(bytes.len() as u32, if version <= 9 { 8 } else { 16 });
+        for byte in bytes {
+            push(*byte as u32, 8);
+        }
+        let terminator = 4.min(capacity - bits.len());
+        bits.resize(bits.len() + terminator, false);
+        bits.resize(bits.len().next_multiple_of(8), false);
+        let mut data = bits
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |value, bit| value << 1 | *bit as u8))
+            .collect::<Vec<_>>();
+        for pad in [0xec, 0x11].into_iter().cycle() {
+            if data.len() * 8 >= capacity {
+                break;
+            }
+            data.push(pad);
+        }
+
+        let size = version * 4 + 17;
+        let mut code = Self {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        code.draw_function_patterns(version);
+        code.draw_codewords(&add_error_correction(version, &data));
+
+        // the mask with the lowest penalty
+        let mask = (0..8)
+            .min_by_key(|mask| {
+                code.apply_mask(*mask);
+                code.draw_format_bits(*mask);
+                let penalty = code.penalty();
+                code.apply_mask(*mask);
+                penalty
+            })
+            .expect("should have masks");
+        code.apply_mask(mask);
+        code.draw_format_bits(mask);
+        Ok(code)
+    }
+
+    /// Number of modules in each dimension.
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at column x and row y is dark.
+    pub(crate) fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// Set the module at column x and row y as part of a function pattern.
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    /// Draw the finder, timing and alignment patterns, and the version information.
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for idx in 0..size {
+            self.set_function(6, idx, idx % 2 == 0);
+            self.set_function(idx, 6, idx % 2 == 0);
+        }
+
+        // finder patterns and their separators in three corners
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4_isize {
+                for dx in -4..=4_isize {
+                    let distance = dx.abs().max(dy.abs());
+                    let (x, y) = (x as isize + dx, y as isize + dy);
+                    if (0..size as isize).contains(&x) && (0..size as isize).contains(&y) {
+                        self.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        // alignment patterns, except those overlapping the finder patterns
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, x) in positions.iter().enumerate() {
+            for (j, y) in positions.iter().enumerate() {
+                if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                    continue;
+                }
+                for dy in -2..=2_isize {
+                    for dx in -2..=2_isize {
+                        self.set_function(
+                            (*x as isize + dx) as usize,
+                            (*y as isize + dy) as usize,
+                            dx.abs().max(dy.abs()) != 1,
+                        );
+                    }
+                }
+            }
+        }
+
+        // reserve the format information, drawn once the mask is chosen
+        self.draw_format_bits(0);
+
+        if version >= 7 {
+            let remainder = (0..12).fold(version as u32, |remainder, _| {
+                (remainder << 1) ^ ((remainder >> 11) * 0x1f25)
+            });
+            let bits = (version as u32) << 12 | remainder;
+            for idx in 0..18 {
+                let dark = (bits >> idx) & 1 != 0;
+                let (a, b) = (size - 11 + idx % 3, idx / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    /// Draw the format information of the error correction level and mask, in both copies.
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = ECC_LOW_FORMAT_BITS << 3 | mask;
+        let remainder = (0..10).fold(data, |remainder, _| {
+            (remainder << 1) ^ ((remainder >> 9) * 0x537)
+        });
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |idx: usize| (bits >> idx) & 1 != 0;
+        let size = self.size;
+
+        for idx in 0..=5 {
+            self.set_function(8, idx, bit(idx));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for idx in 9..15 {
+            self.set_function(14 - idx, 8, bit(idx));
+        }
+
+        for idx in 0..8 {
+            self.set_function(size - 1 - idx, 8, bit(idx));
+        }
+        for idx in 8..15 {
+            self.set_function(8, size - 15 + idx, bit(idx));
+        }
+        // always dark
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Draw codewords into the modules which are not function patterns, in pairs of columns
+    /// zigzagging up and down from the bottom right.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut idx = 0;
+        let mut right = size - 1;
+        loop {
+            // the vertical timing pattern is skipped
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for x in [right, right - 1] {
+                    if !self.function[y * size + x] && idx < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[idx / 8] >> (7 - idx % 8)) & 1 != 0;
+                        idx += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Invert the modules which are not function patterns where mask applies.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.function[y * self.size + x] {
+                    self.modules[y * self.size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    /// Penalty of the modules, lower being easier to scan; for runs of the same colour,
+    /// blocks of the same colour, patterns like the finders and imbalance of dark and light.
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let lines = (0..size)
+            .map(|y| (0..size).map(|x| self.is_dark(x, y)).collect::<Vec<_>>())
+            .chain((0..size).map(|x| (0..size).map(|y| self.is_dark(x, y)).collect()));
+
+        let mut penalty = 0;
+        for line in lines {
+            // lengths of the runs, alternately light and dark, with the light boundary beyond
+            // the line added to the first and last light runs
+            let mut runs = vec![size];
+            for run in line.chunk_by(|a, b| a == b) {
+                if run.len() >= 5 {
+                    penalty += run.len() - 2;
+                }
+                match run[0] || runs.len() > 1 {
+                    true => runs.push(run.len()),
+                    false => runs[0] += run.len(),
+                }
+            }
+            if runs.len() % 2 == 0 {
+                runs.push(0);
+            }
+            if let Some(last) = runs.last_mut() {
+                *last += size;
+            }
+            // finder-like patterns; dark, light, dark, light and dark runs in the ratio
+            // 1:1:3:1:1, counted for each side with a light run four times as long
+            for window in runs.windows(7).step_by(2) {
+                if let &[before, n, b, c, d, e, after] = window {
+                    if b == n && c == 3 * n && d == n && e == n {
+                        penalty += 40
+                            * (usize::from(before >= 4 * n && after >= n)
+                                + usize::from(after >= 4 * n && before >= n));
+                    }
+                }
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.is_dark(x, y);
+                if dark == self.is_dark(x + 1, y)
+                    && dark == self.is_dark(x, y + 1)
+                    && dark == self.is_dark(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let (dark, total) = (
+            self.modules.iter().filter(|dark| **dark).count(),
+            self.modules.len(),
+        );
+        let imbalance = (dark * 20).abs_diff(total * 10);
+        penalty + (imbalance.div_ceil(total).saturating_sub(1)) * 10
+    }
+}
+
+/// Number of modules available for data and error correction in version.
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+/// Number of data codewords in version.
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[version - 1] * ERROR_CORRECTION_BLOCKS[version - 1]
+}
+
+/// Centres of the alignment patterns in each dimension of version.
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let alignments = version / 7 + 2;
+    let step = match version {
+        32 => 26,
+        _ => (version * 4 + alignments * 2 + 1) / (alignments * 2 - 2) * 2,
+    };
+    let size = version * 4 + 17;
+    let mut positions = (0..alignments - 1)
+        .map(|idx| size - 7 - idx * step)
+        .collect::<Vec<_>>();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Data split into blocks with error correction codewords appended, interleaved.
+fn add_error_correction(version: usize, data: &[u8]) -> Vec<u8> {
+    let blocks = ERROR_CORRECTION_BLOCKS[version - 1];
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[version - 1];
+    let raw_codewords = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw_codewords % blocks;
+    let short_len = raw_codewords / blocks;
+    let divisor = reed_solomon_divisor(ecc_len);
+
+    // short blocks have one fewer data codeword, padded so that all blocks are the same length
+    let mut start = 0;
+    let blocks = (0..blocks)
+        .map(|idx| {
+            let len = short_len - ecc_len + usize::from(idx >= short_blocks);
+            let block_data = &data[start..start + len];
+            start += len;
+            let mut block = block_data.to_vec();
+            if idx < short_blocks {
+                block.push(0);
+            }
+            block.extend(reed_solomon_remainder(block_data, &divisor));
+            block
+        })
+        .collect::<Vec<_>>();
+
+    let mut codewords = Vec::with_capacity(raw_codewords);
+    for idx in 0..blocks[0].len() {
+        for (block_idx, block) in blocks.iter().enumerate() {
+            if idx != short_len - ecc_len || block_idx >= short_blocks {
+                codewords.push(block[idx]);
+            }
+        }
+    }
+    codewords
+}
+
+/// Product of two elements of the Galois field GF(2^8) used by QR codes.
+fn multiply(x: u8, y: u8) -> u8 {
+    (0..8).rev().fold(0u16, |product, bit| {
+        let product = (product << 1) ^ ((product >> 7) * 0x11d);
+        product ^ (((y >> bit) & 1) as u16 * x as u16)
+    }) as u8
+}
+
+/// Coefficients of the Reed-Solomon generator polynomial of degree, highest first without
+/// the leading 1.
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0; degree];
+    divisor[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for idx in 0..degree {
+            divisor[idx] = multiply(divisor[idx], root);
+            if idx + 1 < degree {
+                divisor[idx] ^= divisor[idx + 1];
+            }
+        }
+        root = multiply(root, 0x02);
+    }
+    divisor
+}
+
+/// Reed-Solomon error correction codewords of data.
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0; divisor.len()];
+    for byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (value, coefficient) in remainder.iter_mut().zip(divisor) {
+            *value ^= multiply(*coefficient, factor);
+        }
+    }
+    remainder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mask of code, read from the first copy of its format information.
+    fn mask(code: &QrCode) -> u32 {
+        let positions = (0..=5)
+            .map(|idx| (8, idx))
+            .chain([(8, 7), (8, 8), (7, 8)])
+            .chain((9..15).map(|idx| (14 - idx, 8)));
+        let bits = positions.enumerate().fold(0, |bits, (idx, (x, y))| {
+            bits | u32::from(code.is_dark(x, y)) << idx
+        });
+        (bits ^ 0x5412) >> 10 & 0b111
+    }
+
+    #[test]
+    fn known_payload_encodes_as_reference() {
+        // encoded in byte mode with the low error correction level by the reference
+        // implementation, choosing mask 2
+        let reference = [
+            "#######...###.#######",
+            "#.....#.###.#.#.....#",
+            "#.###.#...###.#.###.#",
+            "#.###.#.##..#.#.###.#",
+            "#.###.#..#..#.#.###.#",
+            "#.....#.#..#..#.....#",
+            "#######.#.#.#.#######",
+            ".........#...........",
+            "#####.###..#.#.#.#.#.",
+            ".#.###..##.##.#.#.#.#",
+            "####..#...#.#.#..###.",
+            ".##.#.....####...####",
+            ".##..##.#.#.#.##.#.#.",
+            "........#.#.##.##.###",
+            "#######.#..#.#...#.#.",
+            "#.....#...#......###.",
+            "#.###.#.#..#....#...#",
+            "#.###.#.##.#######...",
+            "#.###.#.##..#.#..##..",
+            "#.....#.#.#####...#..",
+            "#######.###.#...#..#.",
+        ];
+        let code = QrCode::encode(b"wasm-maze").expect("should encode");
+        assert_eq!(code.size(), reference.len());
+        for (y, row) in reference.iter().enumerate() {
+            let modules = (0..code.size())
+                .map(|x| if code.is_dark(x, y) { '#' } else { '.' })
+                .collect::<String>();
+            assert_eq!(&modules, row, "row {y}");
+        }
+        assert_eq!(mask(&code), 2);
+    }
+
+    #[test]
+    fn smallest_version_and_lowest_penalty_mask_are_chosen() {
+        // payload, then the version and mask chosen by the reference implementation; the
+        // longest payloads of versions 1 and 7 and a byte more, and some between
+        for (payload, version, expected) in [
+            (&[b'x'; 17][..], 1, 0),
+            (&[b'x'; 18], 2, 0),
+            (b"https://example.com/#maze", 2, 4),
+            (&[b'y'; 100], 5, 0),
+            (&[b'z'; 154], 7, 1),
+            (&[b'z'; 155], 8, 2),
+        ] {
+            let code = QrCode::encode(payload).expect("should encode");
+            let len = payload.len();
+            assert_eq!(code.size(), version * 4 + 17, "{len} bytes");
+            assert_eq!(mask(&code), expected, "{len} bytes");
+        }
+        assert!(QrCode::encode(&[0; 2953]).is_ok());
+        assert!(QrCode::encode(&[0; 2954]).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::solve::Replay;
+use crate::{CellSolution, Cells};
+
+use std::marker::PhantomData;
+
+/// Trait for the part of `Cells` recorded by `Recording`.
+pub(crate) trait Layer {
+    type State: Clone + PartialEq;
+
+    /// Get the state of cell.
+    fn get(cells: &Cells, cell: usize) -> Self::State;
+
+    /// Set the state of cell.
+    fn set(cells: &mut Cells, cell: usize, state: Self::State);
+}
+
+/// A type implementing `Layer` for the walls and walk index changed by generators.
+pub(crate) struct Maze;
+
+impl Layer for Maze {
+    type State = (u8, Option<usize>);
+
+    /// Get the state of cell.
+    fn get(cells: &Cells, cell: usize) -> Self::State {
+        (cells.walls(cell), cells.walks[cell])
+    }
+
+    /// Set the state of cell.
+    fn set(cells: &mut Cells, cell: usize, state: Self::State) {
+        cells.set_walls(cell, state.0);
+        cells.walks[cell] = state.1;
+    }
+}
+
+/// A type implementing `Layer` for the solution details changed by solvers.
+pub(crate) struct Solution;
+
+impl Layer for Solution {
+    type State = CellSolution;
+
+    /// Get the state of cell.
+    fn get(cells: &Cells, cell: usize) -> Self::State {
+        cells.solution(cell)
+    }
+
+    /// Set the state of cell.
+    fn set(cells: &mut Cells, cell: usize, state: Self::State) {
+        cells.set_solution(cell, state);
+    }
+}
+
+/// A change to the state of a cell.
+struct Change<S> {
+    cell: usize,
+    before: S,
+    after: S,
+}
+
+/// A type recording the changes to a `Layer` of cells made at each step of an algorithm,
+/// allowing the cells to be moved backward and forward through the steps.
+pub(crate) struct Recording<T: Layer> {
+    phantom: PhantomData<T>,
+    // per step, changes to the states of cells
+    steps: Vec<Vec<Change<T::State>>>,
+    // number of steps currently applied to the cells
+    position: usize,
+    // cell states before the current step
+    states: Vec<T::State>,
+}
+
+impl<T: Layer> Default for Recording<T> {
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+            steps: Vec::new(),
+            position: 0,
+            states: Vec::new(),
+        }
+    }
+}
+
+impl<T: Layer> Recording<T> {
+    /// Apply a step of an algorithm and record the changes.
+    ///
+    /// Any steps after the current position are discarded.
+    pub(crate) fn record<F, R>(&mut self, cells: &mut Cells, step: F) -> R
+    where
+        F: FnOnce(&mut Cells) -> R,
+    {
+        self.steps.truncate(self.position);
+        self.states.clear();
+        self.states
+            .extend((0..cells.len()).map(|cell| T::get(cells, cell)));
+
+        let result = step(cells);
+
+        self.steps.push(
+            (0..cells.len())
+                .map(|cell| T::get(cells, cell))
+                .zip(self.states.drain(..))
+                .enumerate()
+                .filter(|(_, (after, before))| after != before)
+                .map(|(cell, (after, before))| Change {
+                    cell,
+                    before,
+                    after,
+                })
+                .collect(),
+        );
+        self.position = self.steps.len();
+
+        result
+    }
+
+    /// Cells changed by the last recorded step, with their states before and after.
+    pub(crate) fn last_changes(&self) -> impl Iterator<Item = (usize, &T::State, &T::State)> {
+        self.steps
+            .last()
+            .into_iter()
+            .flatten()
+            .map(|change| (change.cell, &change.before, &change.after))
+    }
+
+    /// Remove all recorded steps.
+    pub(crate) fn clear(&mut self) {
+        self.steps.clear();
+        self.position = 0;
+    }
+
+    /// Number of recorded steps.
+    pub(crate) fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether any steps are recorded.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Number of steps currently applied to the cells.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Move cells to the state after the number of steps.
+    pub(crate) fn seek(&mut self, cells: &mut Cells, position: usize) {
+        let position = position.min(self.steps.len());
+        while self.position > position {
+            self.position -= 1;
+            for change in &self.steps[self.position] {
+                T::set(cells, change.cell, change.before.clone());
+            }
+        }
+        while self.position < position {
+            for change in &self.steps[self.position] {
+                T::set(cells, change.cell, change.after.clone());
+            }
+            self.position += 1;
+        }
+    }
+}
+
+impl Recording<Solution> {
+    /// A `Replay` of the recorded steps.
+    pub(crate) fn replay(&self) -> Replay {
+        Replay::new(
+            self.steps
+                .iter()
+                .map(|changes| {
+                    changes
+                        .iter()
+                        .map(|change| (change.cell, change.after))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A type holding the recordings of the generate and solve phases.
+#[derive(Default)]
+pub(crate) struct Timeline {
+    pub(crate) generation: Recording<Maze>,
+    pub(crate) solution: Recording<Solution>,
+}
+
+impl Timeline {
+    /// Total number of recorded steps.
+    pub(crate) fn len(&self) -> usize {
+        self.generation.len() + self.solution.len()
+    }
+
+    /// Number of steps currently applied to the cells, generation steps first.
+    pub(crate) fn position(&self) -> usize {
+        self.generation.position() + self.solution.position()
+    }
+
+    /// Move cells to the state after the number of steps, generation steps first.
+    pub(crate) fn seek(&mut self, cells: &mut Cells, position: usize) {
+        match position.checked_sub(self.generation.len()) {
+            None => {
+                self.solution.seek(cells, 0);
+                self.generation.seek(cells, position);
+            }
+            Some(position) => {
+                self.generation.seek(cells, self.generation.len());
+                self.solution.seek(cells, position);
+            }
+        }
+    }
+
+    /// Move cells to the state after all steps.
+    pub(crate) fn seek_end(&mut self, cells: &mut Cells) {
+        self.seek(cells, self.len());
+    }
+}
@@ -0,0 +1,75 @@
+// Identities of the available algorithms, so that they can be selected by an id which does
+// not change with the names displayed for them.
+
+use crate::algorithm_name;
+
+use std::collections::BTreeMap;
+
+/// Category of an algorithm, for grouping in lists.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Category {
+    /// Generators of a random spanning tree of the cells.
+    SpanningTree,
+    /// Generators and solvers searching depth first.
+    DepthFirst,
+    /// Solvers finding shortest paths.
+    ShortestPath,
+    /// Solvers following a wall.
+    WallFollower,
+    /// Solvers moving otherwise than a cell at a time.
+    Movement,
+    /// Solvers registered from JavaScript.
+    #[cfg(feature = "wasm")]
+    Custom,
+}
+
+impl Category {
+    /// Id of the category.
+    pub(crate) fn id(&self) -> &'static str {
+        match self {
+            Self::SpanningTree => "spanning-tree",
+            Self::DepthFirst => "depth-first",
+            Self::ShortestPath => "shortest-path",
+            Self::WallFollower => "wall-follower",
+            Self::Movement => "movement",
+            #[cfg(feature = "wasm")]
+            Self::Custom => "custom",
+        }
+    }
+}
+
+/// Trait for an algorithm registered by its id, which does not change with its display name.
+pub(crate) trait Registered {
+    /// Display name of the algorithm.
+    fn name(&self) -> &str;
+
+    /// Category of the algorithm.
+    fn category(&self) -> Category;
+}
+
+/// Id of the algorithm with id value or named value, or otherwise whose name starts with
+/// value as for `algorithm_name`. Value if none match.
+pub(crate) fn resolve<T: Registered>(registry: &BTreeMap<String, T>, value: &str) -> String {
+    if registry.contains_key(value) {
+        return value.to_string();
+    }
+    let names = registry.values().map(|entry| entry.name().to_string());
+    let name = match registry.values().any(|entry| entry.name() == value) {
+        true => value.to_string(),
+        false => algorithm_name(names, value),
+    };
+    registry
+        .iter()
+        .find(|(_, entry)| entry.name() == name)
+        .map_or_else(|| value.to_string(), |(id, _)| id.clone())
+}
+
+/// Id made from a name, in lower case with words joined by '-'.
+#[cfg(feature = "wasm")]
+pub(crate) fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
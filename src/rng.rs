@@ -0,0 +1,226 @@
+// Sources of random numbers, passed to the algorithms when they are created so that each
+// may be seeded independently.
+
+use crate::format::binary::{decode_base64, encode_base64};
+use crate::platform::{self, Level};
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Trait for a source of random numbers.
+pub(crate) trait Rng {
+    /// Random number in the range 0 (inclusive) to 1 (exclusive).
+    fn random(&mut self) -> f64;
+
+    /// Random index below len, which is not zero.
+    fn index(&mut self, len: usize) -> usize {
+        (self.random() * len as f64) as usize
+    }
+}
+
+/// A type implementing `Rng` with the generator shared by the platform, which is seeded
+/// differently on each run.
+#[derive(Default)]
+pub(crate) struct Platform;
+
+impl Rng for Platform {
+    fn random(&mut self) -> f64 {
+        platform::random()
+    }
+}
+
+/// A type implementing `Rng` with a [xorshift](https://en.wikipedia.org/wiki/Xorshift)
+/// generator of its own.
+pub(crate) struct Xorshift {
+    state: u64,
+}
+
+impl Xorshift {
+    /// Create from seed; the same seed gives the same random numbers.
+    pub(crate) fn new(seed: u64) -> Self {
+        // the state must not be zero; mix so that nearby seeds diverge quickly
+        Self {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+}
+
+impl Rng for Xorshift {
+    fn random(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        // the 53 most significant bits fill the mantissa
+        (self.state >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A random draw made by an algorithm.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Draw {
+    // index returned by `Rng::index`
+    Index(usize),
+    // number returned by `Rng::random`
+    Random(f64),
+}
+
+/// A type for the random draws made by algorithms, in order, so that a run may be replayed
+/// step for step. Clones share the draws, so that several algorithms record in turn and
+/// are replayed in the same order.
+#[derive(Clone, Default)]
+pub(crate) struct Draws(Rc<RefCell<VecDeque<Draw>>>);
+
+impl Draws {
+    /// Number of draws not yet replayed.
+    pub(crate) fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// A `Rng` drawing from rng and recording each draw into these draws.
+    pub(crate) fn recorder(&self, rng: Box<dyn Rng>) -> Box<dyn Rng> {
+        Box::new(Recorder {
+            rng,
+            draws: self.clone(),
+        })
+    }
+
+    /// A `Rng` replaying these draws, removing each as it is replayed.
+    pub(crate) fn replayer(&self) -> Box<dyn Rng> {
+        Box::new(Replayer {
+            draws: self.clone(),
+            diverged: false,
+        })
+    }
+
+    /// Serialise as base64 of a variable length number for each draw; twice an index, or
+    /// 1 followed by the 8 bytes of a random number.
+    pub(crate) fn to_base64(&self) -> String {
+        let mut bytes = Vec::new();
+        for draw in self.0.borrow().iter() {
+            match draw {
+                Draw::Index(index) => push_varint(&mut bytes, *index as u64 * 2),
+                Draw::Random(value) => {
+                    push_varint(&mut bytes, 1);
+                    bytes.extend_from_slice(&value.to_bits().to_le_bytes());
+                }
+            }
+        }
+        encode_base64(&bytes)
+    }
+
+    /// Deserialise from the base64 of `to_base64`.
+    pub(crate) fn from_base64(base64: &str) -> Result<Self, String> {
+        let bytes = decode_base64(base64.trim())?;
+        let mut bytes = bytes.iter();
+        let mut draws = VecDeque::new();
+        while bytes.len() > 0 {
+            draws.push_back(match read_varint(&mut bytes)? {
+                1 => {
+                    let value = bytes.by_ref().take(8).copied().collect::<Vec<_>>();
+                    let value = f64::from_bits(u64::from_le_bytes(
+                        value.try_into().map_err(|_| "random draw is truncated")?,
+                    ));
+                    if !(0.0..1.0).contains(&value) {
+                        return Err(format!("random draw {value} is not between 0 and 1"));
+                    }
+                    Draw::Random(value)
+                }
+                value if value % 2 == 0 => Draw::Index((value / 2) as usize),
+                value => return Err(format!("unknown draw {value}")),
+            });
+        }
+        Ok(Self(Rc::new(RefCell::new(draws))))
+    }
+}
+
+/// A type implementing `Rng` which records each draw of another.
+struct Recorder {
+    rng: Box<dyn Rng>,
+    draws: Draws,
+}
+
+impl Rng for Recorder {
+    fn random(&mut self) -> f64 {
+        let value = self.rng.random();
+        self.draws.0.borrow_mut().push_back(Draw::Random(value));
+        value
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        let index = self.rng.index(len);
+        self.draws.0.borrow_mut().push_back(Draw::Index(index));
+        index
+    }
+}
+
+/// A type implementing `Rng` which replays recorded draws. Once the draws are used up, or
+/// if they do not match those asked for, the draws are from `Platform` instead.
+struct Replayer {
+    draws: Draws,
+    // whether the draws have stopped matching the run being replayed
+    diverged: bool,
+}
+
+impl Replayer {
+    /// Log, the first time, that the draws no longer match the run being replayed.
+    fn diverged(&mut self, expected: &str) {
+        if std::mem::replace(&mut self.diverged, true) {
+            return;
+        }
+        platform::log(
+            Level::Info,
+            format_args!(
+                "replay diverged expecting {expected} with {} draws left; drawing at random",
+                self.draws.len()
+            ),
+        );
+    }
+}
+
+impl Rng for Replayer {
+    fn random(&mut self) -> f64 {
+        let draw = self.draws.0.borrow_mut().pop_front();
+        match draw {
+            Some(Draw::Random(value)) => value,
+            _ => {
+                self.diverged("a random number");
+                Platform.random()
+            }
+        }
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        let draw = self.draws.0.borrow_mut().pop_front();
+        match draw {
+            Some(Draw::Index(index)) if index < len => index,
+            _ => {
+                self.diverged(&format!("an index below {len}"));
+                Platform.index(len)
+            }
+        }
+    }
+}
+
+/// Push value as a variable length number; 7 bits to a byte, least significant first, with
+/// the top bit set on all but the last byte.
+fn push_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Read a variable length number written by `push_varint`.
+fn read_varint<'a>(bytes: &mut impl Iterator<Item = &'a u8>) -> Result<u64, String> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = bytes.next().ok_or("draw is truncated")?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("draw is too long".to_string())
+}
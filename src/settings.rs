@@ -0,0 +1,257 @@
+use crate::app::{window, DEFAULT_CONFIRM_CELLS, DEFAULT_HEIGHT, DEFAULT_WIDTH};
+use crate::app::{DEFAULT_MEMORY_BUDGET, DEFAULT_PRINT_SCALE, DEFAULT_SPEED};
+use crate::app::{MAXIMUM_PRINT_SCALE, MAXIMUM_SPEED};
+use crate::generate::generators;
+use crate::platform::{Level, DEFAULT_LEVEL};
+use crate::registry::resolve;
+use crate::solve::{solvers, DEFAULT_ORDER};
+use crate::theme::{LineCap, LineJoin, Theme};
+use crate::{Direction, MINIMUM_DIMENSION};
+
+// Key of the settings in local storage
+const STORAGE_KEY: &str = "wasm-maze-settings";
+
+/// Settings of the controls, remembered between visits in local storage.
+#[derive(Clone)]
+pub(crate) struct Settings {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) confirm_cells: usize,
+    // megabytes of memory above which generation is refused
+    pub(crate) memory_budget: usize,
+    // whether the shortest paths from the start are searched once generated and kept for reuse
+    pub(crate) precompute_paths: bool,
+    // seed of the random numbers of a generated maze; if None then a random seed
+    pub(crate) seed: Option<u64>,
+    // ids of the chosen algorithms; if empty then the first available
+    pub(crate) generator: String,
+    pub(crate) solver: String,
+    pub(crate) comparison: String,
+    // directions in the order tried by the depth first search solver
+    pub(crate) order: [Direction; 4],
+    // steps after which a solver gives up; if 0 then no limit
+    pub(crate) max_steps: usize,
+    // whether solving pauses where the solver has more than one way on
+    pub(crate) pause_at_decisions: bool,
+    pub(crate) new_locations: bool,
+    pub(crate) auto_solve: bool,
+    pub(crate) race: bool,
+    // whether play slides on to the next wall, as on ice
+    pub(crate) slide: bool,
+    // number of items to collect in play before the goal
+    pub(crate) items: usize,
+    // number of enemies patrolling in play
+    pub(crate) enemies: usize,
+    // whether two players race each other in play
+    pub(crate) two_players: bool,
+    pub(crate) cell_details: bool,
+    // whether the legend of the colours is shown under the maze
+    pub(crate) legend: bool,
+    // whether the rates of frames and steps are shown under the maze
+    pub(crate) diagnostics: bool,
+    // whether the solver is heard as well as seen
+    pub(crate) sound: bool,
+    // style in which the maze is drawn
+    pub(crate) theme: Theme,
+    // multiple of the resolution on screen of a rendering for print
+    pub(crate) print_scale: u32,
+    pub(crate) demo: bool,
+    // multiplier of the number of algorithm steps applied each frame
+    pub(crate) speed: u32,
+    // most detailed level of the messages logged to the console
+    pub(crate) log_level: Level,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH as usize,
+            height: DEFAULT_HEIGHT as usize,
+            confirm_cells: DEFAULT_CONFIRM_CELLS,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            precompute_paths: true,
+            seed: None,
+            generator: String::new(),
+            solver: String::new(),
+            comparison: String::new(),
+            order: DEFAULT_ORDER,
+            max_steps: 0,
+            pause_at_decisions: false,
+            new_locations: false,
+            auto_solve: true,
+            race: false,
+            slide: false,
+            items: 0,
+            enemies: 0,
+            two_players: false,
+            cell_details: true,
+            legend: false,
+            diagnostics: false,
+            sound: false,
+            theme: Theme::default(),
+            print_scale: DEFAULT_PRINT_SCALE,
+            demo: false,
+            speed: DEFAULT_SPEED,
+            log_level: DEFAULT_LEVEL,
+        }
+    }
+}
+
+impl Settings {
+    /// Serialise as lines of `name=value`.
+    pub(crate) fn serialise(&self) -> String {
+        [
+            format!("width={}", self.width),
+            format!("height={}", self.height),
+            format!("confirm-cells={}", self.confirm_cells),
+            format!("memory-budget={}", self.memory_budget),
+            format!("precompute-paths={}", self.precompute_paths),
+            format!(
+                "seed={}",
+                self.seed.map_or(String::new(), |seed| seed.to_string())
+            ),
+            format!("generator={}", self.generator),
+            format!("solver={}", self.solver),
+            format!("comparison={}", self.comparison),
+            format!("order={}", order_letters(self.order)),
+            format!("max-steps={}", self.max_steps),
+            format!("pause-at-decisions={}", self.pause_at_decisions),
+            format!("new-locations={}", self.new_locations),
+            format!("auto-solve={}", self.auto_solve),
+            format!("race={}", self.race),
+            format!("slide={}", self.slide),
+            format!("items={}", self.items),
+            format!("enemies={}", self.enemies),
+            format!("two-players={}", self.two_players),
+            format!("cell-details={}", self.cell_details),
+            format!("legend={}", self.legend),
+            format!("diagnostics={}", self.diagnostics),
+            format!("sound={}", self.sound),
+            format!("wall-width={}", self.theme.wall_width),
+            format!("passage-width={}", self.theme.passage_width),
+            format!("line-cap={}", self.theme.line_cap.name()),
+            format!("line-join={}", self.theme.line_join.name()),
+            format!("print-scale={}", self.print_scale),
+            format!("demo={}", self.demo),
+            format!("speed={}", self.speed),
+            format!("log-level={}", self.log_level.name()),
+        ]
+        .join("\n")
+    }
+
+    /// Deserialise from lines of `name=value`. Unknown names and invalid values are
+    /// ignored, leaving the default.
+    pub(crate) fn deserialise(text: &str) -> Self {
+        let mut settings = Self::default();
+        for (name, value) in text.lines().filter_map(|line| line.split_once('=')) {
+            settings.set(name, value);
+        }
+        settings
+    }
+
+    /// Set the setting with name from value. Unknown names and invalid values are ignored.
+    pub(crate) fn set(&mut self, name: &str, value: &str) {
+        match name {
+            "width" => parse_dimension(value, &mut self.width),
+            "height" => parse_dimension(value, &mut self.height),
+            "confirm-cells" => parse(value, &mut self.confirm_cells),
+            "memory-budget" => {
+                if let Ok(budget) = value.parse::<usize>() {
+                    self.memory_budget = budget.max(1);
+                }
+            }
+            "precompute-paths" => parse(value, &mut self.precompute_paths),
+            "seed" => self.seed = value.trim().parse().ok(),
+            "generator" => self.generator = resolve(&generators(), value),
+            "solver" => self.solver = resolve(&solvers(), value),
+            "comparison" => self.comparison = resolve(&solvers(), value),
+            "order" => {
+                if let Some(order) = Direction::parse_order(value) {
+                    self.order = order;
+                }
+            }
+            "max-steps" => parse(value, &mut self.max_steps),
+            "pause-at-decisions" => parse(value, &mut self.pause_at_decisions),
+            "new-locations" => parse(value, &mut self.new_locations),
+            "auto-solve" => parse(value, &mut self.auto_solve),
+            "race" => parse(value, &mut self.race),
+            "slide" => parse(value, &mut self.slide),
+            "items" => parse(value, &mut self.items),
+            "enemies" => parse(value, &mut self.enemies),
+            "two-players" => parse(value, &mut self.two_players),
+            "cell-details" => parse(value, &mut self.cell_details),
+            "legend" => parse(value, &mut self.legend),
+            "diagnostics" => parse(value, &mut self.diagnostics),
+            "sound" => parse(value, &mut self.sound),
+            "wall-width" => {
+                if let Ok(width) = value.parse::<f64>() {
+                    self.theme.set_wall_width(width);
+                }
+            }
+            "passage-width" => parse(value, &mut self.theme.passage_width),
+            "line-cap" => {
+                if let Some(cap) = LineCap::parse(value) {
+                    self.theme.line_cap = cap;
+                }
+            }
+            "line-join" => {
+                if let Some(join) = LineJoin::parse(value) {
+                    self.theme.line_join = join;
+                }
+            }
+            "print-scale" => {
+                if let Ok(scale) = value.parse::<u32>() {
+                    self.print_scale = scale.clamp(1, MAXIMUM_PRINT_SCALE);
+                }
+            }
+            "demo" => parse(value, &mut self.demo),
+            "speed" => {
+                if let Ok(speed) = value.parse::<u32>() {
+                    self.speed = speed.clamp(1, MAXIMUM_SPEED);
+                }
+            }
+            "log-level" => {
+                if let Some(level) = Level::parse(value) {
+                    self.log_level = level;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Load from local storage, or the default if unavailable.
+    pub(crate) fn load() -> Self {
+        window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .map_or_else(Self::default, |text| Self::deserialise(&text))
+    }
+
+    /// Save to local storage, if available.
+    pub(crate) fn save(&self) {
+        if let Ok(Some(storage)) = window().local_storage() {
+            let _ = storage.set_item(STORAGE_KEY, &self.serialise());
+        }
+    }
+}
+
+/// Parse value into setting, if valid.
+fn parse<T: std::str::FromStr>(value: &str, setting: &mut T) {
+    if let Ok(value) = value.parse() {
+        *setting = value;
+    }
+}
+
+/// Parse value into dimension setting, if valid.
+fn parse_dimension(value: &str, setting: &mut usize) {
+    if let Ok(value) = value.parse::<usize>() {
+        *setting = value.max(MINIMUM_DIMENSION);
+    }
+}
+
+/// Compass letters of order of directions; e.g. "NESW".
+pub(crate) fn order_letters(order: [Direction; 4]) -> String {
+    order.iter().map(Direction::letter).collect()
+}
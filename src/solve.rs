@@ -1,9 +1,646 @@
 pub(crate) mod a_star_search;
+#[cfg(feature = "wasm")]
+pub(crate) mod custom_search;
+pub(crate) mod depth_first_search;
+pub(crate) mod fringe_search;
 pub(crate) mod randomised_depth_first_search;
+#[cfg(feature = "wasm")]
+pub(crate) mod replay;
+pub(crate) mod sliding_search;
 pub(crate) mod solver;
+pub(crate) mod step_limit;
+#[cfg(feature = "wasm")]
+pub(crate) mod tour;
 pub(crate) mod wall_follower_search;
 
 pub(crate) use a_star_search::*;
+#[cfg(feature = "wasm")]
+pub(crate) use custom_search::*;
+pub(crate) use depth_first_search::*;
+pub(crate) use fringe_search::*;
 pub(crate) use randomised_depth_first_search::*;
+#[cfg(feature = "wasm")]
+pub(crate) use replay::*;
+pub(crate) use sliding_search::*;
 pub(crate) use solver::*;
+pub(crate) use step_limit::*;
+#[cfg(feature = "wasm")]
+pub(crate) use tour::*;
 pub(crate) use wall_follower_search::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "wasm")]
+    use crate::format::binary::{from_base64, from_bytes, to_base64, to_bytes};
+    #[cfg(feature = "wasm")]
+    use crate::format::json::{from_json, to_json};
+    use crate::format::MazeData;
+    use crate::generate::generators;
+    use crate::generate::tests::{cases, generate};
+    use crate::movement::Movement;
+    use crate::parameter::Values;
+    use crate::passage::carve;
+    #[cfg(feature = "wasm")]
+    use crate::path::PathTree;
+    use crate::path::{distances, shortest_moves, shortest_path, solution};
+    use crate::registry::{resolve, Registered};
+    use crate::rng::{Rng, Xorshift};
+    use crate::step::StepResult;
+    use crate::{Cells, Dimensions, Direction, Directions};
+
+    // Solvers whose solutions are shortest paths
+    const OPTIMAL: [&str; 3] = ["a-star", "dijkstra", "fringe"];
+
+    // Solvers moving by sliding, which may find no path where sliding never stops at the goal
+    const SLIDING: [&str; 1] = ["sliding"];
+
+    /// Solution of the named solver between cells, with seed. `None` if not solved.
+    fn solve(
+        name: &str,
+        dimensions: Dimensions,
+        cells: &Cells,
+        from: usize,
+        to: usize,
+        seed: u64,
+    ) -> Option<Vec<usize>> {
+        let mut cells = cells.clone();
+        cells.reset_solution(from, to);
+        let mut solver = solvers()[name].create(Box::new(Xorshift::new(seed)), &Values::default());
+        while solver.step(dimensions, &mut cells, from, to).is_continue() {}
+        solution(&cells, from, to)
+    }
+
+    /// Two different random cells.
+    fn random_cells(rng: &mut impl Rng, count: usize) -> (usize, usize) {
+        let from = rng.index(count);
+        let to = (from + 1 + rng.index(count - 1)) % count;
+        (from, to)
+    }
+
+    #[test]
+    fn solvers_find_valid_paths() {
+        for generator in generators().into_keys() {
+            for (dimensions, seed) in cases() {
+                let cells = generate(&generator, dimensions, seed);
+                let (from, to) = random_cells(&mut Xorshift::new(seed), cells.len());
+                for name in solvers().into_keys() {
+                    let case = format!("{name} on {generator} {dimensions:?} seed {seed}");
+                    let path = solve(&name, dimensions, &cells, from, to, seed);
+                    if path.is_none() && SLIDING.contains(&name.as_str()) {
+                        continue;
+                    }
+                    assert!(path.is_some(), "{case}: not solved");
+                    let mut data = MazeData::new(dimensions, &cells, from, to);
+                    data.solution = path;
+                    assert_eq!(data.validate(), Ok(()), "{case}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn optimal_solvers_find_shortest_paths() {
+        let generator = generators()
+            .into_keys()
+            .next()
+            .expect("should have generator");
+        for (dimensions, seed) in cases() {
+            // remove extra walls so that there are loops and so several paths
+            let mut cells = generate(&generator, dimensions, seed);
+            let mut rng = Xorshift::new(seed);
+            for cell in 0..cells.len() {
+                for direction in [Direction::East, Direction::South] {
+                    if let Some(neighbour) = direction.neighbour(dimensions, cell) {
+                        if rng.random() < 0.2 {
+                            carve(&mut cells, dimensions, cell, neighbour);
+                        }
+                    }
+                }
+            }
+            let (from, to) = random_cells(&mut rng, cells.len());
+            let shortest = shortest_path(dimensions, &cells, from, to).map(|path| path.len());
+            for name in OPTIMAL {
+                assert_eq!(
+                    solve(name, dimensions, &cells, from, to, seed).map(|path| path.len()),
+                    shortest,
+                    "{name} {dimensions:?} seed {seed} from {from} to {to}"
+                );
+            }
+        }
+    }
+
+    /// A heuristic of the exact distance to the goal at some cells and zero at the others,
+    /// so never overestimating though not consistent.
+    struct Patchy(Vec<Option<usize>>);
+
+    impl AStarSearchHeuristic for Patchy {
+        fn heuristic(&self, _: Dimensions, from: usize, _: usize) -> usize {
+            match from % 3 {
+                0 => self.0[from].unwrap_or_default(),
+                _ => 0,
+            }
+        }
+    }
+
+    #[test]
+    fn inconsistent_heuristics_find_shortest_paths() {
+        let generator = generators()
+            .into_keys()
+            .next()
+            .expect("should have generator");
+        for (dimensions, seed) in cases() {
+            let mut cells = generate(&generator, dimensions, seed);
+            let mut rng = Xorshift::new(seed);
+            for cell in 0..cells.len() {
+                for direction in [Direction::East, Direction::South] {
+                    if let Some(neighbour) = direction.neighbour(dimensions, cell) {
+                        if rng.random() < 0.3 {
+                            carve(&mut cells, dimensions, cell, neighbour);
+                        }
+                    }
+                }
+            }
+            let (from, to) = random_cells(&mut rng, cells.len());
+            let heuristic = Patchy(distances(dimensions, &cells, to));
+            let mut solver = AStarSearch::new(heuristic);
+            cells.reset_solution(from, to);
+            while solver.step(dimensions, &mut cells, from, to).is_continue() {}
+            assert_eq!(
+                solution(&cells, from, to).map(|path| path.len()),
+                shortest_path(dimensions, &cells, from, to).map(|path| path.len()),
+                "{dimensions:?} seed {seed} from {from} to {to}"
+            );
+        }
+    }
+
+    #[test]
+    fn weighting_the_heuristic_finds_valid_paths() {
+        let name = OPTIMAL[0];
+        let mut values = Values::default();
+        values.set("epsilon", 2.0);
+        for (dimensions, seed) in cases().step_by(3) {
+            let mut cells = generate("kruskal", dimensions, seed);
+            let mut rng = Xorshift::new(seed);
+            for cell in 0..cells.len() {
+                if let Some(neighbour) = Direction::East.neighbour(dimensions, cell) {
+                    if rng.random() < 0.3 {
+                        carve(&mut cells, dimensions, cell, neighbour);
+                    }
+                }
+            }
+            let (from, to) = random_cells(&mut rng, cells.len());
+            cells.reset_solution(from, to);
+            let mut solver = solvers()[name].create(Box::new(Xorshift::new(seed)), &values);
+            while solver.step(dimensions, &mut cells, from, to).is_continue() {}
+            let case = format!("{dimensions:?} seed {seed}");
+            let path = solution(&cells, from, to).expect("should be solved");
+            let shortest = shortest_path(dimensions, &cells, from, to).expect("should have path");
+            assert!(path.len() >= shortest.len(), "{case}");
+            let mut data = MazeData::new(dimensions, &cells, from, to);
+            data.solution = Some(path);
+            assert_eq!(data.validate(), Ok(()), "{case}");
+        }
+    }
+
+    #[test]
+    fn solvers_are_resolved_by_id_or_unique_name() {
+        let solvers = solvers();
+        for (id, solver) in &solvers {
+            assert_eq!(
+                solvers
+                    .values()
+                    .filter(|other| other.name() == solver.name())
+                    .count(),
+                1,
+                "{id}"
+            );
+            assert_eq!(&resolve(&solvers, solver.name()), id);
+            assert_eq!(&resolve(&solvers, id), id);
+        }
+        assert_eq!(resolve(&solvers, "astar"), OPTIMAL[0]);
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn path_trees_hold_shortest_paths_to_every_cell() {
+        for (dimensions, seed) in cases().step_by(5) {
+            let cells = generate("kruskal", dimensions, seed);
+            let from = Xorshift::new(seed).index(cells.len());
+            let tree = PathTree::new(dimensions, &cells, from);
+            for cell in 0..cells.len() {
+                let path = tree.path(cell);
+                assert_eq!(
+                    path,
+                    shortest_path(dimensions, &cells, from, cell),
+                    "{cell}"
+                );
+                assert_eq!(tree.distance(cell), path.map(|path| path.len() - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn searching_solvers_fail_without_path() {
+        // only cells 0 and 1 are connected, so cell 8 is not reachable from cell 0
+        let dimensions = (3, 3);
+        let mut cells = Cells::new(dimensions);
+        carve(&mut cells, dimensions, 0, 1);
+        for name in OPTIMAL.into_iter().chain([
+            "randomised-depth-first",
+            "wall-follower-left",
+            "wall-follower-right",
+            "wall-follower-auto",
+        ]) {
+            let mut cells = cells.clone();
+            cells.reset_solution(0, 8);
+            let mut solver = solvers()[name].create(Box::new(Xorshift::new(0)), &Values::default());
+            let result = loop {
+                match solver.step(dimensions, &mut cells, 0, 8) {
+                    StepResult::Continue { .. } => {}
+                    result => break result,
+                }
+            };
+            assert_eq!(
+                result,
+                StepResult::Failed("no path from cell 0 to cell 8".to_string()),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn depth_first_search_follows_direction_order() {
+        // no walls inside, so each direction order takes its own path
+        let dimensions = (3, 3);
+        let mut cells = Cells::new(dimensions);
+        for cell in 0..cells.len() {
+            for direction in [Direction::East, Direction::South] {
+                if let Some(neighbour) = direction.neighbour(dimensions, cell) {
+                    carve(&mut cells, dimensions, cell, neighbour);
+                }
+            }
+        }
+        // the order is a parameter of the solver
+        let kind = &solvers()[DEPTH_FIRST_SEARCH_ID];
+        let parameter = kind.parameters()[0];
+        assert_eq!(parameter.key, ORDER);
+        for (text, path) in [("NESW", [0, 1, 2, 5, 8]), ("s, w, e, n", [0, 3, 6, 7, 8])] {
+            let mut values = Values::default();
+            values.set(ORDER, parameter.parse(text).expect("should be an order"));
+            cells.reset_solution(0, 8);
+            let mut solver = kind.create(Box::new(Xorshift::new(0)), &values);
+            while solver.step(dimensions, &mut cells, 0, 8).is_continue() {}
+            assert_eq!(solution(&cells, 0, 8), Some(path.to_vec()), "{text}");
+        }
+        for text in ["NESN", "NES", "NESWN", "NEXW"] {
+            assert!(parameter.parse(text).is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn direction_orders_are_numbered() {
+        assert_eq!(Direction::order_index(DEFAULT_ORDER), 0);
+        let orders: Vec<_> = (0..Direction::ORDERS).map(Direction::order_at).collect();
+        for (index, order) in orders.iter().enumerate() {
+            assert_eq!(Direction::order_index(*order), index, "{index}");
+            assert!(!orders[..index].contains(order), "{index}");
+        }
+    }
+
+    #[test]
+    fn solvers_give_up_at_step_limit() {
+        let dimensions = (5, 5);
+        let cells = generate("kruskal", dimensions, 1);
+        let mut values = Values::default();
+        values.set(MAX_STEPS, 2.0);
+        for name in ["randomised-depth-first", "wall-follower-auto"] {
+            let mut cells = cells.clone();
+            cells.reset_solution(0, 24);
+            let mut solver = solvers()[name].create(Box::new(Xorshift::new(0)), &values);
+            let result = loop {
+                match solver.step(dimensions, &mut cells, 0, 24) {
+                    StepResult::Continue { .. } => {}
+                    result => break result,
+                }
+            };
+            assert_eq!(
+                result,
+                StepResult::Failed("gave up after 2 steps".to_string()),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn solvers_start_again_after_step_limit() {
+        let dimensions = (5, 5);
+        let cells = generate("kruskal", dimensions, 1);
+        let mut values = Values::default();
+        values.set(MAX_STEPS, 2.0);
+        // solution of each cell after a solve that gives up with the solver given
+        let give_up = |solver: &mut Box<dyn Solver>| {
+            let mut cells = cells.clone();
+            cells.reset_solution(0, 24);
+            while solver.step(dimensions, &mut cells, 0, 24).is_continue() {}
+            (0..cells.len())
+                .map(|cell| cells.solution(cell))
+                .collect::<Vec<_>>()
+        };
+        let kind = &solvers()["wall-follower-left"];
+        let mut solver = kind.create(Box::new(Xorshift::new(0)), &values);
+        let first = give_up(&mut solver);
+        assert!(first == give_up(&mut solver));
+        let mut fresh = kind.create(Box::new(Xorshift::new(0)), &values);
+        assert!(first == give_up(&mut fresh));
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn wall_follower_auto_takes_path_of_first_hand() {
+        let generator = generators()
+            .into_keys()
+            .next()
+            .expect("should have generator");
+        for (dimensions, seed) in cases() {
+            let cells = generate(&generator, dimensions, seed);
+            let (from, to) = random_cells(&mut Xorshift::new(seed), cells.len());
+            // steps and path of each solver
+            let runs = ["left", "right", "auto"].map(|hand| {
+                let mut cells = cells.clone();
+                cells.reset_solution(from, to);
+                let mut solver = solvers()[&format!("wall-follower-{hand}")]
+                    .create(Box::new(Xorshift::new(seed)), &Values::default());
+                let mut steps = 0;
+                while solver.step(dimensions, &mut cells, from, to).is_continue() {
+                    steps += 1;
+                }
+                (steps, solution(&cells, from, to), solver.outcome())
+            });
+            let [left, right, auto] = runs;
+            let (first, hand) = match left.0 <= right.0 {
+                true => (left, "left"),
+                false => (right, "right"),
+            };
+            let case = format!("{dimensions:?} seed {seed} from {from} to {to}");
+            assert_eq!(auto.0, first.0, "{case}");
+            assert_eq!(auto.1, first.1, "{case}");
+            assert_eq!(
+                auto.2,
+                Some(format!("the {hand} hand reached the goal first")),
+                "{case}"
+            );
+        }
+    }
+
+    #[test]
+    fn sliding_solvers_find_fewest_slides() {
+        let generator = generators()
+            .into_keys()
+            .next()
+            .expect("should have generator");
+        for (dimensions, seed) in cases() {
+            let cells = generate(&generator, dimensions, seed);
+            let (from, to) = random_cells(&mut Xorshift::new(seed), cells.len());
+            let fewest = shortest_moves(dimensions, &cells, from, to, Movement::Slide);
+            for name in SLIDING {
+                let case = format!("{name} {dimensions:?} seed {seed} from {from} to {to}");
+                let path = solve(name, dimensions, &cells, from, to, seed);
+                assert_eq!(path.is_some(), fewest.is_some(), "{case}");
+                // a turn is made only where a slide stopped, so the path turns as often
+                let turns = |path: &[usize]| {
+                    path.windows(3)
+                        .filter(|cells| {
+                            cells[1].wrapping_sub(cells[0]) != cells[2].wrapping_sub(cells[1])
+                        })
+                        .count()
+                };
+                if let (Some(path), Some(fewest)) = (path, &fewest) {
+                    assert!(turns(&path) < fewest.len() - 1, "{case}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn tours_through_waypoints_visit_them_in_order() {
+        let generator = generators()
+            .into_keys()
+            .next()
+            .expect("should have generator");
+        for (dimensions, seed) in cases() {
+            let mut cells = generate(&generator, dimensions, seed);
+            let mut rng = Xorshift::new(seed);
+            let (from, to) = random_cells(&mut rng, cells.len());
+            let waypoints = scatter(&mut rng, cells.len(), 3, &[from, to]);
+            let case = format!("{dimensions:?} seed {seed} waypoints {waypoints:?}");
+            cells.reset_solution(from, to);
+            let mut tour = Tour::through(
+                solvers()[OPTIMAL[0]].create(Box::new(rng), &Values::default()),
+                waypoints.clone(),
+            );
+            while tour.step(dimensions, &mut cells, from, to).is_continue() {}
+            let legs = tour.legs();
+            let stops = std::iter::once(from)
+                .chain(waypoints)
+                .chain(std::iter::once(to))
+                .collect::<Vec<_>>();
+            assert_eq!(legs.len(), stops.len() - 1, "{case}");
+            for (leg, pair) in legs.iter().zip(stops.windows(2)) {
+                assert_eq!(leg.first(), Some(&pair[0]), "{case}");
+                assert_eq!(leg.last(), Some(&pair[1]), "{case}");
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn tours_visit_every_item() {
+        let generator = generators()
+            .into_keys()
+            .next()
+            .expect("should have generator");
+        for (dimensions, seed) in cases() {
+            let mut cells = generate(&generator, dimensions, seed);
+            let mut rng = Xorshift::new(seed);
+            let (from, to) = random_cells(&mut rng, cells.len());
+            let items = scatter(&mut rng, cells.len(), 3, &[from, to]);
+            let case = format!("{dimensions:?} seed {seed} items {items:?}");
+            cells.reset_solution(from, to);
+            let mut tour = Tour::new(
+                solvers()[OPTIMAL[0]].create(Box::new(rng), &Values::default()),
+                items.clone(),
+            );
+            let result = loop {
+                match tour.step(dimensions, &mut cells, from, to) {
+                    StepResult::Continue { .. } => {}
+                    result => break result,
+                }
+            };
+            assert_eq!(result, StepResult::Finished, "{case}");
+            for item in items {
+                assert!(
+                    cells.solution(item).result,
+                    "{case}: item {item} not visited"
+                );
+            }
+            let mut data = MazeData::new(dimensions, &cells, from, to);
+            data.solution = solution(&cells, from, to);
+            assert!(data.solution.is_some(), "{case}: not solved");
+            assert_eq!(data.validate(), Ok(()), "{case}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn binary_format_round_trips() {
+        for (dimensions, seed) in cases() {
+            let cells = generate("kruskal", dimensions, seed);
+            let (from, to) = random_cells(&mut Xorshift::new(seed), cells.len());
+            let case = format!("{dimensions:?} seed {seed} from {from} to {to}");
+            let mut data = MazeData::new(dimensions, &cells, from, to);
+            data.solution = solve("a-star", dimensions, &cells, from, to, seed);
+            let decoded = from_base64(&to_base64(&data)).expect("should decode");
+            assert_eq!(decoded.dimensions, data.dimensions, "{case}");
+            assert_eq!(decoded.walls, data.walls, "{case}");
+            assert_eq!((decoded.from, decoded.to), (from, to), "{case}");
+            assert_eq!(decoded.solution, data.solution, "{case}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn binary_format_rejects_invalid_bytes() {
+        let dimensions = (5, 4);
+        let cells = generate("kruskal", dimensions, 1);
+        let to = cells.len() - 1;
+        let mut data = MazeData::new(dimensions, &cells, 0, to);
+        data.solution = solve("a-star", dimensions, &cells, 0, to, 1);
+        let bytes = to_bytes(&data);
+        assert!(from_bytes(&bytes).is_ok());
+
+        let mut version = bytes.clone();
+        version[0] = 2;
+        assert_eq!(
+            from_bytes(&version).err().as_deref(),
+            Some("version 2 is not supported")
+        );
+        assert_eq!(from_bytes(&bytes[..20]).err().as_deref(), Some("too short"));
+        for length in [
+            &bytes[..bytes.len() - 1],
+            &[bytes.as_slice(), &[0]].concat(),
+        ] {
+            assert_eq!(
+                from_bytes(length).err().as_deref(),
+                Some("length does not match dimensions 5 by 4")
+            );
+        }
+
+        // The first move, from the top left cell, north out of the maze
+        let mut leaves = bytes.clone();
+        let first_move = 21 + cells.len().div_ceil(4);
+        leaves[first_move] &= !0b11;
+        leaves[first_move] |= (Direction::North as u8).trailing_zeros() as u8;
+        assert_eq!(
+            from_bytes(&leaves).err().as_deref(),
+            Some("solution leaves the maze")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn json_format_round_trips() {
+        for (dimensions, seed) in cases() {
+            let cells = generate("kruskal", dimensions, seed);
+            let (from, to) = random_cells(&mut Xorshift::new(seed), cells.len());
+            let case = format!("{dimensions:?} seed {seed} from {from} to {to}");
+            let mut data = MazeData::new(dimensions, &cells, from, to);
+            data.solution = solve("a-star", dimensions, &cells, from, to, seed);
+            data.notes
+                .insert(from, "start \"here\"\\\n\t\u{1}é 🧭".to_string());
+            let decoded = from_json(&to_json(&data)).expect("should decode");
+            assert_eq!(decoded.dimensions, data.dimensions, "{case}");
+            assert_eq!(decoded.walls, data.walls, "{case}");
+            assert_eq!((decoded.from, decoded.to), (from, to), "{case}");
+            assert_eq!(decoded.solution, data.solution, "{case}");
+            assert_eq!(decoded.notes, data.notes, "{case}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn json_format_rejects_invalid_mazes() {
+        let dimensions = (5, 4);
+        let cells = generate("kruskal", dimensions, 1);
+        let to = cells.len() - 1;
+        let mut data = MazeData::new(dimensions, &cells, 0, to);
+        data.solution = solve("a-star", dimensions, &cells, 0, to, 1);
+        let json = to_json(&data);
+        assert!(from_json(&json).is_ok());
+        let fingerprint = data.fingerprint();
+        let walls = format!(
+            "[{}]",
+            data.walls
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        let edited = |from: &str, to: &str| {
+            assert!(json.contains(from), "{from} not in {json}");
+            from_json(&json.replacen(from, to, 1)).err()
+        };
+        assert_eq!(from_json("{").err().as_deref(), Some("not valid JSON"));
+        assert_eq!(
+            from_json(&format!("{json},")).err().as_deref(),
+            Some("not valid JSON")
+        );
+        assert_eq!(from_json("[]").err().as_deref(), Some("not a JSON object"));
+        assert_eq!(
+            edited("\"wasm-maze\"", "\"maze\"").as_deref(),
+            Some("format is not \"wasm-maze\"")
+        );
+        assert_eq!(
+            edited("\"version\":1", "\"version\":2").as_deref(),
+            Some("version 2 is newer than supported")
+        );
+        assert_eq!(
+            edited("\"width\":5", "\"width\":-5").as_deref(),
+            Some("width is not a non-negative integer")
+        );
+        assert_eq!(
+            edited("\"width\":5", "\"width\":5.5").as_deref(),
+            Some("width is not a non-negative integer")
+        );
+        assert_eq!(
+            edited(&format!("\"walls\":{walls},"), "").as_deref(),
+            Some("walls are missing")
+        );
+        assert_eq!(
+            edited("\"walls\":[", "\"walls\":[256,").as_deref(),
+            Some("walls 256 are invalid")
+        );
+        assert_eq!(
+            edited(&format!("\"to\":{to}"), "\"to\":0").as_deref(),
+            Some("from and to cells are the same")
+        );
+        assert_eq!(
+            edited(&format!("\"to\":{to}"), "\"to\":20").as_deref(),
+            Some("to cell 20 is outside the maze")
+        );
+        assert_eq!(
+            edited("\"solution\":[0,", "\"solution\":[").as_deref(),
+            Some("solution does not lead from the from cell to the to cell")
+        );
+        assert_eq!(
+            edited(&fingerprint, "0000").as_deref(),
+            Some("walls do not match fingerprint 0000")
+        );
+        assert_eq!(
+            edited("}", ",\"notes\":[{\"cell\":20,\"text\":\"\"}]}").as_deref(),
+            Some("note cell 20 is outside the maze")
+        );
+    }
+}
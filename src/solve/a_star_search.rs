@@ -1,14 +1,16 @@
-use super::Solver;
+use super::{flag_path, Solver};
+use crate::direction::Neighbours;
 use crate::geometry::taxicab_distance;
-use crate::{Cell, Dimensions, DIRECTIONS};
+use crate::platform::{log, Level};
+use crate::step::StepResult;
+use crate::{Cells, Dimensions, Directions, DIRECTIONS};
 
 use std::collections::BinaryHeap;
-use std::marker::PhantomData;
 
 /// Trait for the heuristic used in `AStarSearch`.
 pub(crate) trait AStarSearchHeuristic {
     /// Calculate heuristic value.
-    fn heuristic(dimensions: Dimensions, from: usize, to: usize) -> usize;
+    fn heuristic(&self, dimensions: Dimensions, from: usize, to: usize) -> usize;
 }
 
 /// A type implementing `AStarSearchHeuristic` for the value zero.
@@ -20,7 +22,7 @@ pub(crate) struct Zero;
 
 impl AStarSearchHeuristic for Zero {
     /// Calculate heuristic value.
-    fn heuristic(_: Dimensions, _: usize, _: usize) -> usize {
+    fn heuristic(&self, _: Dimensions, _: usize, _: usize) -> usize {
         0
     }
 }
@@ -31,7 +33,7 @@ pub(crate) struct TaxicabDistance;
 
 impl AStarSearchHeuristic for TaxicabDistance {
     /// Calculate heuristic value.
-    fn heuristic(dimensions: Dimensions, from: usize, to: usize) -> usize {
+    fn heuristic(&self, dimensions: Dimensions, from: usize, to: usize) -> usize {
         taxicab_distance(dimensions, from, to)
     }
 }
@@ -44,12 +46,36 @@ impl AStarSearchHeuristic for TaxicabDistance {
 #[derive(Default)]
 pub(crate) struct AStarSearch<T: AStarSearchHeuristic> {
     initialised: bool,
-    phantom: PhantomData<T>,
+    heuristic: T,
+    // extra weight of the heuristic; above 0 fewer cells are visited, though the path found
+    // is then bounded-suboptimal, longer than the shortest by up to that fraction
+    epsilon: f64,
     // shortest distance so far for each cell
     distances: Vec<Option<usize>>,
+    // whether each cell has been visited; entries of visited cells remaining in the fringe
+    // are stale and are skipped. A visited cell later reached by a shorter distance, as with
+    // a heuristic which is not consistent, is reopened
+    closed: Vec<bool>,
     // fringe (or frontier) priority queue of the shortest distance
     // plus a heuristic estimate of the remaining distance for cells
     fringe: BinaryHeap<AStarSearchState>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl<T: AStarSearchHeuristic> AStarSearch<T> {
+    /// Create using heuristic.
+    pub(crate) fn new(heuristic: T) -> Self {
+        Self {
+            initialised: false,
+            heuristic,
+            epsilon: 0.0,
+            distances: Vec::new(),
+            closed: Vec::new(),
+            fringe: BinaryHeap::new(),
+            changed: Vec::new(),
+        }
+    }
 }
 
 impl<T: AStarSearchHeuristic> Solver for AStarSearch<T> {
@@ -57,74 +83,100 @@ impl<T: AStarSearchHeuristic> Solver for AStarSearch<T> {
     fn step(
         &mut self,
         dimensions: Dimensions,
-        cells: &mut Vec<Cell>,
+        cells: &mut Cells,
         from: usize,
         to: usize,
-    ) -> bool {
+    ) -> StepResult<'_> {
+        self.changed.clear();
         if !self.initialised {
             // start of the algorithm
-            web_sys::console::log_1(&"solve using A* search algorithm".into());
+            log(Level::Info, "solve using A* search algorithm");
 
             self.distances.resize(cells.len(), None);
             self.distances[from] = Some(0);
+            self.closed.resize(cells.len(), false);
             self.fringe.push(AStarSearchState {
-                cost: T::heuristic(dimensions, from, to),
+                cost: self.estimate(dimensions, from, to),
                 cell: from,
             });
 
             self.initialised = true;
         } else {
-            match self.fringe.pop() {
-                Some(AStarSearchState { cost: _, cell }) => {
+            // skip stale entries of cells already visited
+            let state =
+                std::iter::from_fn(|| self.fringe.pop()).find(|state| !self.closed[state.cell]);
+            match state {
+                Some(AStarSearchState { cost, cell }) => {
+                    log(
+                        Level::Trace,
+                        format_args!("visit cell {cell} at cost {cost}"),
+                    );
                     if cell == to {
                         // end of algorithm; flag path and reset data
-                        web_sys::console::log_1(&"solve is complete".into());
-
-                        let mut cell = to;
-                        while cell != from {
-                            cells[cell].solution.result = true;
-                            cell = cells[cell]
-                                .solution
-                                .previous
-                                .expect("should have previous cell");
-                        }
-
-                        self.initialised = false;
-                        self.distances.clear();
-                        self.fringe.clear();
-                        return false;
+                        log(Level::Info, "solve is complete");
+                        self.reset();
+                        return flag_path(cells, from, to);
                     }
 
-                    // housekeeping; remove all additional entries of cell from fringe
-                    self.fringe.retain(|state| state.cell != cell);
+                    self.closed[cell] = true;
 
-                    // accessible unvisited neighbours
+                    // accessible neighbours; those already reached are updated, and
+                    // visited again, if a shorter distance is found, as when the maze has loops
                     let neighbours = DIRECTIONS
                         .iter()
-                        .filter(|direction| !cells[cell].has_wall(**direction))
+                        .filter(|direction| !cells.has_wall(cell, **direction))
                         .filter_map(|direction| direction.neighbour(dimensions, cell))
-                        .filter(|neighbour| {
-                            *neighbour != from && cells[*neighbour].solution.previous.is_none()
-                        })
-                        .collect::<Vec<_>>();
+                        .filter(|neighbour| *neighbour != from)
+                        .collect::<Neighbours>();
 
-                    for neighbour in neighbours {
+                    for neighbour in neighbours.iter().copied() {
                         let distance = self.distances[cell].unwrap() + 1; // move 1 additional cell
-                        if self.distances[neighbour].map_or(true, |val| distance < val) {
-                            cells[neighbour].solution.previous = Some(cell);
+                        if self.distances[neighbour].is_none_or(|val| distance < val) {
+                            cells.solution_mut(neighbour).previous = Some(cell);
+                            self.changed.push(neighbour);
                             self.distances[neighbour] = Some(distance);
+                            self.closed[neighbour] = false;
                             self.fringe.push(AStarSearchState {
-                                cost: distance + T::heuristic(dimensions, neighbour, to),
+                                cost: distance + self.estimate(dimensions, neighbour, to),
                                 cell: neighbour,
                             });
                         }
                     }
                 }
-                None => unreachable!(),
+                None => {
+                    // every cell reachable has been visited
+                    log(Level::Info, "solve failed");
+                    self.reset();
+                    return StepResult::Failed(format!("no path from cell {from} to cell {to}"));
+                }
             }
         }
 
-        true
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.initialised = false;
+        self.distances.clear();
+        self.closed.clear();
+        self.fringe.clear();
+    }
+}
+
+impl<T: AStarSearchHeuristic> AStarSearch<T> {
+    /// Set the extra weight of the heuristic, 0 or more; 0 for the shortest path, otherwise a
+    /// path at most that fraction longer.
+    pub(crate) fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon.max(0.0);
+        self
+    }
+
+    /// Heuristic value of cell, with its extra weight.
+    fn estimate(&self, dimensions: Dimensions, cell: usize, to: usize) -> usize {
+        let heuristic = self.heuristic.heuristic(dimensions, cell, to);
+        heuristic + (heuristic as f64 * self.epsilon).round() as usize
     }
 }
 
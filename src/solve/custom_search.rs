@@ -0,0 +1,293 @@
+use super::{flag_path, AStarSearchHeuristic, Solver};
+
+use crate::geometry::row_and_col;
+use crate::platform::{log, Level};
+use crate::registry::slug;
+use crate::step::StepResult;
+use crate::{Cells, Dimensions, Directions, DIRECTIONS};
+
+use js_sys::{Function, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+thread_local! {
+    // step functions of the solvers registered from JavaScript, by name
+    static CUSTOM_SOLVERS: RefCell<BTreeMap<String, Function>> = RefCell::default();
+    // heuristic function registered from JavaScript; if None then not registered
+    static CUSTOM_HEURISTIC: RefCell<Option<Function>> = RefCell::default();
+}
+
+// Ids of the solvers using the heuristic registered from JavaScript
+pub(crate) const CUSTOM_A_STAR_ID: &str = "custom-a-star";
+pub(crate) const CUSTOM_FRINGE_ID: &str = "custom-fringe";
+
+// Name of the event dispatched on the window when a solver is registered
+pub(crate) const SOLVERS_CHANGED_EVENT: &str = "wasm-maze-solvers";
+
+/// Register a solver written in JavaScript with name, replacing any of the same name. The
+/// id of the solver is `custom-` then the words of the name in lower case joined by `-`,
+/// which must differ from the ids of the other solvers.
+///
+/// The step function is called as `step(maze, agent)` for each step. `maze` is a read-only
+/// object of the `width`, `height`, `from` and `to` cells and the `walls` of each cell, as
+/// returned by `Maze.walls`. `agent` is an object kept between steps, with the current
+/// `cell`, `row` and `col`, to which any other state may be added. The function returns
+/// the direction to move, one of "north", "east", "south" or "west", or `null` to give up.
+/// The move is checked against the walls, and the solution is the path by which each cell
+/// was first reached.
+#[wasm_bindgen]
+pub fn register_solver(name: &str, step: Function) -> Result<(), JsValue> {
+    if slug(name).is_empty() {
+        return Err("solver name must have a letter or digit".into());
+    }
+    let id = custom_solver_id(name);
+    if [CUSTOM_A_STAR_ID, CUSTOM_FRINGE_ID].contains(&id.as_str()) {
+        return Err(format!("solver name {name:?} has the id of a built-in solver").into());
+    }
+    CUSTOM_SOLVERS.with_borrow_mut(|solvers| {
+        if let Some(other) = solvers
+            .keys()
+            .find(|other| *other != name && custom_solver_id(other) == id)
+        {
+            return Err(format!("solver name {name:?} has the same id as {other:?}"));
+        }
+        solvers.insert(name.to_string(), step);
+        Ok(())
+    })?;
+    solvers_changed()
+}
+
+/// Set the heuristic used by the A* algorithm with custom heuristic, or remove it and
+/// that solver if not given.
+///
+/// The heuristic function is called as `heuristic(fromRow, fromCol, toRow, toCol)` and
+/// returns an estimate of the number of moves between the cells. The estimate is rounded
+/// to a whole number, and anything not a non-negative number is taken as zero.
+#[wasm_bindgen]
+pub fn set_heuristic(heuristic: Option<Function>) -> Result<(), JsValue> {
+    CUSTOM_HEURISTIC.set(heuristic);
+    solvers_changed()
+}
+
+/// Notify that the available solvers have changed.
+fn solvers_changed() -> Result<(), JsValue> {
+    if let Some(window) = web_sys::window() {
+        window.dispatch_event(&web_sys::Event::new(SOLVERS_CHANGED_EVENT)?)?;
+    }
+    Ok(())
+}
+
+/// Id of the solver registered from JavaScript with name.
+pub(crate) fn custom_solver_id(name: &str) -> String {
+    format!("custom-{}", slug(name))
+}
+
+/// Names of the solvers registered from JavaScript.
+pub(crate) fn custom_solvers() -> Vec<String> {
+    CUSTOM_SOLVERS.with_borrow(|solvers| solvers.keys().cloned().collect())
+}
+
+/// A type implementing `AStarSearchHeuristic` by calling a function registered from
+/// JavaScript.
+pub(crate) struct CustomHeuristic {
+    function: Function,
+}
+
+impl CustomHeuristic {
+    /// Create for the registered heuristic. `None` if not registered.
+    pub(crate) fn new() -> Option<Self> {
+        CUSTOM_HEURISTIC
+            .with_borrow(|heuristic| heuristic.clone().map(|function| Self { function }))
+    }
+}
+
+impl AStarSearchHeuristic for CustomHeuristic {
+    /// Calculate heuristic value.
+    fn heuristic(&self, dimensions: Dimensions, from: usize, to: usize) -> usize {
+        let (from_row, from_col) = row_and_col(dimensions, from);
+        let (to_row, to_col) = row_and_col(dimensions, to);
+        let [from_row, from_col, to_row, to_col] =
+            [from_row, from_col, to_row, to_col].map(|value| JsValue::from_f64(value as f64));
+        match self
+            .function
+            .call4(&JsValue::NULL, &from_row, &from_col, &to_row, &to_col)
+        {
+            // casting saturates, with NaN as zero
+            Ok(value) => value.as_f64().unwrap_or_default().round() as usize,
+            Err(error) => {
+                web_sys::console::error_1(&format!("custom heuristic failed: {error:?}").into());
+                0
+            }
+        }
+    }
+}
+
+/// A type implementing a solver by calling a step function registered from JavaScript.
+pub(crate) struct CustomSearch {
+    // step function called with the maze and agent
+    step: Function,
+    // maze and agent objects passed to the step function, and current cell index;
+    // if None then start of the algorithm
+    state: Option<(Object, Object, usize)>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl CustomSearch {
+    /// Create for the solver registered with name. `None` if not registered.
+    pub(crate) fn new(name: &str) -> Option<Self> {
+        CUSTOM_SOLVERS.with_borrow(|solvers| {
+            solvers.get(name).map(|step| Self {
+                step: step.clone(),
+                state: None,
+                changed: Vec::new(),
+            })
+        })
+    }
+
+    /// Call the step function and check the move. Returns the neighbouring cell moved to,
+    /// `None` if the step function gave up, or a message if the move is not valid.
+    fn call(
+        &self,
+        dimensions: Dimensions,
+        cells: &Cells,
+        (maze, agent, cell): (&Object, &Object, usize),
+    ) -> Result<Option<usize>, String> {
+        let value = self
+            .step
+            .call2(&JsValue::NULL, maze, agent)
+            .map_err(|error| format!("step failed: {error:?}"))?;
+        if value.is_null() || value.is_undefined() {
+            return Ok(None);
+        }
+        let name = value.as_string().unwrap_or_default();
+        let direction = DIRECTIONS
+            .iter()
+            .find(|direction| direction.name() == name)
+            .ok_or_else(|| format!("step returned {value:?}, which is not a direction"))?;
+        if cells.has_wall(cell, *direction) {
+            return Err(format!("step moved {name} from cell {cell} through a wall"));
+        }
+        Ok(direction.neighbour(dimensions, cell))
+    }
+}
+
+/// Set properties of object.
+fn set(object: &Object, properties: &[(&str, JsValue)]) {
+    for (key, value) in properties {
+        let _ = Reflect::set(object, &JsValue::from_str(key), value);
+    }
+}
+
+/// Set the current cell of the agent.
+fn move_agent(dimensions: Dimensions, agent: &Object, cell: usize) {
+    let (row, col) = row_and_col(dimensions, cell);
+    set(
+        agent,
+        &[
+            ("cell", cell.into()),
+            ("row", row.into()),
+            ("col", col.into()),
+        ],
+    );
+}
+
+impl Solver for CustomSearch {
+    fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &mut Cells,
+        from: usize,
+        to: usize,
+    ) -> StepResult<'_> {
+        self.changed.clear();
+        match &self.state {
+            None => {
+                // start of the algorithm
+                log(Level::Info, "solve using custom solver");
+                let maze = Object::new();
+                set(
+                    &maze,
+                    &[
+                        ("width", dimensions.0.into()),
+                        ("height", dimensions.1.into()),
+                        ("from", from.into()),
+                        ("to", to.into()),
+                        (
+                            "walls",
+                            Uint8Array::from(cells.all_walls().as_slice()).into(),
+                        ),
+                    ],
+                );
+                let maze = Object::freeze(&maze);
+                let agent = Object::new();
+                move_agent(dimensions, &agent, from);
+                self.state = Some((maze, agent, from));
+            }
+            Some((_, _, cell)) if *cell == to => {
+                // end of algorithm; flag path and reset data
+                log(Level::Info, "solve is complete");
+                self.state = None;
+                return flag_path(cells, from, to);
+            }
+            Some((maze, agent, cell)) => match self.call(dimensions, cells, (maze, agent, *cell)) {
+                Ok(Some(neighbour)) => {
+                    if neighbour != from && cells.solution(neighbour).previous.is_none() {
+                        cells.solution_mut(neighbour).previous = Some(*cell);
+                        self.changed.push(neighbour);
+                    }
+                    move_agent(dimensions, agent, neighbour);
+                    self.state = Some((maze.clone(), agent.clone(), neighbour));
+                }
+                Ok(None) => {
+                    log(Level::Info, "solve gave up");
+                    self.state = None;
+                    return StepResult::Failed("the custom solver gave up".to_string());
+                }
+                Err(message) => {
+                    web_sys::console::error_1(&format!("custom solver: {message}").into());
+                    self.state = None;
+                    return StepResult::Failed(format!("custom solver: {message}"));
+                }
+            },
+        }
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// A type implementing a solver which fails at once, created in place of a solver whose
+/// function from JavaScript was removed after the solvers were listed.
+pub(crate) struct Unregistered {
+    // what is no longer registered
+    what: &'static str,
+}
+
+impl Unregistered {
+    /// Create for what is no longer registered.
+    pub(crate) fn new(what: &'static str) -> Self {
+        Self { what }
+    }
+}
+
+impl Solver for Unregistered {
+    fn step(
+        &mut self,
+        _dimensions: Dimensions,
+        _cells: &mut Cells,
+        _from: usize,
+        _to: usize,
+    ) -> StepResult<'_> {
+        StepResult::Failed(format!("{} is no longer registered", self.what))
+    }
+
+    fn reset(&mut self) {}
+}
@@ -0,0 +1,126 @@
+use super::{flag_path, Solver};
+use crate::{Cells, Dimensions, Direction, Directions};
+
+use crate::platform::{log, Level};
+use crate::step::StepResult;
+
+/// Name and id of the solver, whose order of directions may be configured.
+pub(crate) const DEPTH_FIRST_SEARCH: &str = "Depth first search algorithm (fixed direction order)";
+pub(crate) const DEPTH_FIRST_SEARCH_ID: &str = "depth-first";
+
+/// Order of directions tried unless configured.
+pub(crate) const DEFAULT_ORDER: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+/// A type implementing a [depth first search](https://en.wikipedia.org/wiki/Depth-first_search)
+/// algorithm to solve a maze, trying neighbours in a fixed order of directions so that each
+/// run is the same and the bias of the order is seen.
+pub(crate) struct DepthFirstSearch {
+    // directions in the order their neighbours are tried
+    order: [Direction; 4],
+    initialised: bool,
+    // stack of cell indexes
+    stack: Vec<usize>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Default for DepthFirstSearch {
+    fn default() -> Self {
+        Self::new(DEFAULT_ORDER)
+    }
+}
+
+impl DepthFirstSearch {
+    /// Create trying neighbours in the order of directions.
+    pub(crate) fn new(order: [Direction; 4]) -> Self {
+        Self {
+            order,
+            initialised: false,
+            stack: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+}
+
+impl Solver for DepthFirstSearch {
+    /// Apply a step of the algorithm.
+    fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &mut Cells,
+        from: usize,
+        to: usize,
+    ) -> StepResult<'_> {
+        self.changed.clear();
+        if !self.initialised {
+            // start of the algorithm
+            log(
+                Level::Info,
+                format_args!(
+                    "solve using depth first search algorithm, in the order {}",
+                    self.order
+                        .map(|direction| direction.letter())
+                        .iter()
+                        .collect::<String>()
+                ),
+            );
+            self.initialised = true;
+            self.stack.push(from);
+        } else {
+            // loop used to backtrack search path in one step
+            loop {
+                match self.stack.pop() {
+                    None => {
+                        // every cell reachable has been visited
+                        log(Level::Info, "solve failed");
+                        self.initialised = false;
+                        return StepResult::Failed(format!(
+                            "no path from cell {from} to cell {to}"
+                        ));
+                    }
+                    Some(cell) => {
+                        if cell == to {
+                            // end of algorithm; flag path and reset data
+                            log(Level::Info, "solve is complete");
+                            self.initialised = false;
+                            self.stack.clear();
+                            return flag_path(cells, from, to);
+                        }
+
+                        // first accessible unvisited neighbour in order
+                        let neighbour = self
+                            .order
+                            .iter()
+                            .filter(|direction| !cells.has_wall(cell, **direction))
+                            .filter_map(|direction| direction.neighbour(dimensions, cell))
+                            .find(|neighbour| {
+                                *neighbour != from && cells.solution(*neighbour).previous.is_none()
+                            });
+
+                        if let Some(neighbour) = neighbour {
+                            cells.solution_mut(neighbour).previous = Some(cell);
+                            self.changed.push(neighbour);
+                            self.stack.push(cell);
+                            self.stack.push(neighbour);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.initialised = false;
+        self.stack.clear();
+    }
+}
@@ -0,0 +1,158 @@
+use super::{flag_path, AStarSearchHeuristic, Solver};
+use crate::direction::Neighbours;
+use crate::platform::{log, Level};
+use crate::step::StepResult;
+use crate::{Cells, Dimensions, Directions, DIRECTIONS};
+
+/// A type implementing the [fringe search algorithm](https://en.wikipedia.org/wiki/Fringe_search)
+/// to solve a maze.
+///
+/// Like the A* search algorithm it visits cells by their distance plus a heuristic estimate
+/// of the remaining distance, but without a priority queue; cells within a threshold are
+/// visited depth first from the now list, and those beyond are left in the later list for
+/// when the threshold is raised to the least of them.
+#[derive(Default)]
+pub(crate) struct FringeSearch<T: AStarSearchHeuristic> {
+    initialised: bool,
+    heuristic: T,
+    // shortest distance so far for each cell
+    distances: Vec<Option<usize>>,
+    // distance at which each cell was visited; entries of cells already visited at their
+    // shortest distance are stale and are skipped
+    visited: Vec<Option<usize>>,
+    // cells to visit within the threshold, the last first
+    now: Vec<usize>,
+    // cells beyond the threshold, to visit once it is raised
+    later: Vec<usize>,
+    // most distance plus heuristic estimate of the cells visited now
+    threshold: usize,
+    // least distance plus heuristic estimate of the cells left until later
+    next_threshold: Option<usize>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl<T: AStarSearchHeuristic> FringeSearch<T> {
+    /// Create using heuristic.
+    #[cfg(feature = "wasm")]
+    pub(crate) fn new(heuristic: T) -> Self {
+        Self {
+            initialised: false,
+            heuristic,
+            distances: Vec::new(),
+            visited: Vec::new(),
+            now: Vec::new(),
+            later: Vec::new(),
+            threshold: 0,
+            next_threshold: None,
+            changed: Vec::new(),
+        }
+    }
+}
+
+impl<T: AStarSearchHeuristic> Solver for FringeSearch<T> {
+    /// Apply a step of the algorithm.
+    fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &mut Cells,
+        from: usize,
+        to: usize,
+    ) -> StepResult<'_> {
+        self.changed.clear();
+        if !self.initialised {
+            // start of the algorithm
+            log(Level::Info, "solve using fringe search algorithm");
+
+            self.distances.resize(cells.len(), None);
+            self.distances[from] = Some(0);
+            self.visited.resize(cells.len(), None);
+            self.now.push(from);
+            self.threshold = self.heuristic.heuristic(dimensions, from, to);
+
+            self.initialised = true;
+        } else {
+            // loop used to pass over cells beyond the threshold in one step
+            loop {
+                let Some(cell) = self.now.pop() else {
+                    match self.next_threshold.take() {
+                        Some(threshold) => {
+                            // raise the threshold and visit the cells left until later
+                            log(Level::Debug, format_args!("raise threshold to {threshold}"));
+                            self.threshold = threshold;
+                            std::mem::swap(&mut self.now, &mut self.later);
+                            self.now.reverse();
+                            continue;
+                        }
+                        None => {
+                            // every cell reachable has been visited
+                            log(Level::Info, "solve failed");
+                            self.reset();
+                            return StepResult::Failed(format!(
+                                "no path from cell {from} to cell {to}"
+                            ));
+                        }
+                    }
+                };
+
+                let distance = self.distances[cell].unwrap();
+                if self.visited[cell] == Some(distance) {
+                    continue;
+                }
+                let cost = distance + self.heuristic.heuristic(dimensions, cell, to);
+                if cost > self.threshold {
+                    self.later.push(cell);
+                    self.next_threshold =
+                        Some(self.next_threshold.map_or(cost, |next| next.min(cost)));
+                    continue;
+                }
+
+                log(
+                    Level::Trace,
+                    format_args!("visit cell {cell} at cost {cost}"),
+                );
+                if cell == to {
+                    // end of algorithm; flag path and reset data
+                    log(Level::Info, "solve is complete");
+                    self.reset();
+                    return flag_path(cells, from, to);
+                }
+
+                self.visited[cell] = Some(distance);
+
+                // accessible neighbours; those already reached are updated if a shorter
+                // distance is found, as when the maze has loops
+                let neighbours = DIRECTIONS
+                    .iter()
+                    .filter(|direction| !cells.has_wall(cell, **direction))
+                    .filter_map(|direction| direction.neighbour(dimensions, cell))
+                    .filter(|neighbour| *neighbour != from)
+                    .collect::<Neighbours>();
+
+                for neighbour in neighbours.iter().copied() {
+                    let distance = distance + 1; // move 1 additional cell
+                    if self.distances[neighbour].is_none_or(|val| distance < val) {
+                        cells.solution_mut(neighbour).previous = Some(cell);
+                        self.changed.push(neighbour);
+                        self.distances[neighbour] = Some(distance);
+                        self.now.push(neighbour);
+                    }
+                }
+                break;
+            }
+        }
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.initialised = false;
+        self.distances.clear();
+        self.visited.clear();
+        self.now.clear();
+        self.later.clear();
+        self.next_threshold = None;
+    }
+}
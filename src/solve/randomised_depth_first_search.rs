@@ -1,77 +1,94 @@
-use super::Solver;
-use crate::{Dimensions, DIRECTIONS};
+use super::{flag_path, Solver};
+use crate::direction::Neighbours;
+use crate::{Dimensions, Directions, DIRECTIONS};
 
-use js_sys::Math::random;
+use crate::platform::{log, Level};
+use crate::rng::Rng;
+use crate::step::StepResult;
 
 /// A type implementing a randomised [depth first search](https://en.wikipedia.org/wiki/Depth-first_search)
 /// algorithm to solve a maze.
-#[derive(Default)]
 pub(crate) struct RandomisedDepthFirstSearch {
+    rng: Box<dyn Rng>,
     initialised: bool,
     // stack of cell indexes
     stack: Vec<usize>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl RandomisedDepthFirstSearch {
+    /// Create using rng for the choice of neighbours.
+    pub(crate) fn new(rng: Box<dyn Rng>) -> Self {
+        Self {
+            rng,
+            initialised: false,
+            stack: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
 }
 
 impl Solver for RandomisedDepthFirstSearch {
     fn step(
         &mut self,
         dimensions: Dimensions,
-        cells: &mut Vec<crate::Cell>,
+        cells: &mut crate::Cells,
         from: usize,
         to: usize,
-    ) -> bool {
+    ) -> StepResult<'_> {
+        self.changed.clear();
         if !self.initialised {
             // start of the algorithm
-            web_sys::console::log_1(&"solve using randomised depth first search algorithm".into());
+            log(
+                Level::Info,
+                "solve using randomised depth first search algorithm",
+            );
             self.initialised = true;
+            self.stack.push(from);
         } else {
             // loop used to backtrack search path in one step
             loop {
                 match self.stack.pop() {
                     None => {
-                        // reset stack; applies if first search or previous search exhausted
-                        self.stack.push(from);
+                        // every cell reachable has been visited
+                        log(Level::Info, "solve failed");
+                        self.initialised = false;
+                        return StepResult::Failed(format!(
+                            "no path from cell {from} to cell {to}"
+                        ));
                     }
                     Some(cell) => {
                         if cell == to {
                             // end of algorithm; flag path and reset data
-                            web_sys::console::log_1(&"solve is complete".into());
-
-                            let mut cell = to;
-                            while cell != from {
-                                cells[cell].solution.result = true;
-                                cell = cells[cell]
-                                    .solution
-                                    .previous
-                                    .expect("should have previous cell");
-                            }
-
+                            log(Level::Info, "solve is complete");
                             self.initialised = false;
                             self.stack.clear();
-                            return false;
+                            return flag_path(cells, from, to);
                         }
 
                         let neighbour = {
                             // accessible unvisited neighbours
                             let neighbours = DIRECTIONS
                                 .iter()
-                                .filter(|direction| !cells[cell].has_wall(**direction))
+                                .filter(|direction| !cells.has_wall(cell, **direction))
                                 .filter_map(|direction| direction.neighbour(dimensions, cell))
                                 .filter(|neighbour| {
                                     *neighbour != from
-                                        && cells[*neighbour].solution.previous.is_none()
+                                        && cells.solution(*neighbour).previous.is_none()
                                 })
-                                .collect::<Vec<_>>();
+                                .collect::<Neighbours>();
 
                             // pick neighbour (if any) at random
                             match neighbours.len() {
                                 0 => None,
-                                len => Some(neighbours[(random() * len as f64) as usize]),
+                                len => Some(neighbours[self.rng.index(len)]),
                             }
                         };
 
                         if let Some(neighbour) = neighbour {
-                            cells[neighbour].solution.previous = Some(cell);
+                            cells.solution_mut(neighbour).previous = Some(cell);
+                            self.changed.push(neighbour);
                             self.stack.push(cell);
                             self.stack.push(neighbour);
                             break;
@@ -81,6 +98,13 @@ impl Solver for RandomisedDepthFirstSearch {
             }
         }
 
-        true
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.initialised = false;
+        self.stack.clear();
     }
 }
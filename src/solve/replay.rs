@@ -0,0 +1,49 @@
+use super::Solver;
+use crate::step::StepResult;
+use crate::{CellSolution, Cells, Dimensions};
+
+/// A type implementing `Solver` by replaying the recorded steps of another solver.
+pub(crate) struct Replay {
+    // per step, cell indexes and their solutions after the step
+    steps: Vec<Vec<(usize, CellSolution)>>,
+    // index of the next step to apply
+    step: usize,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Replay {
+    /// Create a `Replay` of steps.
+    pub(crate) fn new(steps: Vec<Vec<(usize, CellSolution)>>) -> Self {
+        Self {
+            steps,
+            step: 0,
+            changed: Vec::new(),
+        }
+    }
+}
+
+impl Solver for Replay {
+    /// Apply a step of the recording.
+    fn step(&mut self, _: Dimensions, cells: &mut Cells, _: usize, _: usize) -> StepResult<'_> {
+        self.changed.clear();
+        if let Some(changes) = self.steps.get(self.step) {
+            for (idx, solution) in changes {
+                cells.set_solution(*idx, *solution);
+                self.changed.push(*idx);
+            }
+            self.step += 1;
+        }
+
+        match self.step < self.steps.len() {
+            true => StepResult::Continue {
+                changed: &self.changed,
+            },
+            false => StepResult::Finished,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.step = 0;
+    }
+}
@@ -0,0 +1,107 @@
+use super::{flag_path, Solver};
+use crate::movement::Movement;
+use crate::platform::{log, Level};
+use crate::step::StepResult;
+use crate::{Cells, Dimensions, Direction, Directions, DIRECTIONS};
+
+use std::collections::VecDeque;
+
+/// A type implementing a [breadth first search](https://en.wikipedia.org/wiki/Breadth-first_search)
+/// to solve a maze whose floor is ice, so that each move slides on until the next wall or
+/// the goal. The path found has the fewest slides, which need not be the fewest cells.
+#[derive(Default)]
+pub(crate) struct SlidingSearch {
+    initialised: bool,
+    // cell and direction of the slide first reaching each cell which a slide stops at
+    origins: Vec<Option<(usize, Direction)>>,
+    // queue of the cells stopped at, not yet visited
+    queue: VecDeque<usize>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Solver for SlidingSearch {
+    /// Apply a step of the algorithm.
+    fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &mut Cells,
+        from: usize,
+        to: usize,
+    ) -> StepResult<'_> {
+        self.changed.clear();
+        if !self.initialised {
+            // start of the algorithm
+            log(Level::Info, "solve using sliding breadth first search");
+            self.origins.resize(cells.len(), None);
+            self.queue.push_back(from);
+            self.initialised = true;
+        } else {
+            match self.queue.pop_front() {
+                Some(cell) => {
+                    log(Level::Trace, format_args!("visit cell {cell}"));
+                    if cell == to {
+                        // end of algorithm; flag path along the slides and reset data
+                        log(Level::Info, "solve is complete");
+                        self.link_slides(dimensions, cells, from, to);
+                        self.reset();
+                        return flag_path(cells, from, to);
+                    }
+
+                    for direction in DIRECTIONS {
+                        let Some(destination) =
+                            Movement::Slide.destination(dimensions, cells, cell, *direction, to)
+                        else {
+                            continue;
+                        };
+                        if destination != from && self.origins[destination].is_none() {
+                            self.origins[destination] = Some((cell, *direction));
+                            // shown as a straight line back to the start of the slide
+                            cells.solution_mut(destination).previous = Some(cell);
+                            self.changed.push(destination);
+                            self.queue.push_back(destination);
+                        }
+                    }
+                }
+                None => {
+                    // every cell a slide stops at has been visited
+                    log(Level::Info, "solve failed");
+                    self.reset();
+                    return StepResult::Failed(format!("no slides from cell {from} to cell {to}"));
+                }
+            }
+        }
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.initialised = false;
+        self.origins.clear();
+        self.queue.clear();
+    }
+}
+
+impl SlidingSearch {
+    /// Link each cell of the slides from to to with the previous cell it slid over, so that
+    /// the path is of neighbouring cells. Links are made backward from to, so that a cell
+    /// slid over more than once keeps its earliest link and the path stays unbroken.
+    fn link_slides(&self, dimensions: Dimensions, cells: &mut Cells, from: usize, to: usize) {
+        let mut stop = to;
+        while let Some((start, direction)) = self.origins[stop] {
+            let mut cell = stop;
+            while cell != start {
+                let previous = direction
+                    .opposite()
+                    .neighbour(dimensions, cell)
+                    .expect("should slide within the maze");
+                cells.solution_mut(cell).previous = Some(previous);
+                cell = previous;
+            }
+            stop = start;
+        }
+        cells.solution_mut(from).previous = None;
+    }
+}
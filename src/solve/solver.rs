@@ -1,12 +1,291 @@
-use crate::{Cell, Dimensions};
+use crate::parameter::{Control, Parameter, Values};
+use crate::registry::{Category, Registered};
+use crate::rng::Rng;
+#[cfg(feature = "wasm")]
+use crate::solve::{custom_solver_id, custom_solvers, CustomHeuristic, CustomSearch};
+use crate::solve::{AStarSearch, DepthFirstSearch, FringeSearch, Left, RandomisedDepthFirstSearch};
+use crate::solve::{Right, SlidingSearch, TaxicabDistance, WallFollowerAutoSearch};
+use crate::solve::{
+    StepLimit, WallFollowerSearch, Zero, DEPTH_FIRST_SEARCH, DEPTH_FIRST_SEARCH_ID,
+};
+#[cfg(feature = "wasm")]
+use crate::solve::{Unregistered, CUSTOM_A_STAR_ID, CUSTOM_FRINGE_ID};
+use crate::step::StepResult;
+use crate::{Cells, Dimensions, Direction};
+
+use std::collections::BTreeMap;
 
 pub(crate) trait Solver {
     /// Apply a step of the algorithm.
     fn step(
         &mut self,
         dimensions: Dimensions,
-        cells: &mut Vec<Cell>,
+        cells: &mut Cells,
         from: usize,
         to: usize,
-    ) -> bool;
+    ) -> StepResult<'_>;
+
+    /// Forget any solve in progress, so that the next step starts a new solve.
+    fn reset(&mut self);
+
+    /// Outcome of the last solve, if any worth reporting.
+    #[cfg(feature = "wasm")]
+    fn outcome(&self) -> Option<String> {
+        None
+    }
+
+    /// Paths of the legs of the last solve, each from one stop to the next. Empty unless
+    /// solved in legs.
+    #[cfg(feature = "wasm")]
+    fn legs(&self) -> Vec<Vec<usize>> {
+        Vec::new()
+    }
+}
+
+/// Flag the cells of the path found between cells, following the previous cell of each
+/// back from to. `Failed` if the path is broken.
+pub(crate) fn flag_path(cells: &mut Cells, from: usize, to: usize) -> StepResult<'static> {
+    let mut cell = to;
+    while cell != from {
+        cells.solution_mut(cell).result = true;
+        match cells.solution(cell).previous {
+            Some(previous) => cell = previous,
+            None => return StepResult::Failed(format!("cell {cell} has no previous cell")),
+        }
+    }
+    StepResult::Finished
+}
+
+/// Function creating a solver using a source of random numbers and the values of its
+/// parameters.
+type CreateSolver = Box<dyn Fn(Box<dyn Rng>, &Values) -> Box<dyn Solver>>;
+
+/// A type for a solver which can be created, with its display name, category and the
+/// parameters it declares.
+pub(crate) struct SolverKind {
+    name: String,
+    category: Category,
+    parameters: &'static [Parameter],
+    create: CreateSolver,
+}
+
+impl SolverKind {
+    /// Create with display name and category from a function creating the solver using a
+    /// source of random numbers.
+    pub(crate) fn new(
+        name: impl Into<String>,
+        category: Category,
+        create: impl Fn(Box<dyn Rng>, &Values) -> Box<dyn Solver> + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            category,
+            parameters: &STEP_LIMIT_PARAMETERS,
+            create: Box::new(create),
+        }
+    }
+
+    /// Set the parameters, whose values are given to the function creating the solver.
+    fn with_parameters(mut self, parameters: &'static [Parameter]) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Parameters declared by the solver.
+    pub(crate) fn parameters(&self) -> &'static [Parameter] {
+        self.parameters
+    }
+
+    /// Create the solver using rng, with the values chosen for its parameters; defaults for
+    /// any not chosen. A solver given a step limit gives up on reaching it.
+    pub(crate) fn create(&self, rng: Box<dyn Rng>, chosen: &Values) -> Box<dyn Solver> {
+        let values = Values::of(self.parameters, chosen);
+        let solver = (self.create)(rng, &values);
+        match values.number(MAX_STEPS) as usize {
+            0 => solver,
+            max_steps => Box::new(StepLimit::new(solver, max_steps)),
+        }
+    }
+}
+
+impl Registered for SolverKind {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn category(&self) -> Category {
+        self.category
+    }
+}
+
+/// Solvers by id.
+pub(crate) type Solvers = BTreeMap<String, SolverKind>;
+
+// Keys of the steps after which a solver gives up, of the extra weight of the heuristic of
+// the A* search and of the direction order of the depth first search
+pub(crate) const MAX_STEPS: &str = "max-steps";
+const EPSILON: &str = "epsilon";
+pub(crate) const ORDER: &str = "order";
+
+// Steps after which a solver gives up, declared by every solver; if 0 then no limit
+const MAX_STEPS_PARAMETER: Parameter = Parameter {
+    key: MAX_STEPS,
+    label: "steps before giving up (0 for no limit)",
+    control: Control::Number {
+        minimum: 0.0,
+        maximum: u32::MAX as f64,
+        step: 1.0,
+    },
+    default: 0.0,
+};
+
+// Parameters of the solvers declaring only the step limit
+const STEP_LIMIT_PARAMETERS: [Parameter; 1] = [MAX_STEPS_PARAMETER];
+
+// Parameters of the A* search with a heuristic
+const A_STAR_PARAMETERS: [Parameter; 2] = [
+    Parameter {
+        key: EPSILON,
+        label: "extra weight of heuristic (0 for shortest path)",
+        control: Control::Number {
+            minimum: 0.0,
+            maximum: 4.0,
+            step: 0.25,
+        },
+        default: 0.0,
+    },
+    MAX_STEPS_PARAMETER,
+];
+
+// Parameters of the depth first search, whose default order is the first
+const DEPTH_FIRST_SEARCH_PARAMETERS: [Parameter; 2] = [
+    Parameter {
+        key: ORDER,
+        label: "direction order",
+        control: Control::Order,
+        default: 0.0,
+    },
+    MAX_STEPS_PARAMETER,
+];
+
+/// Available solvers by id, including those registered from JavaScript.
+pub(crate) fn solvers() -> Solvers {
+    let mut solvers = Solvers::new();
+    solvers.insert(
+        "a-star".to_string(),
+        SolverKind::new(
+            "A* algorithm (using Taxicab distance heuristic)",
+            Category::ShortestPath,
+            |_, values| {
+                Box::new(AStarSearch::new(TaxicabDistance).with_epsilon(values.number(EPSILON)))
+            },
+        )
+        .with_parameters(&A_STAR_PARAMETERS),
+    );
+    #[cfg(feature = "wasm")]
+    if CustomHeuristic::new().is_some() {
+        solvers.insert(
+            CUSTOM_A_STAR_ID.to_string(),
+            SolverKind::new(
+                "A* algorithm (using custom heuristic)",
+                Category::Custom,
+                |_, values| match CustomHeuristic::new() {
+                    Some(heuristic) => {
+                        Box::new(AStarSearch::new(heuristic).with_epsilon(values.number(EPSILON)))
+                    }
+                    None => Box::new(Unregistered::new("heuristic")),
+                },
+            )
+            .with_parameters(&A_STAR_PARAMETERS),
+        );
+        solvers.insert(
+            CUSTOM_FRINGE_ID.to_string(),
+            SolverKind::new(
+                "Fringe search algorithm (using custom heuristic)",
+                Category::Custom,
+                |_, _| match CustomHeuristic::new() {
+                    Some(heuristic) => Box::new(FringeSearch::new(heuristic)),
+                    None => Box::new(Unregistered::new("heuristic")),
+                },
+            ),
+        );
+    }
+    solvers.insert(
+        DEPTH_FIRST_SEARCH_ID.to_string(),
+        SolverKind::new(DEPTH_FIRST_SEARCH, Category::DepthFirst, |_, values| {
+            Box::new(DepthFirstSearch::new(Direction::order_at(
+                values.number(ORDER) as usize,
+            )))
+        })
+        .with_parameters(&DEPTH_FIRST_SEARCH_PARAMETERS),
+    );
+    solvers.insert(
+        "dijkstra".to_string(),
+        SolverKind::new(
+            "Dijkstra's algorithm (A* algorithm without heuristic)",
+            Category::ShortestPath,
+            |_, _| Box::<AStarSearch<Zero>>::default(),
+        ),
+    );
+    solvers.insert(
+        "fringe".to_string(),
+        SolverKind::new(
+            "Fringe search algorithm (using Taxicab distance heuristic)",
+            Category::ShortestPath,
+            |_, _| Box::<FringeSearch<TaxicabDistance>>::default(),
+        ),
+    );
+    solvers.insert(
+        "randomised-depth-first".to_string(),
+        SolverKind::new(
+            "Randomised depth first search algorithm",
+            Category::DepthFirst,
+            |rng, _| Box::new(RandomisedDepthFirstSearch::new(rng)),
+        ),
+    );
+    solvers.insert(
+        "sliding".to_string(),
+        SolverKind::new(
+            "Sliding breadth first search (ice floor)",
+            Category::Movement,
+            |_, _| Box::<SlidingSearch>::default(),
+        ),
+    );
+    solvers.insert(
+        "wall-follower-auto".to_string(),
+        SolverKind::new("Wall follower (auto)", Category::WallFollower, |_, _| {
+            Box::<WallFollowerAutoSearch>::default()
+        }),
+    );
+    solvers.insert(
+        "wall-follower-left".to_string(),
+        SolverKind::new(
+            "Wall follower (left turn)",
+            Category::WallFollower,
+            |_, _| Box::<WallFollowerSearch<Left>>::default(),
+        ),
+    );
+    solvers.insert(
+        "wall-follower-right".to_string(),
+        SolverKind::new(
+            "Wall follower (right turn)",
+            Category::WallFollower,
+            |_, _| Box::<WallFollowerSearch<Right>>::default(),
+        ),
+    );
+    #[cfg(feature = "wasm")]
+    for name in custom_solvers() {
+        solvers.insert(
+            custom_solver_id(&name),
+            SolverKind::new(
+                name.clone(),
+                Category::Custom,
+                move |_, _| match CustomSearch::new(&name) {
+                    Some(search) => Box::new(search),
+                    None => Box::new(Unregistered::new("solver")),
+                },
+            ),
+        );
+    }
+    solvers
 }
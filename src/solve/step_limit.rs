@@ -0,0 +1,78 @@
+use super::Solver;
+use crate::platform::{log, Level};
+use crate::step::StepResult;
+use crate::{Cells, Dimensions};
+
+/// A type implementing `Solver` by stepping another solver until it finishes or has taken a
+/// number of steps, when it gives up, leaving what it explored and resetting the solver so
+/// that the next step starts a new solve.
+pub(crate) struct StepLimit {
+    solver: Box<dyn Solver>,
+    // steps after which the solver gives up
+    max_steps: usize,
+    // steps taken in the current solve
+    steps: usize,
+}
+
+impl StepLimit {
+    /// Create stepping solver at most max_steps times each solve.
+    pub(crate) fn new(solver: Box<dyn Solver>, max_steps: usize) -> Self {
+        Self {
+            solver,
+            max_steps,
+            steps: 0,
+        }
+    }
+}
+
+impl Solver for StepLimit {
+    /// Apply a step of the solver, giving up once the limit is reached.
+    fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &mut Cells,
+        from: usize,
+        to: usize,
+    ) -> StepResult<'_> {
+        self.steps += 1;
+        if self.steps < self.max_steps {
+            let result = self.solver.step(dimensions, cells, from, to);
+            if !result.is_continue() {
+                self.steps = 0;
+            }
+            return result;
+        }
+        // the last step allowed, after which the solver gives up unless done
+        let done = match self.solver.step(dimensions, cells, from, to) {
+            StepResult::Continue { .. } => None,
+            StepResult::Finished => Some(StepResult::Finished),
+            StepResult::Failed(reason) => Some(StepResult::Failed(reason)),
+        };
+        match done {
+            Some(result) => {
+                self.steps = 0;
+                result
+            }
+            None => {
+                log(Level::Info, "solve gave up");
+                self.reset();
+                StepResult::Failed(format!("gave up after {} steps", self.max_steps))
+            }
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    fn outcome(&self) -> Option<String> {
+        self.solver.outcome()
+    }
+
+    #[cfg(feature = "wasm")]
+    fn legs(&self) -> Vec<Vec<usize>> {
+        self.solver.legs()
+    }
+
+    fn reset(&mut self) {
+        self.solver.reset();
+        self.steps = 0;
+    }
+}
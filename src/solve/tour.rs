@@ -0,0 +1,262 @@
+use super::Solver;
+use crate::path::{distances, solution};
+use crate::platform::{log, Level};
+use crate::rng::Rng;
+use crate::step::StepResult;
+use crate::{Cells, Dimensions};
+
+// Most items ordered exactly, with the Held-Karp algorithm; more are ordered nearest first
+const HELD_KARP_ITEMS: usize = 10;
+
+/// A type solving a maze through items which must all be visited before the goal, by
+/// ordering the items then solving from each stop to the next with another solver.
+pub(crate) struct Tour {
+    solver: Box<dyn Solver>,
+    items: Vec<usize>,
+    // whether the items are visited in their order, as waypoints, rather than ordered for
+    // the fewest moves
+    ordered: bool,
+    // cells stopped at in order; from, the items, then to; if empty then start of the
+    // algorithm
+    stops: Vec<usize>,
+    // index of the stop the current leg starts from
+    leg: usize,
+    // cells of the route so far, including from
+    route: Vec<usize>,
+    // paths of the legs solved so far, kept after the solve
+    legs: Vec<Vec<usize>>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Tour {
+    /// Create solving each leg with solver, visiting items.
+    pub(crate) fn new(solver: Box<dyn Solver>, items: Vec<usize>) -> Self {
+        Self {
+            solver,
+            items,
+            ordered: false,
+            stops: Vec::new(),
+            leg: 0,
+            route: Vec::new(),
+            legs: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Create solving each leg with solver, visiting waypoints in order.
+    pub(crate) fn through(solver: Box<dyn Solver>, waypoints: Vec<usize>) -> Self {
+        Self {
+            ordered: true,
+            ..Self::new(solver, waypoints)
+        }
+    }
+
+    /// Clear the solution details of cells then start the leg from stop index leg.
+    fn start_leg(&mut self, cells: &mut Cells, leg: usize) {
+        let (from, to) = (self.stops[leg], self.stops[leg + 1]);
+        // every cell with solution details is cleared, so is changed
+        self.changed.extend(cells.solutions.keys());
+        cells.reset_solution(from, to);
+        self.changed.extend([from, to]);
+        self.leg = leg;
+    }
+
+    /// Flag the route through every stop as the result, each cell linked to the cell it was
+    /// first reached from so that the path from from to to is unbroken.
+    fn flag_route(&mut self, cells: &mut Cells, from: usize, to: usize) {
+        self.changed.extend(cells.solutions.keys());
+        cells.reset_solution(from, to);
+        for pair in self.route.windows(2) {
+            if pair[1] != from && cells.solution(pair[1]).previous.is_none() {
+                cells.solution_mut(pair[1]).previous = Some(pair[0]);
+            }
+        }
+        for cell in &self.route {
+            cells.solution_mut(*cell).result = true;
+        }
+        self.changed.extend(&self.route);
+    }
+}
+
+impl Solver for Tour {
+    /// Apply a step of the algorithm.
+    fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &mut Cells,
+        from: usize,
+        to: usize,
+    ) -> StepResult<'_> {
+        self.changed.clear();
+        if self.stops.is_empty() {
+            // start of the algorithm
+            log(
+                Level::Info,
+                format_args!("solve visiting {} items", self.items.len()),
+            );
+            let order = match self.ordered {
+                true => Some(self.items.clone()),
+                false => visit_order(dimensions, cells, from, to, &self.items),
+            };
+            let Some(order) = order else {
+                log(Level::Info, "solve failed");
+                return StepResult::Failed("the items cannot all be reached".to_string());
+            };
+            self.stops = std::iter::once(from)
+                .chain(order)
+                .chain(std::iter::once(to))
+                .collect();
+            self.route.push(from);
+            self.legs.clear();
+            self.start_leg(cells, 0);
+        } else {
+            let (start, end) = (self.stops[self.leg], self.stops[self.leg + 1]);
+            match self.solver.step(dimensions, cells, start, end) {
+                StepResult::Continue { changed } => self.changed.extend_from_slice(changed),
+                StepResult::Finished => {
+                    let Some(path) = solution(cells, start, end) else {
+                        self.reset();
+                        return StepResult::Failed(format!("no path from cell {start} to {end}"));
+                    };
+                    self.route.extend(&path[1..]);
+                    self.legs.push(path);
+                    if self.leg + 2 == self.stops.len() {
+                        // end of algorithm; flag the whole route and reset data
+                        log(Level::Info, "solve is complete");
+                        self.flag_route(cells, from, to);
+                        self.reset();
+                        return StepResult::Finished;
+                    }
+                    self.start_leg(cells, self.leg + 1);
+                }
+                StepResult::Failed(message) => {
+                    self.reset();
+                    return StepResult::Failed(message);
+                }
+            }
+        }
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    /// Paths of the legs of the last solve, each from one stop to the next. Empty unless
+    /// solved in legs.
+    fn legs(&self) -> Vec<Vec<usize>> {
+        self.legs.clone()
+    }
+
+    fn reset(&mut self) {
+        self.stops.clear();
+        self.route.clear();
+        self.solver.reset();
+    }
+}
+
+/// Order in which to visit items between cells from and to, with the fewest moves in total
+/// for a few items and nearest first for more. `None` if any item cannot be reached.
+pub(crate) fn visit_order(
+    dimensions: Dimensions,
+    cells: &Cells,
+    from: usize,
+    to: usize,
+    items: &[usize],
+) -> Option<Vec<usize>> {
+    // moves between each pair of stops; from, the items, then to
+    let stops = std::iter::once(from)
+        .chain(items.iter().copied())
+        .chain(std::iter::once(to))
+        .collect::<Vec<_>>();
+    let moves = stops
+        .iter()
+        .map(|stop| {
+            let distances = distances(dimensions, cells, *stop);
+            stops.iter().map(|other| distances[*other]).collect()
+        })
+        .collect::<Option<Vec<Vec<_>>>>()?;
+
+    let order = match items.len() {
+        0 => Vec::new(),
+        count if count <= HELD_KARP_ITEMS => held_karp(&moves, count),
+        count => nearest_first(&moves, count),
+    };
+    Some(order.into_iter().map(|item| items[item]).collect())
+}
+
+/// Order of items, by index, with the fewest moves using the [Held-Karp algorithm](https://en.wikipedia.org/wiki/Held%E2%80%93Karp_algorithm);
+/// moves between stops are indexed by from, the items, then to.
+fn held_karp(moves: &[Vec<usize>], count: usize) -> Vec<usize> {
+    // fewest moves from from through each set of items, ending at each item of the set,
+    // and the item before it
+    let sets = 1 << count;
+    let mut best = vec![vec![(usize::MAX, None); count]; sets];
+    for item in 0..count {
+        best[1 << item][item] = (moves[0][item + 1], None);
+    }
+    for set in 1..sets {
+        for last in (0..count).filter(|last| set & 1 << last != 0) {
+            let (cost, _) = best[set][last];
+            if cost == usize::MAX {
+                continue;
+            }
+            for next in (0..count).filter(|next| set & 1 << next == 0) {
+                let cost = cost + moves[last + 1][next + 1];
+                let entry = &mut best[set | 1 << next][next];
+                if cost < entry.0 {
+                    *entry = (cost, Some(last));
+                }
+            }
+        }
+    }
+
+    let all = sets - 1;
+    let mut last = (0..count)
+        .min_by_key(|last| best[all][*last].0 + moves[last + 1][count + 1])
+        .expect("should have items");
+    let mut set = all;
+    let mut order = vec![last];
+    while let (_, Some(previous)) = best[set][last] {
+        set &= !(1 << last);
+        last = previous;
+        order.push(last);
+    }
+    order.reverse();
+    order
+}
+
+/// Order of items, by index, always moving to the nearest not yet visited; moves between
+/// stops are indexed by from, the items, then to.
+fn nearest_first(moves: &[Vec<usize>], count: usize) -> Vec<usize> {
+    let mut remaining = (0..count).collect::<Vec<_>>();
+    let mut order = Vec::with_capacity(count);
+    let mut stop = 0;
+    while let Some(idx) = (0..remaining.len()).min_by_key(|idx| moves[stop][remaining[*idx] + 1]) {
+        let item = remaining.remove(idx);
+        order.push(item);
+        stop = item + 1;
+    }
+    order
+}
+
+/// Count different cells chosen at random for items, other than the excluded cells; fewer if
+/// there are not enough cells.
+pub(crate) fn scatter(
+    rng: &mut dyn Rng,
+    len: usize,
+    count: usize,
+    excluded: &[usize],
+) -> Vec<usize> {
+    let mut candidates = (0..len)
+        .filter(|cell| !excluded.contains(cell))
+        .collect::<Vec<_>>();
+    let count = count.min(candidates.len());
+    // partial Fisher-Yates shuffle
+    for idx in 0..count {
+        let other = idx + rng.index(candidates.len() - idx);
+        candidates.swap(idx, other);
+    }
+    candidates.truncate(count);
+    candidates
+}
@@ -1,7 +1,9 @@
-use super::Solver;
+use super::{flag_path, Solver};
 
-use crate::direction::Direction;
-use crate::Dimensions;
+use crate::direction::{Direction, Directions, DIRECTIONS};
+use crate::platform::{log, Level};
+use crate::step::StepResult;
+use crate::{Cells, Dimensions};
 
 use std::marker::PhantomData;
 
@@ -52,56 +54,68 @@ pub(crate) struct WallFollowerSearch<T: WallFollowerSearchTurnDirection> {
     phantom: PhantomData<T>,
     // current cell index and direction; if None then start of the algorithm
     cell_and_direction: Option<(usize, Direction)>,
+    // number of moves made; the walk is in a loop without the goal once it has made more
+    // moves than there are cells and directions to move in
+    moves: usize,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
 }
 
 impl<T: WallFollowerSearchTurnDirection> Solver for WallFollowerSearch<T> {
     fn step(
         &mut self,
         dimensions: Dimensions,
-        cells: &mut Vec<crate::Cell>,
+        cells: &mut Cells,
         from: usize,
         to: usize,
-    ) -> bool {
+    ) -> StepResult<'_> {
+        self.changed.clear();
         // loop used to backtrack search path in one step
         loop {
             match self.cell_and_direction {
                 None => {
                     // start of the algorithm
-                    web_sys::console::log_1(&"solve using wall follower search algorithm".into());
-                    self.cell_and_direction = Some((from, Direction::First));
+                    log(Level::Info, "solve using wall follower search algorithm");
+                    self.cell_and_direction = Some((from, Direction::North));
+                    self.moves = 0;
                     break;
                 }
                 Some((cell, direction)) => {
                     if cell == to {
                         // end of algorithm; flag path and reset data
-                        web_sys::console::log_1(&"solve is complete".into());
-
-                        let mut cell = to;
-                        while cell != from {
-                            cells[cell].solution.result = true;
-                            cell = cells[cell]
-                                .solution
-                                .previous
-                                .expect("should have previous cell");
-                        }
-
+                        log(Level::Info, "solve is complete");
                         self.cell_and_direction = None;
-                        return false;
+                        return flag_path(cells, from, to);
                     }
 
-                    // neighbour depending on turn direction
-                    let mut direction = T::initial(direction);
-                    let neighbour = loop {
-                        if !cells[cell].has_wall(direction) {
-                            break direction
-                                .neighbour(dimensions, cell)
-                                .expect("should have neighbour");
-                        }
-                        direction = T::subsequent(direction);
+                    // neighbour depending on turn direction, turning at most once to each
+                    let Some((direction, neighbour)) =
+                        std::iter::successors(Some(T::initial(direction)), |direction| {
+                            Some(T::subsequent(*direction))
+                        })
+                        .take(DIRECTIONS.len())
+                        .find(|direction| !cells.has_wall(cell, *direction))
+                        .and_then(|direction| {
+                            Some((direction, direction.neighbour(dimensions, cell)?))
+                        })
+                    else {
+                        log(Level::Info, "solve failed");
+                        self.cell_and_direction = None;
+                        return StepResult::Failed(format!("cell {cell} is enclosed by walls"));
                     };
 
-                    let backtrack = if cells[neighbour].solution.previous.is_none() {
-                        cells[neighbour].solution.previous = Some(cell);
+                    self.moves += 1;
+                    if self.moves > DIRECTIONS.len() * cells.len() {
+                        log(Level::Info, "solve failed");
+                        self.cell_and_direction = None;
+                        return StepResult::Failed(format!(
+                            "no path from cell {from} to cell {to}"
+                        ));
+                    }
+
+                    let backtrack = if cells.solution(neighbour).previous.is_none() {
+                        cells.solution_mut(neighbour).previous = Some(cell);
+                        self.changed.push(neighbour);
                         false
                     } else {
                         true
@@ -116,6 +130,100 @@ impl<T: WallFollowerSearchTurnDirection> Solver for WallFollowerSearch<T> {
             }
         }
 
-        true
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.cell_and_direction = None;
+        self.moves = 0;
+    }
+}
+
+/// A type following the wall with both hands at once, each on its own copy of the maze,
+/// taking the path of whichever reaches the goal first. Cells explored by either hand are
+/// shown.
+#[derive(Default)]
+pub(crate) struct WallFollowerAutoSearch {
+    left: WallFollowerSearch<Left>,
+    right: WallFollowerSearch<Right>,
+    // copies of the maze followed by the left then right hand; if None then start of the
+    // algorithm
+    copies: Option<[Cells; 2]>,
+    // hand reaching the goal first; if None then not yet reached
+    winner: Option<&'static str>,
+    // cell indexes changed by the last step
+    changed: Vec<usize>,
+}
+
+impl Solver for WallFollowerAutoSearch {
+    /// Apply a step of the algorithm.
+    fn step(
+        &mut self,
+        dimensions: Dimensions,
+        cells: &mut Cells,
+        from: usize,
+        to: usize,
+    ) -> StepResult<'_> {
+        self.changed.clear();
+        let mut copies = self.copies.take().unwrap_or_else(|| {
+            // start of the algorithm
+            log(
+                Level::Info,
+                "solve using wall follower search algorithm with both hands",
+            );
+            [cells.clone(), cells.clone()]
+        });
+        self.winner = None;
+
+        let hands: [(&str, &mut dyn Solver); 2] =
+            [("left", &mut self.left), ("right", &mut self.right)];
+        for ((hand, solver), copy) in hands.into_iter().zip(&mut copies) {
+            match solver.step(dimensions, copy, from, to) {
+                StepResult::Continue { changed } => {
+                    // cells explored by either hand, as first reached
+                    for cell in changed {
+                        if cells.solution(*cell).previous.is_none() {
+                            cells.solution_mut(*cell).previous = copy.solution(*cell).previous;
+                            self.changed.push(*cell);
+                        }
+                    }
+                }
+                StepResult::Finished => {
+                    // end of algorithm; take the path of the winning hand
+                    log(
+                        Level::Info,
+                        format_args!("solve is complete; the {hand} hand reached the goal first"),
+                    );
+                    cells.solutions.clone_from(&copy.solutions);
+                    self.winner = Some(hand);
+                    return StepResult::Finished;
+                }
+                StepResult::Failed(reason) => {
+                    log(Level::Info, "solve failed");
+                    return StepResult::Failed(reason);
+                }
+            }
+        }
+        self.copies = Some(copies);
+
+        StepResult::Continue {
+            changed: &self.changed,
+        }
+    }
+
+    /// Outcome of the last solve, if any worth reporting.
+    #[cfg(feature = "wasm")]
+    fn outcome(&self) -> Option<String> {
+        self.winner
+            .map(|hand| format!("the {hand} hand reached the goal first"))
+    }
+
+    fn reset(&mut self) {
+        self.left.reset();
+        self.right.reset();
+        self.copies = None;
+        self.winner = None;
     }
 }
@@ -0,0 +1,84 @@
+use crate::geometry::taxicab_distance;
+use crate::Dimensions;
+
+use wasm_bindgen::prelude::*;
+use web_sys::{AudioContext, AudioContextState, GainNode, OscillatorNode, OscillatorType};
+
+// Frequencies in hertz of the lowest and highest notes, three octaves apart
+const LOWEST_HERTZ: f32 = 220.0;
+const HIGHEST_HERTZ: f32 = 1760.0;
+
+// Duration of each note in seconds
+const NOTE_SECONDS: f64 = 0.03;
+
+// Seconds ahead of the audio clock within which notes are scheduled; notes any later are
+// dropped so that the sound keeps up with the visualisation
+const LOOKAHEAD_SECONDS: f64 = 0.1;
+
+// Volume of notes, from 0 to 1
+const VOLUME: f32 = 0.1;
+
+/// A type for hearing an algorithm, scheduling a short note on an oscillator for each of
+/// its steps, pitched by its progress.
+pub(crate) struct Sonification {
+    context: AudioContext,
+    oscillator: OscillatorNode,
+    gain: GainNode,
+    // audio time at which the next note starts
+    next: f64,
+}
+
+impl Sonification {
+    /// Create, silent until a note. Browsers only start sound once the user has interacted
+    /// with the page.
+    pub(crate) fn new() -> Result<Self, JsValue> {
+        let context = AudioContext::new()?;
+        let oscillator = context.create_oscillator()?;
+        oscillator.set_type(OscillatorType::Triangle);
+        let gain = context.create_gain()?;
+        gain.gain().set_value(0.0);
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&context.destination())?;
+        oscillator.start()?;
+        Ok(Self {
+            context,
+            oscillator,
+            gain,
+            next: 0.0,
+        })
+    }
+
+    /// Schedule a note after those already scheduled, pitched by fraction from 0 for the
+    /// lowest to 1 for the highest; dropped if too far ahead.
+    pub(crate) fn note(&mut self, fraction: f64) {
+        if self.context.state() == AudioContextState::Suspended {
+            let _ = self.context.resume();
+        }
+        let now = self.context.current_time();
+        let start = self.next.max(now);
+        if start > now + LOOKAHEAD_SECONDS {
+            return;
+        }
+        // equal fractions are equal musical intervals
+        let hertz =
+            LOWEST_HERTZ * (HIGHEST_HERTZ / LOWEST_HERTZ).powf(fraction.clamp(0.0, 1.0) as f32);
+        self.next = start + NOTE_SECONDS;
+        // a following note replaces the silence at its start, so notes run together
+        let _ = self.oscillator.frequency().set_value_at_time(hertz, start);
+        let _ = self.gain.gain().set_value_at_time(VOLUME, start);
+        let _ = self.gain.gain().set_value_at_time(0.0, self.next);
+    }
+}
+
+impl Drop for Sonification {
+    fn drop(&mut self) {
+        let _ = self.context.close();
+    }
+}
+
+/// Pitch of cell, as a fraction from 0 to 1, by its distance from cell from; so that an
+/// algorithm meandering is heard wavering and one converging on the goal heard settling.
+pub(crate) fn distance_pitch(dimensions: Dimensions, from: usize, cell: usize) -> f64 {
+    let farthest = (dimensions.0 + dimensions.1).saturating_sub(2).max(1);
+    taxicab_distance(dimensions, from, cell) as f64 / farthest as f64
+}
@@ -0,0 +1,26 @@
+/// Result of applying a step of a generating or solving algorithm.
+#[derive(Debug, PartialEq)]
+pub(crate) enum StepResult<'a> {
+    /// More steps are needed; the cells changed by this step.
+    Continue { changed: &'a [usize] },
+    /// The algorithm is complete, with any last changes applied.
+    Finished,
+    /// The algorithm cannot complete, for the reason given.
+    Failed(String),
+}
+
+impl StepResult<'_> {
+    /// Whether more steps are needed.
+    pub(crate) fn is_continue(&self) -> bool {
+        matches!(self, Self::Continue { .. })
+    }
+
+    /// Whether more steps are needed, or the reason the algorithm failed.
+    pub(crate) fn continuing(self) -> Result<bool, String> {
+        match self {
+            Self::Continue { .. } => Ok(true),
+            Self::Finished => Ok(false),
+            Self::Failed(reason) => Err(reason),
+        }
+    }
+}
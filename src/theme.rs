@@ -0,0 +1,155 @@
+use crate::app::CELL_PIXELS;
+use crate::Dimensions;
+
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
+
+// Least and most width of the walls in pixels of the canvas
+pub(crate) const MINIMUM_WALL_WIDTH: f64 = 0.5;
+pub(crate) const MAXIMUM_WALL_WIDTH: f64 = CELL_PIXELS as f64 / 2.0;
+
+// Opacity of the backdrop, faint so that the maze is still clear over it
+const BACKDROP_OPACITY: f64 = 0.35;
+
+/// Shapes of the ends of lines.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+// Shapes of the ends of lines in order
+pub(crate) const LINE_CAPS: [LineCap; 3] = [LineCap::Butt, LineCap::Round, LineCap::Square];
+
+impl LineCap {
+    /// Name of this `LineCap`, as in the canvas.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Butt => "butt",
+            Self::Round => "round",
+            Self::Square => "square",
+        }
+    }
+
+    /// `LineCap` with name, ignoring case. `None` if unknown.
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        LINE_CAPS
+            .into_iter()
+            .find(|cap| cap.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Shapes of the corners where lines join.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+// Shapes of the corners where lines join in order
+pub(crate) const LINE_JOINS: [LineJoin; 3] = [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel];
+
+impl LineJoin {
+    /// Name of this `LineJoin`, as in the canvas.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Miter => "miter",
+            Self::Round => "round",
+            Self::Bevel => "bevel",
+        }
+    }
+
+    /// `LineJoin` with name, ignoring case. `None` if unknown.
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        LINE_JOINS
+            .into_iter()
+            .find(|join| join.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// A type for the style in which the maze is drawn; heavier walls suit projectors and
+/// lighter ones small prints.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Theme {
+    // width of the walls in pixels of the canvas, in which each cell is `CELL_PIXELS` wide
+    pub(crate) wall_width: f64,
+    // width of each passage as shown on the page in pixels; if 0 then that of the canvas,
+    // shrunk to fit
+    pub(crate) passage_width: u32,
+    pub(crate) line_cap: LineCap,
+    pub(crate) line_join: LineJoin,
+    // picture stretched over the maze and shown faintly within the carved cells, so that
+    // solving traces over it; if None then no picture
+    pub(crate) backdrop: Option<HtmlImageElement>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            wall_width: 2.0,
+            passage_width: 0,
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Miter,
+            backdrop: None,
+        }
+    }
+}
+
+impl Theme {
+    /// Set the width of the walls, within the least and most.
+    pub(crate) fn set_wall_width(&mut self, width: f64) {
+        if width.is_finite() {
+            self.wall_width = width.clamp(MINIMUM_WALL_WIDTH, MAXIMUM_WALL_WIDTH);
+        }
+    }
+
+    /// Apply the line style to context. The style is lost when its canvas is resized, so is
+    /// applied again then.
+    pub(crate) fn apply(&self, context: &CanvasRenderingContext2d) {
+        context.set_line_cap(self.line_cap.name());
+        context.set_line_join(self.line_join.name());
+    }
+
+    /// Size canvas on the page for the passages of dimensions.
+    pub(crate) fn fit(&self, canvas: &HtmlCanvasElement, dimensions: Dimensions) {
+        let style = canvas.style();
+        let _ = match self.passage_width {
+            0 => style.remove_property("width").map(|_| ()),
+            width => style.set_property("width", &format!("{}px", dimensions.0 * width as usize)),
+        };
+    }
+
+    /// Draw the part of the backdrop, if any, within the cell at row and col of a maze of
+    /// dimensions into context.
+    pub(crate) fn draw_backdrop(
+        &self,
+        context: &CanvasRenderingContext2d,
+        dimensions: Dimensions,
+        row: usize,
+        col: usize,
+    ) {
+        let Some(image) = &self.backdrop else {
+            return;
+        };
+        let (width, height) = (
+            image.natural_width() as f64 / dimensions.0 as f64,
+            image.natural_height() as f64 / dimensions.1 as f64,
+        );
+        let pixels = CELL_PIXELS as f64;
+        context.set_global_alpha(BACKDROP_OPACITY);
+        let _ = context
+            .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                image,
+                col as f64 * width,
+                row as f64 * height,
+                width,
+                height,
+                col as f64 * pixels,
+                row as f64 * pixels,
+                pixels,
+                pixels,
+            );
+        context.set_global_alpha(1.0);
+    }
+}
@@ -0,0 +1,587 @@
+// Helpers building the elements of the user interface.
+//
+// Every element is given a class and an id prefixed with `PREFIX` so the
+// interface can be styled from the hosting page; `STYLES` provides defaults.
+// Ids of instances after the first also include the number of the instance.
+
+use crate::parameter::{Control, Parameter, Values};
+
+use wasm_bindgen::prelude::*;
+use web_sys::{
+    Document, Element, HtmlAnchorElement, HtmlButtonElement, HtmlCanvasElement, HtmlElement,
+    HtmlImageElement, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement,
+};
+
+// Prefix of element classes and ids
+pub(crate) const PREFIX: &str = "wasm-maze";
+
+// Default styles
+const STYLES: &str = "
+.wasm-maze-app {
+    display: flex;
+    flex-wrap: wrap;
+    align-items: flex-start;
+    gap: 10px;
+}
+.wasm-maze-app:focus {
+    outline: none;
+}
+.wasm-maze-panels {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 10px;
+    max-width: 100%;
+}
+.wasm-maze-panel {
+    max-width: 100%;
+}
+.wasm-maze-canvas {
+    display: block;
+    max-width: 100%;
+    height: auto;
+}
+.wasm-maze-controls {
+    display: flex;
+    flex-direction: column;
+    gap: 5px;
+}
+.wasm-maze-fieldset {
+    border: 1px solid rgb(192,192,192);
+    border-radius: 4px;
+}
+.wasm-maze-row {
+    margin: 5px 0;
+}
+.wasm-maze-row > label {
+    margin: 0 5px;
+}
+.wasm-maze-gallery {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 5px;
+}
+.wasm-maze-thumbnail {
+    padding: 2px;
+    cursor: pointer;
+}
+.wasm-maze-thumbnail-canvas {
+    display: block;
+}
+.wasm-maze-textarea {
+    display: block;
+    width: 100%;
+    box-sizing: border-box;
+}
+.wasm-maze-message {
+    color: rgb(192,0,0);
+}
+.wasm-maze-banner {
+    flex-basis: 100%;
+    padding: 5px 10px;
+    border: 1px solid rgb(192,0,0);
+    border-radius: 4px;
+    color: rgb(192,0,0);
+    background: rgb(255,240,240);
+    cursor: pointer;
+}
+.wasm-maze-text {
+    margin: 5px 0;
+}
+.wasm-maze-legend {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 5px 10px;
+    margin: 5px 0;
+}
+.wasm-maze-legend-entry {
+    display: flex;
+    align-items: center;
+    gap: 4px;
+}
+.wasm-maze-legend-swatch {
+    border: 1px solid rgb(192,192,192);
+}
+.wasm-maze-best {
+    color: rgb(0,128,0);
+    font-weight: bold;
+}
+.wasm-maze-worksheet {
+    display: none;
+}
+.wasm-maze-worksheet-page {
+    text-align: center;
+    break-after: page;
+}
+.wasm-maze-worksheet-page:last-child {
+    break-after: auto;
+}
+.wasm-maze-worksheet-image {
+    max-width: 100%;
+    max-height: 85vh;
+}
+@media print {
+    .wasm-maze-printing * {
+        visibility: hidden;
+    }
+    .wasm-maze-printing .wasm-maze-worksheet:not([hidden]),
+    .wasm-maze-printing .wasm-maze-worksheet:not([hidden]) * {
+        visibility: visible;
+    }
+    .wasm-maze-printing .wasm-maze-worksheet:not([hidden]) {
+        display: block;
+        position: absolute;
+        top: 0;
+        left: 0;
+        width: 100%;
+    }
+}
+.wasm-maze-screen-reader {
+    position: absolute;
+    width: 1px;
+    height: 1px;
+    overflow: hidden;
+    clip: rect(0 0 0 0);
+    white-space: nowrap;
+}
+@media (max-width: 600px) {
+    .wasm-maze-app {
+        flex-direction: column;
+        align-items: stretch;
+    }
+    .wasm-maze-fieldset {
+        margin: 0;
+    }
+    .wasm-maze-button,
+    .wasm-maze-select,
+    .wasm-maze-number {
+        min-height: 44px;
+        font-size: 16px;
+    }
+    .wasm-maze-checkbox {
+        width: 24px;
+        height: 24px;
+    }
+    .wasm-maze-range {
+        width: 100%;
+    }
+}
+";
+
+/// Inject the default styles into the document head, unless already present.
+pub(crate) fn inject_styles(document: &Document) -> Result<(), JsValue> {
+    let id = format!("{PREFIX}-styles");
+    if document.get_element_by_id(&id).is_none() {
+        let style = document.create_element("style")?;
+        style.set_id(&id);
+        style.set_text_content(Some(STYLES));
+        document
+            .head()
+            .ok_or("should have document head")?
+            .append_child(&style)?;
+    }
+    Ok(())
+}
+
+/// A type for building the elements of an instance of the user interface in a document.
+pub(crate) struct Builder {
+    document: Document,
+    // prefix of element ids, unique to the instance
+    prefix: String,
+}
+
+impl Builder {
+    /// Create for a new instance in document. Ids of the first instance are prefixed with
+    /// `PREFIX`, and of any others also with the number of the instance.
+    pub(crate) fn new(document: &Document) -> Self {
+        let prefix = (1..)
+            .map(|instance| match instance {
+                1 => PREFIX.to_string(),
+                _ => format!("{PREFIX}-{instance}"),
+            })
+            .find(|prefix| {
+                document
+                    .get_element_by_id(&format!("{prefix}-app"))
+                    .is_none()
+            })
+            .expect("should have an unused prefix");
+        Self {
+            document: document.clone(),
+            prefix,
+        }
+    }
+
+    /// Whether this is the first instance.
+    pub(crate) fn is_first(&self) -> bool {
+        self.prefix == PREFIX
+    }
+
+    /// Id of the element of this instance with id.
+    fn id(&self, id: &str) -> String {
+        format!("{}-{id}", self.prefix)
+    }
+}
+
+/// Create element with class and id, appended to parent.
+pub(crate) fn element<T: JsCast>(
+    builder: &Builder,
+    parent: &Element,
+    tag: &str,
+    class: &str,
+    id: &str,
+) -> Result<T, JsValue> {
+    let element = builder.document.create_element(tag)?;
+    element.set_class_name(&format!("{PREFIX}-{class}"));
+    if !id.is_empty() {
+        element.set_id(&builder.id(id));
+    }
+    parent.append_child(&element)?;
+    Ok(element.dyn_into::<T>()?)
+}
+
+/// Create a container `div`.
+pub(crate) fn container(
+    builder: &Builder,
+    parent: &Element,
+    class: &str,
+    id: &str,
+) -> Result<HtmlElement, JsValue> {
+    element(builder, parent, "div", class, id)
+}
+
+/// Create a `fieldset` with a legend.
+pub(crate) fn fieldset(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    legend: &str,
+) -> Result<Element, JsValue> {
+    let fieldset = element::<Element>(builder, parent, "fieldset", "fieldset", id)?;
+    element::<Element>(builder, &fieldset, "legend", "legend", "")?.set_text_content(Some(legend));
+    Ok(fieldset)
+}
+
+/// Create a row for controls.
+pub(crate) fn row(builder: &Builder, parent: &Element) -> Result<Element, JsValue> {
+    element(builder, parent, "div", "row", "")
+}
+
+/// Create a `label` for the control with id.
+fn label(builder: &Builder, parent: &Element, id: &str, text: &str) -> Result<(), JsValue> {
+    let label = element::<Element>(builder, parent, "label", "label", "")?;
+    label.set_attribute("for", &builder.id(id))?;
+    label.set_text_content(Some(text));
+    Ok(())
+}
+
+/// Create an `input` of type number, followed by its label, in a new row.
+pub(crate) fn number(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    text: &str,
+    value: &str,
+    min: &str,
+) -> Result<HtmlInputElement, JsValue> {
+    let row = row(builder, parent)?;
+    let input = element::<HtmlInputElement>(builder, &row, "input", "number", id)?;
+    input.set_type("number");
+    input.set_min(min);
+    input.set_value(value);
+    label(builder, &row, id, text)?;
+    Ok(input)
+}
+
+/// Create an `input` of type text, followed by its label, in a new row.
+pub(crate) fn text_input(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    text: &str,
+) -> Result<HtmlInputElement, JsValue> {
+    let row = row(builder, parent)?;
+    let input = element::<HtmlInputElement>(builder, &row, "input", "text-input", id)?;
+    input.set_type("text");
+    label(builder, &row, id, text)?;
+    Ok(input)
+}
+
+/// Create an `input` of type file, followed by its label, in a new row.
+pub(crate) fn file_input(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    text: &str,
+    accept: &str,
+) -> Result<HtmlInputElement, JsValue> {
+    let row = row(builder, parent)?;
+    let input = element::<HtmlInputElement>(builder, &row, "input", "file-input", id)?;
+    input.set_type("file");
+    input.set_accept(accept);
+    label(builder, &row, id, text)?;
+    Ok(input)
+}
+
+/// Create an `input` of type checkbox, followed by its label, in a new row.
+pub(crate) fn checkbox(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    text: &str,
+    checked: bool,
+) -> Result<HtmlInputElement, JsValue> {
+    let row = row(builder, parent)?;
+    let input = element::<HtmlInputElement>(builder, &row, "input", "checkbox", id)?;
+    input.set_type("checkbox");
+    input.set_checked(checked);
+    label(builder, &row, id, text)?;
+    Ok(input)
+}
+
+/// Create an `input` of type range, labelled for assistive technologies.
+pub(crate) fn range(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    label: &str,
+) -> Result<HtmlInputElement, JsValue> {
+    let input = element::<HtmlInputElement>(builder, parent, "input", "range", id)?;
+    input.set_attribute("aria-label", label)?;
+    input.set_type("range");
+    input.set_min("0");
+    input.set_max("0");
+    Ok(input)
+}
+
+/// Create a `select`, labelled for assistive technologies.
+pub(crate) fn select(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    label: &str,
+) -> Result<HtmlSelectElement, JsValue> {
+    let select = element::<HtmlSelectElement>(builder, parent, "select", "select", id)?;
+    select.set_attribute("aria-label", label)?;
+    Ok(select)
+}
+
+/// Create a `textarea` in a new row, labelled for assistive technologies.
+pub(crate) fn textarea(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    label: &str,
+) -> Result<HtmlTextAreaElement, JsValue> {
+    let row = row(builder, parent)?;
+    let textarea = element::<HtmlTextAreaElement>(builder, &row, "textarea", "textarea", id)?;
+    textarea.set_attribute("aria-label", label)?;
+    textarea.set_rows(4);
+    Ok(textarea)
+}
+
+/// Create a `button`.
+pub(crate) fn button(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    text: &str,
+    disabled: bool,
+) -> Result<HtmlButtonElement, JsValue> {
+    let button = element::<HtmlButtonElement>(builder, parent, "button", "button", id)?;
+    button.set_text_content(Some(text));
+    button.set_disabled(disabled);
+    Ok(button)
+}
+
+/// Create a `button` showing a thumbnail drawn into its `canvas`, with a label for assistive
+/// technologies.
+pub(crate) fn thumbnail(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    label: &str,
+) -> Result<(HtmlButtonElement, HtmlCanvasElement), JsValue> {
+    let button = element::<HtmlButtonElement>(builder, parent, "button", "thumbnail", id)?;
+    button.set_attribute("aria-label", label)?;
+    let canvas = element::<HtmlCanvasElement>(builder, &button, "canvas", "thumbnail-canvas", "")?;
+    Ok((button, canvas))
+}
+
+/// Create a link `a` with text.
+pub(crate) fn link(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    text: &str,
+) -> Result<HtmlAnchorElement, JsValue> {
+    let link = element::<HtmlAnchorElement>(builder, parent, "a", "link", id)?;
+    link.set_text_content(Some(text));
+    Ok(link)
+}
+
+/// Create a `canvas`, described as an image for assistive technologies.
+pub(crate) fn canvas(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    label: &str,
+) -> Result<HtmlCanvasElement, JsValue> {
+    let canvas = element::<HtmlCanvasElement>(builder, parent, "canvas", "canvas", id)?;
+    canvas.set_attribute("role", "img")?;
+    canvas.set_attribute("aria-label", label)?;
+    Ok(canvas)
+}
+
+/// Create a `div` of a legend, with a swatch `canvas` before each label, hidden.
+pub(crate) fn legend(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    labels: &[&str],
+) -> Result<(HtmlElement, Vec<HtmlCanvasElement>), JsValue> {
+    let legend = element::<HtmlElement>(builder, parent, "div", "legend", id)?;
+    legend.set_hidden(true);
+    let swatches = labels
+        .iter()
+        .map(|label| {
+            let entry = element::<Element>(builder, &legend, "span", "legend-entry", "")?;
+            let swatch =
+                element::<HtmlCanvasElement>(builder, &entry, "canvas", "legend-swatch", "")?;
+            swatch.set_attribute("aria-hidden", "true")?;
+            entry.append_with_str_1(label)?;
+            Ok(swatch)
+        })
+        .collect::<Result<_, JsValue>>()?;
+    Ok((legend, swatches))
+}
+
+/// Create a `div` of the controls of parameters, hidden, set to the values chosen or else the
+/// defaults. Ids of the controls are id followed by the key of the parameter.
+pub(crate) fn parameters(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+    parameters: &[Parameter],
+    chosen: &Values,
+) -> Result<(HtmlElement, Vec<HtmlInputElement>), JsValue> {
+    let container = element::<HtmlElement>(builder, parent, "div", "parameters", id)?;
+    container.set_hidden(true);
+    let values = Values::of(parameters, chosen);
+    let inputs = parameters
+        .iter()
+        .map(|parameter| {
+            let id = format!("{id}-{}", parameter.key);
+            let value = values.number(parameter.key);
+            match parameter.control {
+                Control::Number {
+                    minimum,
+                    maximum,
+                    step,
+                } => {
+                    let input = number(
+                        builder,
+                        &container,
+                        &id,
+                        parameter.label,
+                        &parameter.format(value),
+                        &minimum.to_string(),
+                    )?;
+                    input.set_max(&maximum.to_string());
+                    input.set_step(&step.to_string());
+                    Ok(input)
+                }
+                Control::Checkbox => {
+                    checkbox(builder, &container, &id, parameter.label, value != 0.0)
+                }
+                Control::Order => {
+                    let input = text_input(builder, &container, &id, parameter.label)?;
+                    input.set_value(&parameter.format(value));
+                    Ok(input)
+                }
+            }
+        })
+        .collect::<Result<_, JsValue>>()?;
+    Ok((container, inputs))
+}
+
+/// Create a `div` for text.
+pub(crate) fn text(builder: &Builder, parent: &Element, id: &str) -> Result<Element, JsValue> {
+    element(builder, parent, "div", "text", id)
+}
+
+/// Create a `div` for text whose changes are announced by assistive technologies.
+pub(crate) fn status(builder: &Builder, parent: &Element, id: &str) -> Result<Element, JsValue> {
+    let status = text(builder, parent, id)?;
+    status.set_attribute("role", "status")?;
+    status.set_attribute("aria-live", "polite")?;
+    Ok(status)
+}
+
+/// Set whether the text of a `div` created by `text` is highlighted, as for a personal best.
+pub(crate) fn highlight(text: &Element, highlighted: bool) {
+    text.set_class_name(&match highlighted {
+        true => format!("{PREFIX}-text {PREFIX}-best"),
+        false => format!("{PREFIX}-text"),
+    });
+}
+
+/// Create a `div` for text only presented by assistive technologies.
+pub(crate) fn screen_reader(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+) -> Result<Element, JsValue> {
+    element(builder, parent, "div", "screen-reader", id)
+}
+
+/// Create a hidden `div` for alerting the user to errors.
+pub(crate) fn banner(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+) -> Result<HtmlElement, JsValue> {
+    let banner = element::<HtmlElement>(builder, parent, "div", "banner", id)?;
+    banner.set_attribute("role", "alert")?;
+    banner.set_title("dismiss");
+    banner.set_hidden(true);
+    Ok(banner)
+}
+
+/// Create a hidden `div` of pages for print, shown in place of the document when printing
+/// once unhidden.
+pub(crate) fn worksheet(
+    builder: &Builder,
+    parent: &Element,
+    id: &str,
+) -> Result<HtmlElement, JsValue> {
+    let worksheet = element::<HtmlElement>(builder, parent, "div", "worksheet", id)?;
+    worksheet.set_hidden(true);
+    Ok(worksheet)
+}
+
+/// Create a page of a worksheet, of a caption above an image.
+pub(crate) fn worksheet_page(
+    builder: &Builder,
+    worksheet: &Element,
+) -> Result<(Element, HtmlImageElement), JsValue> {
+    let page = element::<Element>(builder, worksheet, "div", "worksheet-page", "")?;
+    let caption = element::<Element>(builder, &page, "h2", "worksheet-caption", "")?;
+    let image = element::<HtmlImageElement>(builder, &page, "img", "worksheet-image", "")?;
+    Ok((caption, image))
+}
+
+/// Set whether document is printing a worksheet, hiding everything else when printed.
+pub(crate) fn set_printing(document: &Document, printing: bool) -> Result<(), JsValue> {
+    let body = document.body().ok_or("should have document body")?;
+    let class = format!("{PREFIX}-printing");
+    match printing {
+        true => body.class_list().add_1(&class),
+        false => body.class_list().remove_1(&class),
+    }
+}
+
+/// Create a `div` for messages to the user.
+pub(crate) fn message(builder: &Builder, parent: &Element, id: &str) -> Result<Element, JsValue> {
+    let message = element::<Element>(builder, parent, "div", "message", id)?;
+    message.set_attribute("aria-live", "polite")?;
+    Ok(message)
+}
@@ -0,0 +1,95 @@
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use web_sys::{Blob, BlobEvent, BlobPropertyBag, HtmlCanvasElement, MediaRecorder};
+use web_sys::{MediaRecorderOptions, Url};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Types of video in order of preference, of which the first supported by the browser is used
+const MIME_TYPES: [&str; 3] = [
+    "video/webm;codecs=vp9",
+    "video/webm;codecs=vp8",
+    "video/webm",
+];
+
+// Frames per second captured from the canvas
+const FRAME_RATE: f64 = 30.0;
+
+/// A type recording the animation of a canvas to a WebM video with `MediaRecorder`.
+pub(crate) struct VideoRecorder {
+    recorder: MediaRecorder,
+    // parts of the video recorded so far
+    chunks: Array,
+    // whether any phase has stepped since the recording started; if so then the
+    // recording stops when the maze is complete
+    pub(crate) stepped: bool,
+    on_data: Closure<dyn FnMut(BlobEvent)>,
+}
+
+impl VideoRecorder {
+    /// Start recording canvas. `Err` if the browser cannot record it.
+    pub(crate) fn start(canvas: &HtmlCanvasElement) -> Result<Self, JsValue> {
+        let mime = MIME_TYPES
+            .into_iter()
+            .find(|mime| MediaRecorder::is_type_supported(mime))
+            .ok_or("the browser cannot record WebM video")?;
+        let options = MediaRecorderOptions::new();
+        options.set_mime_type(mime);
+        let stream = canvas.capture_stream_with_frame_request_rate(FRAME_RATE)?;
+        let recorder =
+            MediaRecorder::new_with_media_stream_and_media_recorder_options(&stream, &options)?;
+
+        let chunks = Array::new();
+        let on_data = {
+            let chunks = chunks.clone();
+            Closure::<dyn FnMut(_)>::new(move |event: BlobEvent| {
+                if let Some(data) = event.data().filter(|data| data.size() > 0.0) {
+                    chunks.push(&data);
+                }
+            })
+        };
+        recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+        recorder.start()?;
+        Ok(Self {
+            recorder,
+            chunks,
+            stepped: false,
+            on_data,
+        })
+    }
+
+    /// Stop recording, calling done with an object URL of the video once it is complete.
+    pub(crate) fn stop(self, done: impl FnOnce(Result<String, JsValue>) + 'static) {
+        let Self {
+            recorder,
+            chunks,
+            on_data,
+            ..
+        } = self;
+        let done = Rc::new(RefCell::new(Some(done)));
+        let on_stop = {
+            let recorder = recorder.clone();
+            let done = done.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                // the data listener is kept until the last data is received, which is before
+                // the recorder stops
+                let _ = &on_data;
+                let options = BlobPropertyBag::new();
+                options.set_type(&recorder.mime_type());
+                let url = Blob::new_with_blob_sequence_and_options(&chunks, &options)
+                    .and_then(|blob| Url::create_object_url_with_blob(&blob));
+                if let Some(done) = done.borrow_mut().take() {
+                    done(url);
+                }
+            })
+        };
+        recorder.set_onstop(Some(on_stop.as_ref().unchecked_ref()));
+        on_stop.forget();
+        if let Err(error) = recorder.stop() {
+            if let Some(done) = done.borrow_mut().take() {
+                done(Err(error));
+            }
+        }
+    }
+}